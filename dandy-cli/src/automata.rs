@@ -5,6 +5,7 @@ use dandy::dfa::Dfa;
 use dandy::nfa::parse::NfaParseError;
 use dandy::nfa::Nfa;
 use dandy::parser;
+use dandy::parser::{ParseError, RegexParseError};
 use dandy::regex::Regex;
 use std::path::Path;
 use std::{fs, io};
@@ -15,15 +16,25 @@ pub enum Error<'a> {
     #[error("Error loading file {0}: {1}")]
     File(&'a Path, io::Error),
     #[error("Error parsing DFA: {0}")]
-    DfaParse(nom::error::Error<&'a str>),
-    #[error("Error compiling DFA: {0}")]
-    DfaCompile(DfaParseError<'a>),
-    #[error("Error parsing DFA: {0}")]
-    NfaParse(nom::error::Error<&'a str>),
-    #[error("Error compiling DFA: {0}")]
-    NfaCompile(NfaParseError<'a>),
+    DfaParse(ParseError<'a>),
+    #[error("Error compiling DFA: {}", join_errors(.0))]
+    DfaCompile(Vec<DfaParseError<'a>>),
+    #[error("Error parsing NFA: {0}")]
+    NfaParse(ParseError<'a>),
+    #[error("Error compiling NFA: {}", join_errors(.0))]
+    NfaCompile(Vec<NfaParseError<'a>>),
     #[error("Error parsing Regex: {0}")]
-    RegexParse(nom::error::Error<&'a str>),
+    RegexParse(RegexParseError<'a>),
+    #[error("Couldn't parse the file as a DFA, NFA or regex, give its type explicitly to see why")]
+    UnknownType,
+}
+
+fn join_errors(errors: &[impl ToString]) -> String {
+    errors
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
@@ -71,21 +82,38 @@ impl Automata {
         file.and_then(|f| Self::load(&f, r#type).map_err(|e| e.to_string()))
     }
 
+    /// Loads an automata or regex, detecting its type from the file extension (`.dfa`, `.nfa` or `.regex`) if no type
+    /// is given, or otherwise by trying to parse it as each type in turn
+    pub fn load_file_detect(path: &Path, r#type: Option<AutomataType>) -> Result<Self, String> {
+        let extension_type = || match path.extension()?.to_str()? {
+            "dfa" => Some(AutomataType::Dfa),
+            "nfa" => Some(AutomataType::Nfa),
+            "regex" => Some(AutomataType::Regex),
+            _ => None,
+        };
+        if let Some(r#type) = r#type.or_else(extension_type) {
+            return Self::load_file(path, r#type);
+        }
+
+        let file = fs::read_to_string(path).map_err(|e| Error::File(path, e).to_string())?;
+        // Every DFA is also a valid NFA, so DFAs are tried first
+        [AutomataType::Dfa, AutomataType::Nfa, AutomataType::Regex]
+            .into_iter()
+            .find_map(|r#type| Self::load(&file, r#type).ok())
+            .ok_or_else(|| Error::UnknownType.to_string())
+    }
+
     /// Loads an automata of any type by parsing it from a string.
-    pub fn load(file: &str, r#type: AutomataType) -> Result<Self, Error> {
+    pub fn load(file: &str, r#type: AutomataType) -> Result<Self, Error<'_>> {
         match r#type {
             AutomataType::Dfa => {
-                let dfa: Dfa = parser::dfa(file)
-                    .map_err(Error::DfaParse)?
-                    .try_into()
-                    .map_err(Error::DfaCompile)?;
+                let parsed = parser::dfa(file).map_err(Error::DfaParse)?;
+                let dfa = Dfa::validate_all(parsed).map_err(Error::DfaCompile)?;
                 Ok(Self::Dfa(dfa))
             }
             AutomataType::Nfa => {
-                let nfa: Nfa = parser::nfa(file)
-                    .map_err(Error::NfaParse)?
-                    .try_into()
-                    .map_err(Error::NfaCompile)?;
+                let parsed = parser::nfa(file).map_err(Error::NfaParse)?;
+                let nfa = Nfa::validate_all(parsed).map_err(Error::NfaCompile)?;
                 Ok(Self::Nfa(nfa))
             }
             AutomataType::Regex => parser::regex(file)
@@ -174,20 +202,24 @@ impl Automata {
         }
     }
 
-    /// Converts this Automata to a DFA or NFA. Note that the provided type cannot be Regex, if that is the case,
-    /// this function returns None. Otherwise it returns the Automata and a bool indicating if a conversion
-    /// occurred.
-    pub fn convert_to(self, r#type: AutomataType) -> Option<(Self, bool)> {
+    /// Converts this Automata to a regex (independent of automata type). Returns the regex and a bool indicating
+    /// whether or not a conversion occurred.
+    pub fn into_regex(self) -> (Regex, bool) {
+        match self {
+            Automata::Dfa(dfa) => (dfa.to_regex(), true),
+            Automata::Nfa(nfa) => (nfa.to_regex(), true),
+            Automata::Regex(regex) => (regex, false),
+        }
+    }
+
+    /// Converts this Automata to the given type. Returns the Automata and a bool indicating if a conversion occurred.
+    pub fn convert_to(self, r#type: AutomataType) -> (Self, bool) {
         match r#type {
-            AutomataType::Dfa => Some(self.into_dfa_automata()),
-            AutomataType::Nfa => Some(self.into_to_nfa_automata()),
+            AutomataType::Dfa => self.into_dfa_automata(),
+            AutomataType::Nfa => self.into_to_nfa_automata(),
             AutomataType::Regex => {
-                if let Self::Regex(regex) = self {
-                    Some((Self::Regex(regex), false))
-                } else {
-                    eprintln!("Cannot convert DFA/NFA to Regex");
-                    None
-                }
+                let (regex, converted) = self.into_regex();
+                (Self::Regex(regex), converted)
             }
         }
     }
@@ -223,43 +255,23 @@ impl Automata {
         }
 
         use AutomataType as T;
-        use EquivalenceResult::*;
         match (self.get_type(), other.get_type()) {
             (T::Dfa, T::Dfa) => {
                 let dfa1 = self.borrow_dfa().unwrap();
                 let dfa2 = other.borrow_dfa().unwrap();
-                match dfa1.separable_from(dfa2) {
-                    None =>
-                        if minimized && dfa1.states().len() != dfa2.states().len() {
-                            NotMinimized
-                        } else {
-                            Equivalent
-                        }
-                    Some(s) => s.map(NotEquivalent).unwrap_or(AlphabetMismatch)
-                }
+                compare_dfas(dfa1, dfa2, minimized)
             }
             (T::Dfa, _) => {
                 warn_minimized!(minimized);
                 let dfa1 = self.borrow_dfa().unwrap();
                 let (dfa2, _) = other.into_dfa();
-                match dfa1.separable_from(&dfa2) {
-                    None =>
-                        if minimized && dfa1.states().len() != dfa2.states().len() {
-                            NotMinimized
-                        } else {
-                            Equivalent
-                        }
-                    Some(s) => s.map(NotEquivalent).unwrap_or(AlphabetMismatch)
-                }
+                compare_dfas(dfa1, &dfa2, minimized)
             }
             (T::Nfa, _) => {
                 warn_minimized_check_type!(minimized, other);
                 let nfa1 = self.borrow_nfa().unwrap();
                 let (nfa2, _) = other.into_nfa();
-                match nfa1.separable_from(&nfa2) {
-                    None => Equivalent,
-                    Some(s) => s.map(NotEquivalent).unwrap_or(AlphabetMismatch)
-                }
+                compare_dfas(&nfa1.to_dfa(), &nfa2.to_dfa(), false)
             }
             (T::Regex, _) => {
                 eprintln!("Testing with Regex as base, this gives poor performance");
@@ -267,15 +279,24 @@ impl Automata {
                 warn_minimized!(minimized);
                 let (dfa1, _) = self.clone().into_dfa();
                 let (dfa2, _) = other.into_dfa();
-                match dfa1.separable_from(&dfa2) {
-                    None => Equivalent,
-                    Some(s) => s.map(NotEquivalent).unwrap_or(AlphabetMismatch)
-                }
+                compare_dfas(&dfa1, &dfa2, false)
             }
         }
     }
 }
 
+/// Compares two DFAs with [Dfa::equivalence_witness], reporting a separating word as its elements separated by spaces.
+/// If `minimized` is set, equivalent DFAs must also have the same number of states.
+fn compare_dfas(dfa1: &Dfa, dfa2: &Dfa, minimized: bool) -> EquivalenceResult {
+    use EquivalenceResult::*;
+    match dfa1.equivalence_witness(dfa2) {
+        Err(_) => AlphabetMismatch,
+        Ok(None) if minimized && dfa1.states().len() != dfa2.states().len() => NotMinimized,
+        Ok(None) => Equivalent,
+        Ok(Some(word)) => NotEquivalent(word.join(" ")),
+    }
+}
+
 impl Automata {
     // TODO: Rewrite this
     pub fn load_test(file: &str, r#type: AutomataType) -> Result<Self, EquivalenceResult> {
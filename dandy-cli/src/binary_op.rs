@@ -63,14 +63,14 @@ pub fn binary_op(
             op.as_str(),
             args.r#type.to_string(true)
         );
-        output!("{}", combined.to_table());
+        output!("{}", main_args.format.dfa(&combined));
     } else {
         log!(
             "{} of {} (not minimized, add --minimized to minimize):",
             op.as_str(),
             args.r#type.to_string(true)
         );
-        output!("{}", combined.to_table());
+        output!("{}", main_args.format.dfa(&combined));
     }
 
     if let Some(n) = args.enumerate {
@@ -0,0 +1,73 @@
+use crate::automata::Automata;
+use crate::format::OutputFormat;
+use crate::{ConvertArgs, ConvertTarget, DandyArgs};
+use dandy::parser;
+use thiserror::Error;
+
+/// Converts an automata or regex to another representation: a DFA or NFA (written in the format given by `--format`),
+/// a regex, or a DOT or Mermaid graph of the automata as it is
+pub fn convert(
+    main_args: &DandyArgs,
+    args: &ConvertArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<(), String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&format!($($t)*)))
+    }
+    macro_rules! output {
+        ($($t:tt)*) => (output(&format!($($t)*)))
+    }
+
+    let automata = Automata::load_file_detect(&args.file, args.from)?;
+    log!(
+        "Converting a {} to {}",
+        automata.get_type().to_string(false),
+        args.to.description()
+    );
+
+    let converted = match args.to {
+        ConvertTarget::Dfa => main_args.format.dfa(&automata.into_dfa().0),
+        ConvertTarget::Nfa => main_args.format.nfa(&automata.into_nfa().0),
+        ConvertTarget::Regex => {
+            let (regex, _) = automata.into_regex();
+            let converted = regex.to_string();
+            // Alphabet elements of several characters are written as they are, and would be read back as a sequence
+            if parser::regex(&converted).ok().as_ref() != Some(&regex) {
+                return Err(Error::NotRepresentable.to_string());
+            }
+            match main_args.format {
+                OutputFormat::Json => serde_json::to_string(&converted).unwrap(),
+                _ => converted,
+            }
+        }
+        ConvertTarget::Dot => match automata {
+            Automata::Dfa(dfa) => dfa.to_dot(),
+            automata => automata.into_nfa().0.to_dot(),
+        },
+        ConvertTarget::Mermaid => match automata {
+            Automata::Dfa(dfa) => dfa.to_mermaid(),
+            automata => automata.into_nfa().0.to_mermaid(),
+        },
+        ConvertTarget::Tikz => match automata {
+            Automata::Dfa(dfa) => dfa.to_tikz(),
+            automata => automata.into_nfa().0.to_tikz(),
+        },
+    };
+    output!("{converted}");
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(
+        "The automata can't be written as a regex, since its alphabet contains elements which aren't single characters"
+    )]
+    NotRepresentable,
+}
@@ -3,6 +3,7 @@ use crate::{automata::Automata, DandyArgs, EquivalenceArgs};
 use dandy::dfa::parse::DfaParseError;
 use dandy::nfa::parse::NfaParseError;
 use dandy::parser;
+use dandy::parser::{ParseError, RegexParseError};
 use std::fmt::Display;
 use std::path::Path;
 use std::time::SystemTime;
@@ -20,6 +21,17 @@ pub enum EquivalenceResult {
     Equivalent,
 }
 
+impl EquivalenceResult {
+    /// Whether the file couldn't be tested at all, as opposed to being tested and found not equivalent
+    pub fn is_failure(&self) -> bool {
+        use EquivalenceResult::*;
+        matches!(
+            self,
+            FailedToRead(_) | FailedToParse(_) | FailedToValidate(_)
+        )
+    }
+}
+
 impl Display for EquivalenceResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use EquivalenceResult::*;
@@ -39,7 +51,7 @@ pub fn equivalence(
     main_args: &DandyArgs,
     args: &EquivalenceArgs,
     #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
-) -> Result<(), String> {
+) -> Result<bool, String> {
     let file = fs::read_to_string(&args.automata).map_err(|e| Error::InputFile(e).to_string())?;
 
     let tester = DandyTester::new(&file, args).map_err(|e| e.to_string())?;
@@ -73,6 +85,10 @@ pub fn equivalence(
         duration.as_millis()
     );
 
+    let failures = results
+        .iter()
+        .filter(|(_, result)| result.is_failure())
+        .count();
     let successes = results.into_iter().fold(0usize, |acc, (path, result)| {
         let res = if args.bool {
             format!("{}", result == EquivalenceResult::Equivalent)
@@ -94,12 +110,16 @@ pub fn equivalence(
 
     log!("{}/{} files passed", successes, args.files.len());
 
-    Ok(())
+    if failures > 0 {
+        return Err(format!("{failures} file(s) could not be tested"));
+    }
+    Ok(successes == args.files.len())
 }
 
 struct DandyTester {
     input: Automata,
     minimized: bool,
+    extend_alphabets: bool,
     test_type: AutomataType,
 }
 
@@ -148,6 +168,7 @@ impl DandyTester {
         Ok(Self {
             input,
             minimized,
+            extend_alphabets: args.extend_alphabets,
             test_type: args.r#type,
         })
     }
@@ -156,25 +177,45 @@ impl DandyTester {
         match fs::read_to_string(file) {
             Err(e) => EquivalenceResult::FailedToRead(e.to_string()),
             Ok(f) => match Automata::load_test(&f, self.test_type) {
+                Ok(automata) if self.extend_alphabets => {
+                    let result = self
+                        .input
+                        .test_equivalence(automata.clone(), self.minimized);
+                    if result == EquivalenceResult::AlphabetMismatch {
+                        self.test_extended_alphabets(automata)
+                    } else {
+                        result
+                    }
+                }
                 Ok(automata) => self.input.test_equivalence(automata, self.minimized),
                 Err(res) => res,
             },
         }
     }
+
+    /// Compares the input to an automata over a different alphabet, by extending both to the union of the alphabets
+    fn test_extended_alphabets(&self, other: Automata) -> EquivalenceResult {
+        let (dfa1, _) = self.input.clone().into_dfa();
+        let (dfa2, _) = other.into_dfa();
+        match dfa1.equivalence_witness_extend(&dfa2) {
+            None => EquivalenceResult::Equivalent,
+            Some(word) => EquivalenceResult::NotEquivalent(word.join(" ")),
+        }
+    }
 }
 
 #[derive(Error, Debug)]
 pub enum Error<'a> {
-    #[error("Error parsing DFA: {0:?}")]
-    DfaParse(nom::error::Error<&'a str>),
+    #[error("Error parsing DFA: {0}")]
+    DfaParse(ParseError<'a>),
     #[error("Error compiling DFA: {0}")]
     Dfa(DfaParseError<'a>),
-    #[error("Error parsing NFA: {0:?}")]
-    NfaParse(nom::error::Error<&'a str>),
+    #[error("Error parsing NFA: {0}")]
+    NfaParse(ParseError<'a>),
     #[error("Error compiling NFA: {0}")]
     Nfa(NfaParseError<'a>),
-    #[error("Error parsing regular expression: {0:?}")]
-    RegexParse(nom::error::Error<&'a str>),
+    #[error("Error parsing regular expression: {0}")]
+    RegexParse(RegexParseError<'a>),
     #[error("--minimized option can only be used when testing DFAs")]
     InvalidMinimizedConfig,
     #[error("Error reading input file: {0}")]
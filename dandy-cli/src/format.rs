@@ -0,0 +1,82 @@
+use clap::ValueEnum;
+use dandy::dfa::Dfa;
+use dandy::grammar::Grammar;
+use dandy::nfa::Nfa;
+use thiserror::Error;
+
+/// The representation used when a subcommand outputs an automata, chosen with the global `--format` flag
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// The table format, which can be parsed again
+    #[default]
+    Table,
+    /// The table format with the columns aligned by graphemes and no trailing whitespace, which can be parsed again
+    Aligned,
+    /// The table format using only ASCII characters (`->` and `eps`), which can be parsed again
+    Ascii,
+    /// A Graphviz DOT graph
+    Dot,
+    /// A Mermaid state diagram
+    Mermaid,
+    /// A TikZ picture for LaTeX, using the `automata` library
+    Tikz,
+    /// JSON, with the alphabet, the states and the index of the initial state
+    Json,
+}
+
+impl OutputFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutputFormat::Table => "table",
+            OutputFormat::Aligned => "aligned",
+            OutputFormat::Ascii => "ascii",
+            OutputFormat::Dot => "dot",
+            OutputFormat::Mermaid => "mermaid",
+            OutputFormat::Tikz => "tikz",
+            OutputFormat::Json => "json",
+        }
+    }
+
+    /// Formats a DFA in this format
+    pub fn dfa(self, dfa: &Dfa) -> String {
+        match self {
+            OutputFormat::Table => dfa.to_table(),
+            OutputFormat::Aligned => dfa.to_table_aligned(),
+            OutputFormat::Ascii => dfa.ascii_table(),
+            OutputFormat::Dot => dfa.to_dot(),
+            OutputFormat::Mermaid => dfa.to_mermaid(),
+            OutputFormat::Tikz => dfa.to_tikz(),
+            OutputFormat::Json => serde_json::to_string_pretty(dfa).unwrap(),
+        }
+    }
+
+    /// Formats a NFA in this format
+    pub fn nfa(self, nfa: &Nfa) -> String {
+        match self {
+            OutputFormat::Table => nfa.to_table(),
+            OutputFormat::Aligned => nfa.to_table_aligned(),
+            OutputFormat::Ascii => nfa.ascii_table(),
+            OutputFormat::Dot => nfa.to_dot(),
+            OutputFormat::Mermaid => nfa.to_mermaid(),
+            OutputFormat::Tikz => nfa.to_tikz(),
+            OutputFormat::Json => serde_json::to_string_pretty(nfa).unwrap(),
+        }
+    }
+
+    /// Formats a grammar in this format, which only works for the table formats since grammars are written as lists of
+    /// productions
+    pub fn grammar(self, grammar: &Grammar) -> Result<String, Error> {
+        match self {
+            OutputFormat::Table | OutputFormat::Aligned | OutputFormat::Ascii => {
+                Ok(grammar.to_string())
+            }
+            format => Err(Error::Unsupported("grammars", format.as_str())),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Can't output {0} in the {1} format")]
+    Unsupported(&'static str, &'static str),
+}
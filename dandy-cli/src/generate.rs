@@ -0,0 +1,69 @@
+use crate::automata::AutomataType;
+use crate::{DandyArgs, GenArgs};
+use dandy::dfa::Dfa;
+use dandy::nfa::Nfa;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use thiserror::Error;
+
+/// Generates a random DFA or NFA, with the alphabet consisting of the first letters of the English alphabet. The seed
+/// is logged if it wasn't given, so that the automata can be generated again.
+pub fn generate(
+    main_args: &DandyArgs,
+    args: &GenArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<(), String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&format!($($t)*)))
+    }
+    macro_rules! output {
+        ($($t:tt)*) => (output(&format!($($t)*)))
+    }
+
+    if args.r#type == AutomataType::Regex {
+        return Err(Error::Regex.to_string());
+    }
+    if !(0.0..=1.0).contains(&args.density) {
+        return Err(Error::Density(args.density).to_string());
+    }
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+    let alphabet = ('a'..='z')
+        .take(args.alphabet_size)
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>();
+    let alphabet = alphabet.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+
+    log!(
+        "Random {} with {} states (seed {seed}):",
+        args.r#type.to_string(false),
+        args.states
+    );
+    match args.r#type {
+        AutomataType::Dfa => {
+            let dfa = Dfa::random(&mut rng, args.states, &alphabet);
+            output!("{}", main_args.format.dfa(&dfa));
+        }
+        AutomataType::Nfa => {
+            let nfa = Nfa::random(&mut rng, args.states, &alphabet, args.density, args.epsilon);
+            output!("{}", main_args.format.nfa(&nfa));
+        }
+        AutomataType::Regex => unreachable!(),
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Only DFAs and NFAs can be generated, not regexes")]
+    Regex,
+    #[error("The density must be between 0 and 1, but was {0}")]
+    Density(f64),
+}
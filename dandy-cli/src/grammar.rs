@@ -2,11 +2,12 @@ use crate::{DandyArgs, ParseGrammarArgs};
 use dandy::grammar::parse::GrammarParseError;
 use dandy::grammar::Grammar;
 use dandy::parser;
+use dandy::parser::ParseError;
 use std::{fs, io};
 use thiserror::Error;
 
-pub fn parse_grammar<'a>(
-    _main_args: &DandyArgs,
+pub fn parse_grammar(
+    main_args: &DandyArgs,
     args: &ParseGrammarArgs,
     #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
 ) -> Result<(), String> {
@@ -14,7 +15,11 @@ pub fn parse_grammar<'a>(
 
     let grammar: Grammar = do_parse_grammar(&file).map_err(|e| e.to_string())?;
 
-    println!("{:?}", grammar);
+    let formatted = main_args
+        .format
+        .grammar(&grammar)
+        .map_err(|e| e.to_string())?;
+    output(formatted.trim_end());
     Ok(())
 }
 
@@ -33,7 +38,7 @@ pub fn do_parse_grammar<'a>(
 #[derive(Error, Debug)]
 pub enum Error<'a> {
     #[error("Error parsing grammar: {0}")]
-    GrammarParse(nom::error::Error<&'a str>),
+    GrammarParse(ParseError<'a>),
     #[error("Error validating grammar: {0}")]
     Grammar(GrammarParseError<'a>),
     #[error("Error reading input file: {0}")]
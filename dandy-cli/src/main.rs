@@ -1,19 +1,27 @@
 mod automata;
 mod binary_op;
+mod convert;
 mod enumerate;
 mod equivalence;
+mod format;
+mod generate;
+mod minimize;
 mod test_files;
+mod test_words;
 mod grammar;
 
 use automata::AutomataType;
+use clap::builder::RangedU64ValueParser;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use dandy::dfa::Dfa;
 use dandy::parser;
+use format::OutputFormat;
 use std::fmt;
 use std::fmt::Formatter;
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use thiserror::Error;
 
 /// A cli tool for parsing and checking DFAs, NFAs and Regexes.
@@ -34,6 +42,14 @@ struct DandyArgs {
         default_value_t
     )]
     no_log: bool,
+    #[arg(
+        long,
+        value_enum,
+        global = true,
+        default_value_t,
+        help = "The representation to use for automatas in the output"
+    )]
+    format: OutputFormat,
     #[command(subcommand)]
     command: Operation,
 }
@@ -41,6 +57,7 @@ struct DandyArgs {
 #[derive(Debug, Subcommand)]
 enum Operation {
     #[command(
+        alias = "equiv",
         about = "Checks the equivalence of two or more automatas or regexes (if they define the same language)"
     )]
     Equivalence(EquivalenceArgs),
@@ -70,6 +87,21 @@ enum Operation {
     TestFile(TestFileArgs),
     #[command(about = "Parses a context-free grammar")]
     ParseGrammar(ParseGrammarArgs),
+    #[command(about = "Minimizes a DFA and outputs it, with the states in a canonical order")]
+    Minimize(MinimizeArgs),
+    #[command(
+        about = "Converts an automata or regex to a DFA, NFA, regex, DOT graph, Mermaid state diagram or TikZ picture"
+    )]
+    Convert(ConvertArgs),
+    #[command(
+        about = "Tests if words (given as arguments or on stdin) are accepted by an automata or regex. \
+        Exits with code 1 if a word doesn't match the expectation given by --expect, and 2 on errors"
+    )]
+    Test(TestArgs),
+    #[command(
+        about = "Generates a random DFA or NFA, which is the same every time for a given seed"
+    )]
+    Gen(GenArgs),
 }
 
 #[derive(Debug, Args)]
@@ -225,6 +257,13 @@ struct EquivalenceArgs {
         help = "Output 'true'/'false' rather than a result in text format"
     )]
     r#bool: bool,
+    #[arg(
+        long,
+        default_value_t,
+        help = "Compare automatas over different alphabets by extending both to the union of the alphabets, \
+        giving a separating word rather than only reporting the mismatch"
+    )]
+    extend_alphabets: bool,
     #[arg(short, long, help = "How many path components to print (0 to disable)")]
     path_length: Option<usize>,
     #[arg(help = "The main automata to compare the other automatas to")]
@@ -283,13 +322,137 @@ enum OpType {
     Test,
 }
 
+#[derive(Debug, Args)]
+struct MinimizeArgs {
+    #[arg(long, help = "Writes the minimized DFA to this file instead")]
+    output: Option<PathBuf>,
+    #[arg(help = "The DFA to minimize")]
+    file: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct ConvertArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        help = "The type of the input (detected from the file extension or contents if not given)"
+    )]
+    from: Option<AutomataType>,
+    #[arg(short, long, value_enum, help = "The representation to convert to")]
+    to: ConvertTarget,
+    #[arg(help = "The automata or regex to convert")]
+    file: PathBuf,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+enum ConvertTarget {
+    Dfa,
+    Nfa,
+    Regex,
+    Dot,
+    Mermaid,
+    Tikz,
+}
+
+impl ConvertTarget {
+    fn description(self) -> &'static str {
+        match self {
+            ConvertTarget::Dfa => "a DFA",
+            ConvertTarget::Nfa => "a NFA",
+            ConvertTarget::Regex => "a regex",
+            ConvertTarget::Dot => "a DOT graph",
+            ConvertTarget::Mermaid => "a Mermaid state diagram",
+            ConvertTarget::Tikz => "a TikZ picture",
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct TestArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        help = "The type of the automata or regex (detected from the file extension or contents if not given)"
+    )]
+    r#type: Option<AutomataType>,
+    #[arg(
+        long,
+        default_value_t,
+        help = "Split the words into the longest matching elements of the alphabet, rather than into characters"
+    )]
+    tokenize: bool,
+    #[arg(
+        long,
+        value_enum,
+        help = "Whether all words are expected to be accepted or rejected"
+    )]
+    expect: Option<Expectation>,
+    #[arg(help = "The automata or regex to test the words against")]
+    automata: PathBuf,
+    #[arg(help = "The words to test (read from stdin, one per line, if none are given)")]
+    words: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+enum Expectation {
+    Accept,
+    Reject,
+}
+
+#[derive(Debug, Args)]
+struct GenArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = AutomataType::Dfa,
+        help = "The type of automata to generate"
+    )]
+    r#type: AutomataType,
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 5,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        help = "The number of states"
+    )]
+    states: usize,
+    #[arg(
+        short,
+        long,
+        default_value_t = 2,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..=26),
+        help = "The number of elements of the alphabet, which are the first letters from `a`"
+    )]
+    alphabet_size: usize,
+    #[arg(
+        long,
+        help = "The seed to generate the automata from (random if not given)"
+    )]
+    seed: Option<u64>,
+    #[arg(
+        long,
+        default_value_t = 0.2,
+        help = "(Only for NFAs): The probability of each possible transition to be included"
+    )]
+    density: f64,
+    #[arg(
+        long,
+        default_value_t,
+        help = "(Only for NFAs): Include ε-moves, with the same probability as other transitions"
+    )]
+    epsilon: bool,
+}
+
 #[derive(Debug, Args)]
 struct ParseGrammarArgs {
     #[arg(help = "The file containing the grammar")]
     grammar: PathBuf,
 }
 
-fn main() {
+fn main() -> ExitCode {
     let args = DandyArgs::parse();
 
     let mut out_file = args
@@ -311,9 +474,21 @@ fn main() {
         };
     };
 
+    let mut exit_code = ExitCode::SUCCESS;
     let result = match &args.command {
         Operation::Equivalence(eq_args) => {
-            equivalence::equivalence(&args, eq_args, &mut sink).map_err(Error::Equivalence)
+            match equivalence::equivalence(&args, eq_args, &mut sink) {
+                Ok(equivalent) => {
+                    if !equivalent {
+                        exit_code = ExitCode::from(1);
+                    }
+                    Ok(())
+                }
+                Err(e) => {
+                    exit_code = ExitCode::from(2);
+                    Err(Error::Equivalence(e))
+                }
+            }
         }
         Operation::Union(bin_args)
         | Operation::Intersection(bin_args)
@@ -335,11 +510,33 @@ fn main() {
         Operation::ParseGrammar(grammar_args) => {
             grammar::parse_grammar(&args, grammar_args, &mut sink).map_err(Error::ParseGrammar)
         }
+        Operation::Minimize(minimize_args) => {
+            minimize::minimize(&args, minimize_args, &mut sink).map_err(Error::Minimize)
+        }
+        Operation::Convert(convert_args) => {
+            convert::convert(&args, convert_args, &mut sink).map_err(Error::Convert)
+        }
+        Operation::Gen(gen_args) => {
+            generate::generate(&args, gen_args, &mut sink).map_err(Error::Gen)
+        }
+        Operation::Test(test_args) => match test_words::test_words(&args, test_args, &mut sink) {
+            Ok(as_expected) => {
+                if !as_expected {
+                    exit_code = ExitCode::from(1);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                exit_code = ExitCode::from(2);
+                Err(Error::Test(e))
+            }
+        },
     };
 
     if let Err(e) = result {
         eprintln!("{e}");
     }
+    exit_code
 }
 
 #[derive(Debug, Error)]
@@ -356,6 +553,14 @@ enum Error {
     EnumerateFile(String),
     #[error("Error in parsing grammar: {0}")]
     ParseGrammar(String),
+    #[error("Error in minimizing: {0}")]
+    Minimize(String),
+    #[error("Error in converting: {0}")]
+    Convert(String),
+    #[error("Error in testing words: {0}")]
+    Test(String),
+    #[error("Error in generating automata: {0}")]
+    Gen(String),
 }
 
 pub fn last_n_components(path: &Path, n: Option<usize>) -> Option<String> {
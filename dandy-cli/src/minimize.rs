@@ -0,0 +1,53 @@
+use crate::automata::{Automata, AutomataType};
+use crate::{DandyArgs, MinimizeArgs};
+use std::fs;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Minimizes a DFA and outputs it with the states in canonical order, or writes it to a file if an output file is
+/// given
+pub fn minimize(
+    main_args: &DandyArgs,
+    args: &MinimizeArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<(), String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&format!($($t)*)))
+    }
+    macro_rules! output {
+        ($($t:tt)*) => (output(&format!($($t)*)))
+    }
+
+    let (mut dfa, _) = Automata::load_file(&args.file, AutomataType::Dfa)?.into_dfa();
+    let states_before = dfa.states().len();
+    dfa.minimize();
+    dfa.canonicalize();
+    log!(
+        "Minimized DFA from {states_before} to {} states:",
+        dfa.states().len()
+    );
+
+    let formatted = main_args.format.dfa(&dfa);
+    match &args.output {
+        Some(path) => {
+            fs::write(path, format!("{formatted}\n"))
+                .map_err(|e| Error::OutputFile(path.clone(), e).to_string())?;
+            log!("Written to {}", path.display());
+        }
+        None => output!("{formatted}"),
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error writing to {}: {1}", .0.display())]
+    OutputFile(PathBuf, std::io::Error),
+}
@@ -0,0 +1,73 @@
+use crate::automata::Automata;
+use crate::{DandyArgs, Expectation, TestArgs};
+use std::io;
+use std::io::BufRead;
+use thiserror::Error;
+
+/// Tests if words given as arguments (or on stdin, one per line) are accepted by an automata or regex. Returns whether
+/// all words matched the expectation (always true without one), so that the exit code can reflect it.
+pub fn test_words(
+    main_args: &DandyArgs,
+    args: &TestArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<bool, String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&format!($($t)*)))
+    }
+    macro_rules! output {
+        ($($t:tt)*) => (output(&format!($($t)*)))
+    }
+
+    let (dfa, _) = Automata::load_file_detect(&args.automata, args.r#type)?.into_dfa();
+    let words = if args.words.is_empty() {
+        io::stdin()
+            .lock()
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::Stdin(e).to_string())?
+    } else {
+        args.words.clone()
+    };
+
+    let mut accepted = 0;
+    let mut unexpected = 0;
+    for word in &words {
+        let (accepts, note) = if args.tokenize {
+            match dfa.accepts_tokenized(word) {
+                Ok(accepts) => (accepts, String::new()),
+                Err(e) => (false, format!(" ({e})")),
+            }
+        } else {
+            (dfa.accepts_graphemes(word), String::new())
+        };
+        if accepts {
+            accepted += 1;
+        }
+        let result = if accepts { "[ACCEPT]" } else { "[REJECT]" };
+        match args.expect {
+            Some(expect) if (expect == Expectation::Accept) != accepts => {
+                unexpected += 1;
+                output!("{result} {word}{note} (unexpected)");
+            }
+            _ => output!("{result} {word}{note}"),
+        }
+    }
+
+    log!("{accepted}/{} words accepted", words.len());
+    if unexpected > 0 {
+        log!("{unexpected} words didn't match the expectation");
+    }
+    Ok(unexpected == 0)
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error reading words from stdin: {0}")]
+    Stdin(io::Error),
+}
@@ -39,6 +39,15 @@ impl Drawer for CanvasDrawer {
         self.context.close_path();
     }
 
+    fn fill_circle(&mut self, pos: Pos2, radius: f32) {
+        self.context.begin_path();
+        self.context
+            .arc(pos.x as f64, pos.y as f64, radius as f64, 0.0, 2.0 * PI)
+            .unwrap();
+        self.context.fill();
+        self.context.close_path();
+    }
+
     fn draw_centered_text(&mut self, pos: Pos2, text: &str) {
         self.context
             .fill_text(text, pos.x as f64, pos.y as f64)
@@ -66,8 +75,8 @@ impl Drawer for CanvasDrawer {
     fn set_color(&mut self, rgb: [u8; 3]) {
         let [r, g, b] = rgb;
         self.context
-            .set_stroke_style(&format!("rgb({r} {g} {b})").into());
+            .set_stroke_style_str(&format!("rgb({r} {g} {b})"));
         self.context
-            .set_fill_style(&format!("rgb({r} {g} {b})").into())
+            .set_fill_style_str(&format!("rgb({r} {g} {b})"))
     }
 }
@@ -28,6 +28,10 @@ impl Drawer for EguiDrawer<'_> {
             .circle_stroke(pos.into(), radius, Stroke::new(thickness, self.color));
     }
 
+    fn fill_circle(&mut self, pos: Pos2, radius: f32) {
+        self.painter.circle_filled(pos.into(), radius, self.color);
+    }
+
     fn draw_centered_text(&mut self, pos: Pos2, text: &str) {
         self.painter.text(
             pos.into(),
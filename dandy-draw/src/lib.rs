@@ -3,13 +3,17 @@ pub mod canvas;
 #[cfg(feature = "egui")]
 pub mod egui;
 pub mod pos2;
+pub mod svg;
+#[cfg(test)]
+mod tests;
 
 use crate::pos2::{pos2, Pos2};
 use dandy::dfa::{Dfa, DfaState};
+use dandy::diagram::group_edges;
 use dandy::nfa::{Nfa, NfaState};
 use paste::paste;
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashSet, VecDeque};
 use std::marker::PhantomData;
 use std::mem;
 
@@ -17,6 +21,9 @@ pub trait Drawer {
     fn start_drawing(&mut self);
     fn finish_drawing(&mut self);
     fn draw_circle(&mut self, pos: Pos2, radius: f32, thickness: f32);
+    /// Fills a circle with the current color. Drawers which don't support filled circles may leave this out, in
+    /// which case nothing is drawn.
+    fn fill_circle(&mut self, _pos: Pos2, _radius: f32) {}
     fn draw_centered_text(&mut self, pos: Pos2, text: &str);
     fn draw_rect(&mut self, upper_left: Pos2, size: Pos2);
     fn draw_line(&mut self, from: Pos2, to: Pos2, thickness: f32);
@@ -46,6 +53,11 @@ impl<'a, T: Drawer> Drawer for OffsetScaleDrawer<'a, T> {
         )
     }
 
+    fn fill_circle(&mut self, pos: Pos2, radius: f32) {
+        self.drawer
+            .fill_circle((pos + self.offset) * self.scale, radius * self.scale.x)
+    }
+
     fn draw_centered_text(&mut self, pos: Pos2, text: &str) {
         self.drawer
             .draw_centered_text((pos + self.offset) * self.scale, text)
@@ -78,12 +90,6 @@ macro_rules! define_draw_options {
         }
 
         impl $name {
-            pub fn new($($field: $ty,)*) -> Self {
-                Self {
-                    $($field,)*
-                }
-            }
-
             paste! {
                 $(
                 pub fn [< with_ $field >](mut self, val: $ty) -> Self {
@@ -96,9 +102,9 @@ macro_rules! define_draw_options {
 
         impl Default for $name {
             fn default() -> Self {
-                Self::new(
-                    $($def,)*
-                )
+                Self {
+                    $($field: $def,)*
+                }
             }
         }
     }
@@ -113,6 +119,7 @@ define_draw_options! {
         center_line_padding: f32 = 20.0,
         circle_radius: f32 = 30.0,
         circle_width: f32 = 2.0,
+        initial_circle_width: f32 = 4.0,
         accepting_circle_radius: f32 = 25.0,
         accepting_circle_width: f32 = 2.0,
         init_arrow_length: f32 = 50.0,
@@ -129,30 +136,104 @@ define_draw_options! {
         middle_arrow: bool = true,
         text_color: [u8; 3] = [255, 255, 255],
         circle_color: [u8; 3] = [150, 255, 255],
+        accepting_fill_color: Option<[u8; 3]> = None,
         line_color: [u8; 3] = [0, 255, 255],
     }
 }
 
+define_draw_options! {
+    AsciiArtOptions {
+        spacing: usize = 1,
+        self_loops: bool = true,
+        labels: bool = true,
+    }
+}
+
+/// Draws the DFA with the given drawer, using the default [DrawOptions]. See [draw_dfa_with_opts].
 pub fn draw_dfa(dfa: &Dfa, drawer: &mut impl Drawer) {
     draw_dfa_with_opts(dfa, drawer, DrawOptions::default())
 }
 
+/// Draws the DFA with the given drawer. The states are laid out in a row in breadth-first order from the initial state
+/// (which is leftmost, marked with an arrow and drawn with a thicker circle), with unreachable states last. Accepting
+/// states are drawn with double circles, and are filled if [DrawOptions] has an accepting fill color. Transitions are
+/// drawn as lines above the states, with self-loops as small loops directly above their state and parallel transitions
+/// merged into one line with all their labels.
 pub fn draw_dfa_with_opts(dfa: &Dfa, drawer: &mut impl Drawer, opts: DrawOptions) {
     let states = dfa.states().iter().map(Into::into).collect::<Vec<State>>();
     let arrows = dfa_to_arrows(dfa);
+    let (states, arrows) = layout(states, arrows, dfa.initial_state_index());
     draw(states, arrows, drawer, opts)
 }
 
+/// Draws the NFA with the given drawer, using the default [DrawOptions]. See [draw_nfa_with_opts].
 pub fn draw_nfa(nfa: &Nfa, drawer: &mut impl Drawer) {
     draw_nfa_with_opts(nfa, drawer, DrawOptions::default())
 }
 
+/// Draws the NFA with the given drawer, laid out like [draw_dfa_with_opts]. ε-transitions are labeled with `ε`.
 pub fn draw_nfa_with_opts(nfa: &Nfa, drawer: &mut impl Drawer, opts: DrawOptions) {
     let states = nfa.states().iter().map(Into::into).collect::<Vec<State>>();
     let arrows = nfa_to_arrows(nfa);
+    let (states, arrows) = layout(states, arrows, nfa.initial_state_index());
     draw(states, arrows, drawer, opts)
 }
 
+/// Reorders the states in breadth-first order from the initial state, so that the initial state is first and
+/// transitions tend to be short, with unreachable states last in their original order
+fn layout<'a>(
+    states: Vec<State<'a>>,
+    arrows: Vec<Arrow<'a>>,
+    initial: usize,
+) -> (Vec<State<'a>>, Vec<Arrow<'a>>) {
+    let mut successors = vec![vec![]; states.len()];
+    for arrow in &arrows {
+        let (from, to) = arrow.endpoints();
+        successors[from].push(to);
+    }
+
+    let mut order = Vec::with_capacity(states.len());
+    let mut visited = vec![false; states.len()];
+    let mut queue = VecDeque::new();
+    if !states.is_empty() {
+        visited[initial] = true;
+        queue.push_back(initial);
+    }
+    while let Some(state) = queue.pop_front() {
+        order.push(state);
+        for &next in &successors[state] {
+            if !visited[next] {
+                visited[next] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    order.extend((0..states.len()).filter(|&idx| !visited[idx]));
+
+    let mut position = vec![0; states.len()];
+    order
+        .iter()
+        .enumerate()
+        .for_each(|(pos, &idx)| position[idx] = pos);
+
+    let mut states = states.into_iter().map(Some).collect::<Vec<_>>();
+    let states = order
+        .iter()
+        .map(|&idx| states[idx].take().unwrap())
+        .collect();
+    let arrows = arrows
+        .into_iter()
+        .map(|arrow| {
+            let (from, to) = arrow.endpoints();
+            Arrow {
+                epsilon: arrow.epsilon,
+                ..Arrow::new(position[from], position[to], arrow.label)
+            }
+        })
+        .collect();
+    (states, arrows)
+}
+
 fn draw<'a>(
     states: Vec<State<'a>>,
     arrows: Vec<Arrow<'a>>,
@@ -186,7 +267,7 @@ fn draw<'a>(
 
     drawer.start_drawing();
     // draw arrow
-    // FIXME: This assumes first is initial, which isn't always the case
+    // The states are laid out with the initial state first
     {
         drawer.set_color(opts.line_color);
         let arrow_base = pos2(opts.init_arrow_length, circle_center);
@@ -206,8 +287,17 @@ fn draw<'a>(
     // draw states
     for (idx, state) in states.iter().enumerate() {
         let cc = pos2(x_pos(idx), circle_center);
+        if let (true, Some(fill_color)) = (state.accepting, opts.accepting_fill_color) {
+            drawer.set_color(fill_color);
+            drawer.fill_circle(cc, opts.circle_radius);
+        }
         drawer.set_color(opts.circle_color);
-        drawer.draw_circle(cc, opts.circle_radius, opts.circle_width);
+        let circle_width = if state.initial {
+            opts.initial_circle_width
+        } else {
+            opts.circle_width
+        };
+        drawer.draw_circle(cc, opts.circle_radius, circle_width);
         if state.accepting {
             drawer.draw_circle(
                 cc,
@@ -215,25 +305,6 @@ fn draw<'a>(
                 opts.accepting_circle_width,
             );
         }
-        if state.initial {
-            drawer.set_color(opts.line_color);
-            let start = cc + Pos2::y(opts.circle_radius + opts.center_line_padding);
-            drawer.draw_line(
-                start,
-                start + Pos2::y(opts.init_arrow_length),
-                opts.init_arrow_width,
-            );
-            drawer.draw_line(
-                start,
-                start + pos2(opts.init_arrow_arms_length, opts.init_arrow_arms_length),
-                opts.init_arrow_width,
-            );
-            drawer.draw_line(
-                start,
-                start + pos2(-opts.init_arrow_arms_length, opts.init_arrow_arms_length),
-                opts.init_arrow_width,
-            );
-        }
 
         drawer.set_color(opts.text_color);
         drawer.draw_centered_text(cc, state.name);
@@ -313,32 +384,72 @@ fn draw<'a>(
     drawer.finish_drawing();
 }
 
+/// Draws the DFA as ASCII art, using the default [AsciiArtOptions]. See [dfa_ascii_art_with_opts].
 pub fn dfa_ascii_art(dfa: &Dfa) -> String {
+    dfa_ascii_art_with_opts(dfa, AsciiArtOptions::default())
+}
+
+/// Draws the DFA as ASCII art, with the states laid out in a row like [draw_dfa_with_opts] and the transitions drawn
+/// as arrows above them. The options control the number of spaces between the states, and whether self-loops and
+/// labels are drawn, which can be used to make the art fit in a terminal.
+pub fn dfa_ascii_art_with_opts(dfa: &Dfa, opts: AsciiArtOptions) -> String {
     let states = dfa.states().iter().map(Into::into).collect::<Vec<State>>();
     let arrows = dfa_to_arrows(dfa);
-    ascii_art(states, arrows)
+    let (states, arrows) = layout(states, arrows, dfa.initial_state_index());
+    ascii_art(states, arrows, opts)
 }
 
+/// Draws the NFA as ASCII art, using the default [AsciiArtOptions]. See [nfa_ascii_art_with_opts].
 pub fn nfa_ascii_art(nfa: &Nfa) -> String {
+    nfa_ascii_art_with_opts(nfa, AsciiArtOptions::default())
+}
+
+/// Draws the NFA as ASCII art, laid out like [dfa_ascii_art_with_opts]. ε-transitions are labeled with `ε` and drawn
+/// with `=` instead of `-`, so that they stand out from the transitions upon elements of the alphabet.
+pub fn nfa_ascii_art_with_opts(nfa: &Nfa, opts: AsciiArtOptions) -> String {
     let states = nfa.states().iter().map(Into::into).collect::<Vec<State>>();
     let arrows = nfa_to_arrows(nfa);
-    ascii_art(states, arrows)
+    let (states, arrows) = layout(states, arrows, nfa.initial_state_index());
+    ascii_art(states, arrows, opts)
 }
 
-fn ascii_art<'a>(states: Vec<State<'a>>, arrows: Vec<Arrow<'a>>) -> String {
-    let widest_state_name = states.iter().map(|s| s.name.chars().count()).max().unwrap();
+fn ascii_art<'a>(states: Vec<State<'a>>, arrows: Vec<Arrow<'a>>, opts: AsciiArtOptions) -> String {
+    // Nothing to draw, and there is no widest state name to lay out the art with
+    if states.is_empty() {
+        return String::new();
+    }
 
     // optional grouping
-    let arrows = group_arrows(arrows);
+    let arrows = group_arrows(arrows)
+        .into_iter()
+        .filter(|arrow| opts.self_loops || arrow.direction != Direction::Spot)
+        .collect::<Vec<_>>();
+
+    // Self-loops are drawn as a loop directly above the state with the label inside it, so states are made wide enough
+    // to fit the labels of their self-loops
+    let widest_state_name = states
+        .iter()
+        .map(|s| s.name.chars().count())
+        .chain(
+            arrows
+                .iter()
+                .filter(|arrow| opts.labels && arrow.direction == Direction::Spot)
+                .map(|arrow| arrow.label().chars().count()),
+        )
+        .max()
+        .unwrap();
+
     let (arrows, levels) = place_arrows(arrows);
 
+    // The width of a state and the spacing after it
+    let state_width = 6 + widest_state_name + opts.spacing;
     let left_x_idx = |idx: usize| -> usize {
         //-> ((a))
-        5 + (7 + widest_state_name) * idx
+        5 + state_width * idx
     };
     let right_x_idx = |idx: usize| -> usize {
         //-> ((a))
-        6 + widest_state_name + (7 + widest_state_name) * idx
+        6 + widest_state_name + state_width * idx
     };
     let art_width = right_x_idx(states.len()) - 1;
 
@@ -357,23 +468,26 @@ fn ascii_art<'a>(states: Vec<State<'a>>, arrows: Vec<Arrow<'a>>) -> String {
         acc.push_str("-> ");
         states.iter().for_each(|state| {
             if state.accepting {
-                acc.push_str(&format!("(( {} )) ", pad(state.name, widest_state_name)))
+                acc.push_str(&format!("(( {} ))", pad(state.name, widest_state_name)))
             } else {
-                acc.push_str(&format!("(  {}  ) ", pad(state.name, widest_state_name)))
+                acc.push_str(&format!("(  {}  )", pad(state.name, widest_state_name)))
             }
+            acc.push_str(&" ".repeat(opts.spacing));
         });
         acc
     };
 
-    let mut lines = Vec::with_capacity(levels * 2 + 1);
+    // The lines are built as vectors of chars rather than strings, since state names and labels can contain
+    // multi-byte characters and all positions are counted in chars
+    let mut lines: Vec<Vec<char>> = Vec::with_capacity(levels * 2 + 1);
     let mut bars = HashSet::new();
     for level in (0..levels).rev() {
-        let mut top_line = " ".repeat(art_width);
-        let mut bot_line = " ".repeat(art_width);
+        let mut top_line = vec![' '; art_width];
+        let mut bot_line = vec![' '; art_width];
 
         bars.iter().for_each(|&bar| {
-            unsafe { top_line.as_bytes_mut()[bar] = b'|' }
-            unsafe { bot_line.as_bytes_mut()[bar] = b'|' }
+            top_line[bar] = '|';
+            bot_line[bar] = '|';
         });
 
         arrows
@@ -386,26 +500,18 @@ fn ascii_art<'a>(states: Vec<State<'a>>, arrows: Vec<Arrow<'a>>) -> String {
                     (right_x_idx(arrow.arrow.left), left_x_idx(arrow.arrow.right))
                 };
 
-                // SAFETY: valid utf8 since only ascii is used and string is initially only ascii
-                for x in leftmost..=rightmost {
-                    unsafe { top_line.as_bytes_mut()[x] = b'-' }
-                }
+                let line = if arrow.arrow.epsilon { '=' } else { '-' };
+                top_line[leftmost..=rightmost].fill(line);
                 match arrow.arrow.direction {
-                    Direction::Left => unsafe {
-                        top_line.as_bytes_mut()[left_x_idx(arrow.arrow.right) - 1] = b'<'
-                    },
-                    Direction::Right => unsafe {
-                        top_line.as_bytes_mut()[right_x_idx(arrow.arrow.left) + 2] = b'>'
-                    },
-                    Direction::Spot => unsafe {
-                        top_line.as_bytes_mut()[left_x_idx(arrow.arrow.left) + 1] = b'>'
-                    },
+                    Direction::Left => top_line[left_x_idx(arrow.arrow.right) - 1] = '<',
+                    Direction::Right => top_line[right_x_idx(arrow.arrow.left) + 2] = '>',
+                    Direction::Spot => top_line[left_x_idx(arrow.arrow.left) + 1] = '>',
                 }
 
                 bars.insert(right_x_idx(arrow.arrow.left));
                 bars.insert(left_x_idx(arrow.arrow.right));
-                unsafe { bot_line.as_bytes_mut()[right_x_idx(arrow.arrow.left)] = b'|' }
-                unsafe { bot_line.as_bytes_mut()[left_x_idx(arrow.arrow.right)] = b'|' }
+                bot_line[right_x_idx(arrow.arrow.left)] = '|';
+                bot_line[left_x_idx(arrow.arrow.right)] = '|';
             });
         // We do this in a second for loop to
         // * make sure all shapes have been drawn out
@@ -413,41 +519,31 @@ fn ascii_art<'a>(states: Vec<State<'a>>, arrows: Vec<Arrow<'a>>) -> String {
         // * to be able to disable label drawing
         arrows
             .iter()
-            .filter(|arrow| arrow.level == level)
+            .filter(|arrow| opts.labels && arrow.level == level)
             .for_each(|arrow| {
                 // copy label
-                if arrow.arrow.left != arrow.arrow.right {
-                    // Note that label can be non-ascii (as for ε), so make sure we replace correct amnt of spaces
-                    let label = arrow.arrow.label();
-                    let range = {
-                        // We need to replace the nth *char* which is not necessarily same as the nth byte (we could
-                        // have inserted labels to the left)
-                        let start = right_x_idx(arrow.arrow.left);
-                        let start = bot_line.char_indices().nth(start).unwrap().0;
-                        // We need to replace equally many spaces as the label has (visible) chars
-                        start + 1..start + label.chars().count() + 1
-                    };
-                    bot_line.replace_range(range, &label);
+                let start = if arrow.arrow.direction == Direction::Spot {
+                    left_x_idx(arrow.arrow.left) + 1
                 } else {
-                    // Space is tight, so don't print this
-                    // let label = arrow.arrow.label();
-                    // let range = {
-                    //     let start = left_x_idx(arrow.arrow.left);
-                    //     start + 1..start + label.len() + 1
-                    // };
-                    // // SAFETY: beforehand only ascii, and label is valid string
-                    // unsafe { bot_line.as_bytes_mut()[range].copy_from_slice(label.as_bytes()) }
-                }
+                    right_x_idx(arrow.arrow.left) + 1
+                };
+                // Labels that don't fit are cut off at the end of the line
+                bot_line
+                    .iter_mut()
+                    .skip(start)
+                    .zip(arrow.arrow.label().chars())
+                    .for_each(|(c, l)| *c = l);
             });
         lines.push(top_line);
         lines.push(bot_line);
     }
 
+    let mut lines: Vec<String> = lines.into_iter().map(String::from_iter).collect();
     lines.push(last_line);
     lines.join("\n")
 }
 
-fn dfa_to_arrows(dfa: &Dfa) -> Vec<Arrow> {
+fn dfa_to_arrows(dfa: &Dfa) -> Vec<Arrow<'_>> {
     dfa.states()
         .iter()
         .enumerate()
@@ -461,7 +557,7 @@ fn dfa_to_arrows(dfa: &Dfa) -> Vec<Arrow> {
         .collect()
 }
 
-fn nfa_to_arrows(nfa: &Nfa) -> Vec<Arrow> {
+fn nfa_to_arrows(nfa: &Nfa) -> Vec<Arrow<'_>> {
     nfa.states()
         .iter()
         .enumerate()
@@ -478,7 +574,7 @@ fn nfa_to_arrows(nfa: &Nfa) -> Vec<Arrow> {
                     state
                         .epsilon_transitions()
                         .iter()
-                        .map(move |to| Arrow::new(from, *to, "ε")),
+                        .map(move |to| Arrow::epsilon(from, *to)),
                 )
         })
         .collect()
@@ -518,23 +614,32 @@ fn place_arrows<'a, T: ArrowLike<'a>>(arrows: Vec<T>) -> (Vec<PlacedArrow<'a, T>
     (placed, current_depth)
 }
 
+/// Merges parallel arrows with [group_edges], in the same way as the exporters of dandy merge parallel transitions.
+/// ε-transitions are drawn differently, so they are grouped separately.
 fn group_arrows(arrows: Vec<Arrow>) -> Vec<GroupedArrow> {
-    arrows
+    let (epsilon, symbols): (Vec<_>, Vec<_>) = arrows.into_iter().partition(|arrow| arrow.epsilon);
+    let mut grouped = [(false, symbols), (true, epsilon)]
         .into_iter()
-        .fold(HashMap::<_, Vec<Arrow>>::new(), |mut map, arrow| {
-            map.entry((arrow.left, arrow.right, arrow.direction))
-                .or_default()
-                .push(arrow);
-            map
-        })
-        .drain()
-        .map(|((left, right, direction), arrows)| GroupedArrow {
-            left,
-            right,
-            direction,
-            labels: arrows.into_iter().map(|arrow| arrow.label).collect(),
+        .flat_map(|(epsilon, arrows)| {
+            group_edges(arrows.iter().map(|arrow| {
+                let (from, to) = arrow.endpoints();
+                (from, to, arrow.label)
+            }))
+            .into_iter()
+            .map(move |edge| {
+                let arrow = Arrow::new(edge.from, edge.to, "");
+                GroupedArrow {
+                    left: arrow.left,
+                    right: arrow.right,
+                    direction: arrow.direction,
+                    epsilon,
+                    labels: edge.labels,
+                }
+            })
         })
-        .collect()
+        .collect::<Vec<_>>();
+    grouped.sort_by_key(|arrow| (arrow.left, arrow.right, arrow.direction, arrow.epsilon));
+    grouped
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -549,6 +654,8 @@ struct GroupedArrow<'a> {
     left: usize,
     right: usize,
     direction: Direction,
+    /// Whether the arrows are ε-transitions, which are never grouped with other transitions
+    epsilon: bool,
     labels: Vec<&'a str>,
 }
 
@@ -558,9 +665,18 @@ struct Arrow<'a> {
     right: usize,
     direction: Direction,
     label: &'a str,
+    epsilon: bool,
 }
 
 impl<'a> Arrow<'a> {
+    fn endpoints(&self) -> (usize, usize) {
+        if self.direction == Direction::Left {
+            (self.right, self.left)
+        } else {
+            (self.left, self.right)
+        }
+    }
+
     fn new(from: usize, to: usize, label: &'a str) -> Self {
         use std::cmp::Ordering::*;
         use Direction::*;
@@ -570,24 +686,35 @@ impl<'a> Arrow<'a> {
                 right: to,
                 direction: Right,
                 label,
+                epsilon: false,
             },
             Equal => Arrow {
                 left: from,
                 right: to,
                 direction: Spot,
                 label,
+                epsilon: false,
             },
             Greater => Arrow {
                 left: to,
                 right: from,
                 direction: Left,
                 label,
+                epsilon: false,
             },
         }
     }
+
+    /// Creates an arrow for an ε-transition, labeled with `ε`
+    fn epsilon(from: usize, to: usize) -> Self {
+        Arrow {
+            epsilon: true,
+            ..Arrow::new(from, to, "ε")
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash)]
 enum Direction {
     Left,
     Right,
@@ -597,7 +724,6 @@ enum Direction {
 trait ArrowLike<'a> {
     fn left(&self) -> usize;
     fn right(&self) -> usize;
-    fn direction(&self) -> Direction;
     fn label(&self) -> Cow<'a, str>;
 }
 
@@ -610,10 +736,6 @@ impl<'a> ArrowLike<'a> for Arrow<'a> {
         self.right
     }
 
-    fn direction(&self) -> Direction {
-        self.direction
-    }
-
     fn label(&self) -> Cow<'a, str> {
         Cow::Borrowed(self.label)
     }
@@ -628,10 +750,6 @@ impl<'a> ArrowLike<'a> for GroupedArrow<'a> {
         self.right
     }
 
-    fn direction(&self) -> Direction {
-        self.direction
-    }
-
     fn label(&self) -> Cow<'static, str> {
         Cow::Owned(self.labels.join(", "))
     }
@@ -640,7 +758,6 @@ impl<'a> ArrowLike<'a> for GroupedArrow<'a> {
 struct State<'a> {
     name: &'a str,
     accepting: bool,
-    #[allow(dead_code)]
     initial: bool,
 }
 
@@ -0,0 +1,190 @@
+use crate::pos2::Pos2;
+use crate::Drawer;
+use std::fmt;
+use std::fmt::{Display, Formatter, Write};
+
+static DEFAULT_COLOR: [u8; 3] = [0, 0, 0];
+const FONT_SIZE: f32 = 14.0;
+const MARGIN: f32 = 10.0;
+
+/// A [Drawer] which builds an SVG image. Draw to it with for example [crate::draw_dfa], and then get the image with
+/// [ToString::to_string] (or [SvgDrawer::finish]). The size of the image is the extent of what has been drawn, plus a
+/// small margin.
+///
+/// Note that the default colors of [crate::DrawOptions] are meant for dark backgrounds, so either change the colors or
+/// use [SvgDrawer::with_background].
+///
+/// ```
+/// use dandy::dfa::Dfa;
+/// use dandy_draw::svg::SvgDrawer;
+///
+/// let dfa: Dfa = dandy::parser::dfa("
+///        a  b
+/// -> s₀  s₁ s₀
+///  * s₁  s₁ s₀
+/// ").unwrap().try_into().unwrap();
+/// let mut drawer = SvgDrawer::new().with_background([0, 0, 0]);
+/// dandy_draw::draw_dfa(&dfa, &mut drawer);
+/// let svg = drawer.finish();
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains(">s₀</text>"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SvgDrawer {
+    elements: String,
+    color: [u8; 3],
+    background: Option<[u8; 3]>,
+    max: Pos2,
+}
+
+impl SvgDrawer {
+    pub fn new() -> Self {
+        Self {
+            elements: String::new(),
+            color: DEFAULT_COLOR,
+            background: None,
+            max: Pos2::x(0.0),
+        }
+    }
+
+    /// Fills the background of the image with the given color
+    pub fn with_background(mut self, rgb: [u8; 3]) -> Self {
+        self.background = Some(rgb);
+        self
+    }
+
+    /// Returns the finished SVG image
+    pub fn finish(&self) -> String {
+        self.to_string()
+    }
+
+    fn extend(&mut self, pos: Pos2) {
+        self.max.x = self.max.x.max(pos.x);
+        self.max.y = self.max.y.max(pos.y);
+    }
+
+    fn color(&self) -> String {
+        let [r, g, b] = self.color;
+        format!("rgb({r},{g},{b})")
+    }
+}
+
+impl Default for SvgDrawer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drawer for SvgDrawer {
+    fn start_drawing(&mut self) {}
+
+    fn finish_drawing(&mut self) {}
+
+    fn draw_circle(&mut self, pos: Pos2, radius: f32, thickness: f32) {
+        self.extend(
+            pos + Pos2 {
+                x: radius,
+                y: radius,
+            },
+        );
+        let color = self.color();
+        let _ = writeln!(
+            self.elements,
+            r#"<circle cx="{}" cy="{}" r="{radius}" fill="none" stroke="{color}" stroke-width="{thickness}"/>"#,
+            pos.x, pos.y
+        );
+    }
+
+    fn fill_circle(&mut self, pos: Pos2, radius: f32) {
+        self.extend(
+            pos + Pos2 {
+                x: radius,
+                y: radius,
+            },
+        );
+        let color = self.color();
+        let _ = writeln!(
+            self.elements,
+            r#"<circle cx="{}" cy="{}" r="{radius}" fill="{color}"/>"#,
+            pos.x, pos.y
+        );
+    }
+
+    fn draw_centered_text(&mut self, pos: Pos2, text: &str) {
+        // The width of the text is unknown, so estimate it
+        let half_width = text.chars().count() as f32 * FONT_SIZE * 0.3;
+        self.extend(
+            pos + Pos2 {
+                x: half_width,
+                y: FONT_SIZE / 2.0,
+            },
+        );
+        let color = self.color();
+        let _ = writeln!(
+            self.elements,
+            r#"<text x="{}" y="{}" fill="{color}" font-size="{FONT_SIZE}" font-family="sans-serif" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+            pos.x,
+            pos.y,
+            escape(text)
+        );
+    }
+
+    fn draw_rect(&mut self, upper_left: Pos2, size: Pos2) {
+        self.extend(upper_left + size);
+        let color = self.color();
+        let _ = writeln!(
+            self.elements,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{color}"/>"#,
+            upper_left.x, upper_left.y, size.x, size.y
+        );
+    }
+
+    fn draw_line(&mut self, from: Pos2, to: Pos2, thickness: f32) {
+        self.extend(from);
+        self.extend(to);
+        let color = self.color();
+        let _ = writeln!(
+            self.elements,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{color}" stroke-width="{thickness}" stroke-linecap="round"/>"#,
+            from.x, from.y, to.x, to.y
+        );
+    }
+
+    fn set_color(&mut self, rgb: [u8; 3]) {
+        self.color = rgb;
+    }
+}
+
+impl Display for SvgDrawer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let width = self.max.x + MARGIN;
+        let height = self.max.y + MARGIN;
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        )?;
+        if let Some([r, g, b]) = self.background {
+            writeln!(
+                f,
+                r#"<rect x="0" y="0" width="{width}" height="{height}" fill="rgb({r},{g},{b})"/>"#
+            )?;
+        }
+        f.write_str(&self.elements)?;
+        writeln!(f, "</svg>")
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut acc, c| {
+            match c {
+                '&' => acc.push_str("&amp;"),
+                '<' => acc.push_str("&lt;"),
+                '>' => acc.push_str("&gt;"),
+                '"' => acc.push_str("&quot;"),
+                '\'' => acc.push_str("&apos;"),
+                c => acc.push(c),
+            }
+            acc
+        })
+}
@@ -0,0 +1,232 @@
+use crate::*;
+use dandy::dfa::ConstructionError;
+use dandy::parser;
+
+#[test]
+fn test_ascii_art_multibyte_names() {
+    let input = "
+           α  β
+    -> s₀  s₁ s₀
+     * s₁  s₂ s₀
+       s₂  s₂ s₁
+    ";
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    let art = dfa_ascii_art(&dfa);
+    let lines = art.lines().collect::<Vec<_>>();
+    assert_eq!(*lines.last().unwrap(), "-> (  s₀  ) (( s₁ )) (  s₂  ) ");
+    let width = lines[0].chars().count();
+    assert!(lines[..lines.len() - 1]
+        .iter()
+        .all(|line| line.chars().count() == width));
+    assert!(lines.iter().any(|line| line.contains("|α")));
+    assert!(lines.iter().any(|line| line.contains("|β")));
+}
+
+#[test]
+fn test_ascii_art_self_loops() {
+    let input = "
+           a  b
+    -> s0  s1 s0
+     * s1  s1 s1
+    ";
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    let art = dfa_ascii_art(&dfa);
+    let lines = art.lines().collect::<Vec<_>>();
+    assert!(lines.iter().any(|line| line.contains("|b   |")));
+    assert!(lines.iter().any(|line| line.contains("|a, b|")));
+}
+
+#[test]
+fn test_ascii_art_deterministic() {
+    let input = "
+           a  b  c
+    -> s0  s1 s2 s3
+     * s1  s0 s3 s1
+       s2  s3 s0 s2
+     * s3  s0 s1 s2
+    ";
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    let art = dfa_ascii_art(&dfa);
+    for _ in 0..20 {
+        assert_eq!(dfa_ascii_art(&dfa), art);
+    }
+}
+
+#[test]
+fn test_ascii_art_epsilon_transitions() {
+    let input = "
+           ε    a
+    -> s0  {s1} {s1}
+     * s1  {}   {}
+    ";
+    let nfa: Nfa = parser::nfa(input).unwrap().try_into().unwrap();
+    let art = nfa_ascii_art(&nfa);
+    let lines = art.lines().collect::<Vec<_>>();
+    // The ε-transition and the a-transition between the same states are drawn as separate arrows
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0].trim(), "-->----");
+    assert_eq!(lines[1].trim(), "|a    |");
+    assert_eq!(lines[2].trim(), "==>====");
+    assert_eq!(lines[3].trim(), "|ε    |");
+}
+
+#[test]
+fn test_ascii_art_options() {
+    let input = "
+           a  b
+    -> s0  s1 s0
+     * s1  s1 s1
+    ";
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    let expected = [
+        "          -->----              ",
+        "          |a    |              ",
+        "     ->----     ->----         ",
+        "     |b   |     |a, b|         ",
+        "-> (  s0    ) (( s1   )) ",
+    ]
+    .join("\n");
+    assert_eq!(dfa_ascii_art(&dfa), expected);
+    assert_eq!(
+        dfa_ascii_art_with_opts(&dfa, AsciiArtOptions::default()),
+        expected
+    );
+
+    let art = dfa_ascii_art_with_opts(&dfa, AsciiArtOptions::default().with_spacing(4));
+    assert_eq!(
+        art.lines().last().unwrap(),
+        "-> (  s0    )    (( s1   ))    "
+    );
+
+    let art = dfa_ascii_art_with_opts(&dfa, AsciiArtOptions::default().with_self_loops(false));
+    let lines = art.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[1].trim(), "|a    |");
+    assert_eq!(lines[2], "-> (  s0  ) (( s1 )) ");
+
+    let art = dfa_ascii_art_with_opts(
+        &dfa,
+        AsciiArtOptions::default()
+            .with_spacing(0)
+            .with_self_loops(false)
+            .with_labels(false),
+    );
+    let lines = art.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0].trim_end(), "        -->---");
+    assert_eq!(lines[1].trim_end(), "        |    |");
+    assert_eq!(lines[2], "-> (  s0  )(( s1 ))");
+}
+
+#[test]
+fn test_exporters_group_edges_consistently() {
+    let input = "
+           c  b  a
+    -> s0  s1 s1 s1
+     * s1  s0 s1 s0
+    ";
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    assert!(dfa_ascii_art(&dfa).contains("|a, b, c"));
+    assert!(dfa.to_dot().contains("q0 -> q1 [label=\"a, b, c\"]"));
+    assert!(dfa.to_mermaid().contains("s0 --> s1 : a, b, c"));
+    assert!(dfa.to_tikz().contains("node {a, b, c} (q1)"));
+    for labels in ["a, c", "b"] {
+        assert!(dfa_ascii_art(&dfa).contains(labels));
+        assert!(dfa.to_dot().contains(&format!("[label=\"{labels}\"]")));
+        assert!(dfa.to_mermaid().contains(&format!(" : {labels}\n")));
+        assert!(dfa.to_tikz().contains(&format!("node {{{labels}}}")));
+    }
+}
+
+#[test]
+fn test_ascii_art_no_states() {
+    // The public constructors reject automata without states, so nfa_ascii_art can't be given one
+    let no_states = Nfa::from_parts(vec!["a".into()], vec![], 0);
+    assert_eq!(
+        no_states,
+        Err(ConstructionError::InitialStateDoesNotExist(0))
+    );
+    // The renderer itself still handles the case instead of panicking
+    assert_eq!(ascii_art(vec![], vec![], AsciiArtOptions::default()), "");
+
+    let one_state = Nfa::from_parts(
+        vec!["a".into()],
+        vec![NfaState::new("s", true, false, vec![], vec![vec![]])],
+        0,
+    )
+    .unwrap();
+    assert_eq!(nfa_ascii_art(&one_state), "-> (  s  ) ");
+}
+
+#[test]
+fn test_svg_drawer() {
+    let input = "
+             a     b
+    -> <s0>  s&1   <s0>
+     * s&1   s&1   <s0>
+    ";
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    let mut drawer = svg::SvgDrawer::new();
+    draw_dfa(&dfa, &mut drawer);
+    let svg = drawer.to_string();
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains(">&lt;s0&gt;</text>"));
+    assert!(svg.contains(">s&amp;1</text>"));
+    // Two circles for the accepting state, one for the other state
+    assert_eq!(svg.matches("<circle").count(), 3);
+    // The stub arrow into the initial state, which is drawn leftmost
+    assert!(svg.contains(r#"<line x1="0" "#));
+}
+
+#[test]
+fn test_svg_state_styles() {
+    let input = "
+           a
+    -> s0  s1
+     * s1  s1
+    ";
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    let mut drawer = svg::SvgDrawer::new();
+    draw_dfa(&dfa, &mut drawer);
+    let svg = drawer.to_string();
+    // No fill by default, and the initial state has a thicker circle than the accepting state
+    assert!(!svg.contains(r#"<circle cx="100" cy="115" r="30" fill="rgb"#));
+    assert!(svg.contains(r#"<circle cx="100" cy="115" r="30" fill="none" stroke="rgb(150,255,255)" stroke-width="4"/>"#));
+    assert!(svg.contains(r#"<circle cx="180" cy="115" r="30" fill="none" stroke="rgb(150,255,255)" stroke-width="2"/>"#));
+    assert!(svg.contains(r#"<circle cx="180" cy="115" r="25" fill="none""#));
+
+    let mut drawer = svg::SvgDrawer::new();
+    let opts = DrawOptions::default().with_accepting_fill_color(Some([255, 220, 150]));
+    draw_dfa_with_opts(&dfa, &mut drawer, opts);
+    let svg = drawer.to_string();
+    assert_eq!(svg.matches(r#"fill="rgb(255,220,150)""#).count(), 1);
+    assert!(svg.contains(r#"<circle cx="180" cy="115" r="30" fill="rgb(255,220,150)"/>"#));
+    // The fill is drawn below the outlines and the name
+    let fill = svg.find(r#"fill="rgb(255,220,150)""#).unwrap();
+    assert!(fill < svg.find(">s1</text>").unwrap());
+    assert!(fill < svg.find(r#"<circle cx="180" cy="115" r="25""#).unwrap());
+}
+
+#[test]
+fn test_layout_initial_first() {
+    let input = "
+            a  b
+       s0   s1 s2
+     * s1   s2 s0
+    -> s2   s1 s2
+    ";
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    let art = dfa_ascii_art(&dfa);
+    assert_eq!(
+        art.lines().last().unwrap(),
+        "-> (  s2  ) (( s1 )) (  s0  ) "
+    );
+
+    let mut drawer = svg::SvgDrawer::new();
+    draw_dfa(&dfa, &mut drawer);
+    let svg = drawer.to_string();
+    let s2 = svg.find(">s2</text>").unwrap();
+    let s1 = svg.find(">s1</text>").unwrap();
+    let s0 = svg.find(">s0</text>").unwrap();
+    assert!(s2 < s1 && s1 < s0);
+}
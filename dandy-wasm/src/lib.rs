@@ -182,7 +182,7 @@ pub fn delete_dfa(dfa: usize) -> bool {
 #[wasm_bindgen]
 pub fn load_dfa(input: &str) -> Result<usize, String> {
     let dfa: Dfa = dandy::parser::dfa(input)
-        .map_err(|e| format!("Error parsing DFA: {e:?}"))?
+        .map_err(|e| format!("Error parsing DFA: {e}"))?
         .try_into()
         .map_err(|e: DfaParseError| e.to_string())?;
     Ok(push_dfa(dfa))
@@ -204,7 +204,7 @@ pub fn delete_nfa(nfa: usize) -> bool {
 #[wasm_bindgen]
 pub fn load_nfa(input: &str) -> Result<usize, String> {
     let nfa: Nfa = dandy::parser::nfa(input)
-        .map_err(|e| format!("Error parsing NFA: {e:?}"))?
+        .map_err(|e| format!("Error parsing NFA: {e}"))?
         .try_into()
         .map_err(|e: NfaParseError| e.to_string())?;
     Ok(push_nfa(nfa))
@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use dandy::dfa::Dfa;
+use dandy::nfa::NfaScratch;
 use dandy::parser;
 use lazy_static::lazy_static;
 use proptest::prelude::*;
@@ -8,6 +9,7 @@ use proptest::test_runner::TestRunner;
 use regex::Regex as LibRegex;
 use std::fs;
 use std::path::Path;
+use unicode_segmentation::UnicodeSegmentation;
 
 lazy_static! {
     static ref DFAS: Box<[String]> = {
@@ -59,8 +61,7 @@ pub fn regex_compile(c: &mut Criterion) {
             let input = black_box(&REGEXES[6]);
             let regex = parser::regex(input).unwrap();
             let nfa = regex.to_nfa();
-            let dfa = nfa.to_dfa();
-            dfa
+            nfa.to_dfa()
         })
     });
 
@@ -72,6 +73,46 @@ pub fn regex_compile(c: &mut Criterion) {
     });
 }
 
+pub fn determinize(c: &mut Criterion) {
+    // Thompson constructions from regexes have plenty of epsilon moves, even more so under a star
+    let input = format!("({})*", &REGEXES[5]);
+    let nfa = parser::regex(&input).unwrap().to_nfa();
+    c.bench_function("nfa to dfa", |b| b.iter(|| black_box(&nfa).to_dfa()));
+    c.bench_function("remove epsilon moves", |b| {
+        b.iter(|| {
+            let mut nfa = black_box(&nfa).clone();
+            nfa.remove_epsilon_moves();
+            nfa
+        })
+    });
+}
+
+pub fn nfa_accepts(c: &mut Criterion) {
+    let mut runner = TestRunner::default();
+    let words = (0..1000)
+        .map(|_| "[a-z]{0,20}".new_tree(&mut runner).unwrap().current())
+        .collect::<Vec<_>>();
+    let nfa = parser::regex(&REGEXES[6]).unwrap().to_nfa();
+
+    c.bench_function("nfa accepts", |b| {
+        b.iter(|| {
+            words
+                .iter()
+                .filter(|word| nfa.accepts_graphemes(black_box(word)))
+                .count()
+        })
+    });
+    c.bench_function("nfa accepts with scratch", |b| {
+        let mut scratch = NfaScratch::new();
+        b.iter(|| {
+            words
+                .iter()
+                .filter(|word| nfa.accepts_with(black_box(word).graphemes(true), &mut scratch))
+                .count()
+        })
+    });
+}
+
 pub fn regex_check(c: &mut Criterion) {
     let mut runner = TestRunner::default();
     let string_gen = "[a-z]+".new_tree(&mut runner).unwrap();
@@ -82,6 +123,13 @@ pub fn regex_check(c: &mut Criterion) {
         b.iter(|| regex.accepts_graphemes(black_box(&string_gen.current())))
     });
 
+    let mut runner = TestRunner::default();
+    let string_gen = "[a-z]+".new_tree(&mut runner).unwrap();
+    let compiled = parser::regex(&REGEXES[6]).unwrap().compile();
+    c.bench_function("dandy compiled regex check", |b| {
+        b.iter(|| compiled.is_match(black_box(&string_gen.current())))
+    });
+
     let mut runner = TestRunner::default();
     let string_gen = "[a-z]+".new_tree(&mut runner).unwrap();
     let input_regex = format!("^({})$", &REGEXES[6]);
@@ -96,6 +144,8 @@ criterion_group!(
     equivalence_check,
     powerset,
     regex_compile,
+    determinize,
+    nfa_accepts,
     regex_check
 );
 criterion_main!(benches);
@@ -0,0 +1,22 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dandy::dfa::Dfa;
+use rand::prelude::*;
+
+pub fn product_construction(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(826);
+    let dfa1 = Dfa::random(&mut rng, 500, &["a", "b", "c"]);
+    let dfa2 = Dfa::random(&mut rng, 500, &["a", "b", "c"]);
+
+    let mut group = c.benchmark_group("product construction");
+    group.sample_size(10);
+    group.bench_function("serial", |b| {
+        b.iter(|| dfa1.product_construction(black_box(&dfa2), |s1, s2| s1 && s2))
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| dfa1.par_product_construction(black_box(&dfa2), |s1, s2| s1 && s2))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, product_construction);
+criterion_main!(benches);
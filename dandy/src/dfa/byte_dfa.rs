@@ -0,0 +1,95 @@
+use crate::dfa::Dfa;
+
+/// A DFA over bytes with a flat transition table, for matching byte strings as fast as possible. See
+/// [Dfa::to_byte_dfa].
+///
+/// The states are those of the DFA it was created from, with the same indices, plus one extra rejecting state which is
+/// entered upon any byte not in the alphabet and which is never left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByteDfa {
+    /// The transitions of each state, indexed by the byte
+    pub(crate) table: Vec<[usize; 256]>,
+    pub(crate) accepting: Vec<bool>,
+    pub(crate) initial_state: usize,
+}
+
+impl Dfa {
+    /// Converts this DFA to a [ByteDfa], if every element of the alphabet is a single byte (that is, a single ASCII
+    /// character). Otherwise, `None` is returned.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///           0    1
+    /// -> * even even odd
+    ///      odd  odd  even
+    /// ").unwrap().try_into().unwrap();
+    /// let byte_dfa = dfa.to_byte_dfa().unwrap();
+    /// assert!(byte_dfa.accepts(b"0110"));
+    /// assert!(!byte_dfa.accepts(b"0100"));
+    /// assert!(!byte_dfa.accepts(b"0120")); // '2' isn't in the alphabet
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        ab  c
+    /// -> * s s   s
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.to_byte_dfa(), None);
+    /// ```
+    pub fn to_byte_dfa(&self) -> Option<ByteDfa> {
+        let bytes = self
+            .alphabet
+            .iter()
+            .map(|elem| match elem.as_bytes() {
+                &[byte] => Some(byte),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let reject = self.states.len();
+        let mut table = vec![[reject; 256]; self.states.len() + 1];
+        for (row, state) in table.iter_mut().zip(&self.states) {
+            for (&byte, &target) in bytes.iter().zip(&state.transitions) {
+                row[byte as usize] = target;
+            }
+        }
+        let accepting = self
+            .states
+            .iter()
+            .map(|state| state.accepting)
+            .chain([false])
+            .collect();
+        Some(ByteDfa {
+            table,
+            accepting,
+            initial_state: self.initial_state,
+        })
+    }
+}
+
+impl ByteDfa {
+    /// Checks if the DFA accepts the given bytes, where each byte is one element of the alphabet
+    pub fn accepts(&self, input: &[u8]) -> bool {
+        let state = input.iter().fold(self.initial_state, |state, &byte| {
+            self.table[state][byte as usize]
+        });
+        self.accepting[state]
+    }
+
+    /// Gets the index of the initial state, to start stepping through the DFA with [ByteDfa::next_state]
+    pub fn initial_state_index(&self) -> usize {
+        self.initial_state
+    }
+
+    /// Gets the index of the state entered from the state `state` upon seeing `byte`, for scanning a stream of bytes
+    /// one byte at a time
+    pub fn next_state(&self, state: usize, byte: u8) -> usize {
+        self.table[state][byte as usize]
+    }
+
+    /// Checks if the state with the given index is accepting
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.accepting[state]
+    }
+}
@@ -0,0 +1,195 @@
+use crate::dfa::{ConstructionError, Dfa, DfaState};
+use std::rc::Rc;
+use thiserror::Error;
+
+/// The version of the binary format, which is the first byte of every encoded DFA
+const FORMAT_VERSION: u8 = 1;
+/// The flag bit set for the initial state
+const INITIAL: u8 = 0b01;
+/// The flag bit set for accepting states
+const ACCEPTING: u8 = 0b10;
+
+/// The error returned by [Dfa::from_bytes] when the bytes aren't a DFA encoded by [Dfa::to_bytes]
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("Unsupported format version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Unexpected end of input")]
+    UnexpectedEnd,
+    #[error("The integer at byte {0} is too large")]
+    IntegerOverflow(usize),
+    #[error("The string at byte {0} is not valid UTF-8")]
+    InvalidUtf8(usize),
+    #[error("Invalid state flags {1:#04x} at byte {0}")]
+    InvalidFlags(usize, u8),
+    #[error("There are {0} bytes left after the DFA")]
+    TrailingBytes(usize),
+    #[error("The decoded DFA is invalid: {0}")]
+    Invalid(#[from] ConstructionError),
+}
+
+impl Dfa {
+    /// Encodes this DFA in a compact binary format, which can be decoded with [Dfa::from_bytes]. All integers are
+    /// written as [LEB128](https://en.wikipedia.org/wiki/LEB128) variable-length integers, and strings are written as
+    /// their length followed by their UTF-8 bytes. The format is:
+    /// * A format version byte,
+    /// * The number of elements in the alphabet, followed by each element,
+    /// * The number of states and the index of the initial state,
+    /// * For each state, its name, a byte with the flags `1` if it is initial and `2` if it is accepting, and the index
+    ///   of the target of its transition upon seeing each element of the alphabet.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///           0    1
+    /// -> * even even odd
+    ///      odd  odd  even
+    /// ").unwrap().try_into().unwrap();
+    /// let bytes = dfa.to_bytes();
+    /// assert_eq!(bytes.len(), 23);
+    /// assert_eq!(Dfa::from_bytes(&bytes), Ok(dfa));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![FORMAT_VERSION];
+        write_varint(&mut bytes, self.alphabet.len());
+        for elem in self.alphabet.iter() {
+            write_str(&mut bytes, elem);
+        }
+        write_varint(&mut bytes, self.states.len());
+        write_varint(&mut bytes, self.initial_state);
+        for state in &self.states {
+            write_str(&mut bytes, &state.name);
+            let mut flags = 0;
+            if state.initial {
+                flags |= INITIAL;
+            }
+            if state.accepting {
+                flags |= ACCEPTING;
+            }
+            bytes.push(flags);
+            for &target in &state.transitions {
+                write_varint(&mut bytes, target);
+            }
+        }
+        bytes
+    }
+
+    /// Decodes a DFA encoded by [Dfa::to_bytes]. The decoded DFA is checked in the same way as by [Dfa::from_parts],
+    /// so invalid input gives an error and never an inconsistent DFA.
+    ///
+    /// ```
+    /// use dandy::dfa::{DecodeError, Dfa};
+    ///
+    /// assert_eq!(Dfa::from_bytes(&[]), Err(DecodeError::UnexpectedEnd));
+    /// assert_eq!(Dfa::from_bytes(&[7]), Err(DecodeError::UnsupportedVersion(7)));
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Dfa, DecodeError> {
+        let mut reader = Reader { bytes, position: 0 };
+        let version = reader.byte()?;
+        if version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let alphabet_len = reader.varint()?;
+        let mut alphabet = Vec::with_capacity(reader.capacity(alphabet_len));
+        for _ in 0..alphabet_len {
+            alphabet.push(reader.string()?);
+        }
+
+        let num_states = reader.varint()?;
+        let initial_state = reader.varint()?;
+        let mut states = Vec::with_capacity(reader.capacity(num_states));
+        for _ in 0..num_states {
+            let name = reader.string()?;
+            let flags_position = reader.position;
+            let flags = reader.byte()?;
+            if flags & !(INITIAL | ACCEPTING) != 0 {
+                return Err(DecodeError::InvalidFlags(flags_position, flags));
+            }
+            let transitions = (0..alphabet_len)
+                .map(|_| reader.varint())
+                .collect::<Result<_, _>>()?;
+            states.push(DfaState {
+                name,
+                initial: flags & INITIAL != 0,
+                accepting: flags & ACCEPTING != 0,
+                transitions,
+            });
+        }
+
+        let remaining = bytes.len() - reader.position;
+        if remaining > 0 {
+            return Err(DecodeError::TrailingBytes(remaining));
+        }
+        Ok(Dfa::from_parts(alphabet, states, initial_state)?)
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn write_str(bytes: &mut Vec<u8>, s: &str) {
+    write_varint(bytes, s.len());
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let byte = *self
+            .bytes
+            .get(self.position)
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<usize, DecodeError> {
+        let start = self.position;
+        let mut value = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            let bits = usize::from(byte & 0x7f);
+            if shift >= usize::BITS || (bits << shift) >> shift != bits {
+                return Err(DecodeError::IntegerOverflow(start));
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn string(&mut self) -> Result<Rc<str>, DecodeError> {
+        let start = self.position;
+        let len = self.varint()?;
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        let s = std::str::from_utf8(&self.bytes[self.position..end])
+            .map_err(|_| DecodeError::InvalidUtf8(start))?;
+        self.position = end;
+        Ok(Rc::from(s))
+    }
+
+    /// The capacity to reserve for `len` items, which is limited by the number of bytes left so that corrupted lengths
+    /// don't cause huge allocations
+    fn capacity(&self, len: usize) -> usize {
+        len.min(self.bytes.len() - self.position)
+    }
+}
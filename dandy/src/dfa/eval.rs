@@ -1,18 +1,19 @@
 use crate::dfa::{Dfa, DfaState};
-use std::collections::HashMap;
-use std::iter;
+use crate::util::Map;
+use alloc::vec::Vec;
+use core::iter;
 
 #[derive(Debug, Clone)]
 pub struct DfaEvaluator<'a> {
     dfa: &'a Dfa,
-    rev_map: HashMap<&'a str, usize>,
+    rev_map: Map<&'a str, usize>,
     current_state: usize,
     unknown_elem_seen: bool,
 }
 
 impl<'a> DfaEvaluator<'a> {
     pub fn is_accepting(&self) -> bool {
-        self.current_state().map_or(false, DfaState::is_accepting)
+        self.current_state().is_some_and(DfaState::is_accepting)
     }
 
     pub fn current_state(&self) -> Option<&DfaState> {
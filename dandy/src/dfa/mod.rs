@@ -1,8 +1,8 @@
 //! # Deterministic Finite Automaton
 //! The DFA module includes the [Dfa] struct which represents a
 //! [Deterministic finite automaton](https://en.wikipedia.org/wiki/Deterministic_finite_automaton). Currently,
-//! the only two ways to create such an instance is by [converting a Nfa to a Dfa](Nfa::to_dfa) or by parsing from a
-//! string.
+//! the only ways to create such an instance is by [converting a Nfa to a Dfa](Nfa::to_dfa), by parsing from a
+//! string, by constructing a [Hamming ball](Dfa::hamming_ball) or by [generating one randomly](Dfa::random).
 //!
 //! ## Example
 //! You may parse a state transition table in text form to a DFA. The parsing is done in two steps, the first one
@@ -134,6 +134,9 @@
 //! merges all non-distinguishable states. The minimization is unique. The algorithm involves multiple steps, some of
 //! which can be executed standalone if one would need to:
 //!
+//! Removing unreachable states and then [dead states](Dfa::remove_dead_states), from which no accepting state can be
+//! reached, gives a trimmed DFA (except for at most one trap state, since a DFA has all transitions).
+//!
 //! [Dfa::minimize] involves:
 //! * [Removing unreachable states](Dfa::remove_unreachable_states), which depends on
 //!   * [Identifying unreachable states](Dfa::unreachable_states), and
@@ -143,9 +146,15 @@
 //!   * Remapping states (not exposed), and
 //!   * Removing states (not exposed)
 //!
+//! To compare minimized DFAs by their tables, [Dfa::canonicalize] orders and names the states in a canonical way.
+//!
+//! To see why two states aren't merged, [Dfa::distinguishing_word] gives a shortest word telling them apart.
+//!
 //! We can also invert a DFA by doing [Dfa::invert], which simply makes accepting states non-accepting and non-accepting
 //! states accepting. After inverting a DFA, it will accept all strings not previously accepted, and rejects all strings
-//! it previously accepted (assuming the strings are of the alphabet).
+//! it previously accepted (assuming the strings are of the alphabet). Similarly, [Dfa::prefix_closure] makes every
+//! state from which an accepting state can be reached accepting, giving a DFA accepting all prefixes of accepted
+//! strings.
 //!
 //! ### Product constructions
 //! A product construction is a DFA P created from two DFAs A and B, where the states of P is the product of the states
@@ -163,11 +172,12 @@
 //! Dandy has support for constructing a *reduced product construction*, which is a product construction only containing
 //! the pair of states which are actually reachable from the initial pair of states. This has the potential to reduce
 //! the amount of states greatly, but while this will exclude unreachable states, it will not merge non-distinguishable
-//! states. The product construction is generated by [Dfa::product_construction], and accepts a function from the
-//! pair of states `a` and `b` to if the pair of states should be an accepting state. Here is an example:
+//! states. The product construction is generated by [Dfa::product_construction], and accepts a function from whether
+//! the states `a` and `b` of the pair are accepting to if the pair of states should be an accepting state, so any
+//! boolean combination of the two DFAs can be constructed. Here is an example:
 //! ```
 //! use dandy::parser;
-//! use dandy::dfa::{Dfa, DfaState};
+//! use dandy::dfa::Dfa;
 //!
 //! let ends_with_a = "
 //!      a b c
@@ -181,7 +191,7 @@
 //! let ends_with_a: Dfa = parser::dfa(ends_with_a).unwrap().try_into().unwrap();
 //! let starts_with_b: Dfa = parser::dfa(starts_with_b).unwrap().try_into().unwrap();
 //!
-//! let first_not_second = |first: &DfaState, second: &DfaState| first.is_accepting() && !second.is_accepting();
+//! let first_not_second = |first: bool, second: bool| first && !second;
 //! // 'a_not_b' accepts strings that ends with a and doesn't start with b.
 //! let a_not_b = ends_with_a.product_construction(&starts_with_b, first_not_second).unwrap();
 //! assert!(a_not_b.accepts_graphemes("aa"));
@@ -190,48 +200,235 @@
 //! assert!(!a_not_b.accepts_graphemes("bb"));
 //! ```
 //!
-//! In addition, there are four commonly used operations provided as their own convenience functions:
+//! In addition, there are four commonly used operations provided as their own convenience functions, which are all
+//! product constructions:
 //! * [Dfa::union] - Binary `or`, `A.union(&B)` accepts all strings accepted by `A` or `B` (or both)
 //! * [Dfa::intersection] - Binary `and`, `A.intersection(&B)` accepts all strings accepted by `A` and `B`
 //! * [Dfa::difference] - `A.difference(&B)` accepts all strings accepted by `A` but not by `B`
 //! * [Dfa::symmetric_difference] - Binary `xor`, `A.symmetric_difference(&B)` accepts all strings accepted by
 //!   `A` or by `B` but not by both
 //!
+//! The product construction is also used for the [right quotient](Dfa::right_quotient) `A.right_quotient(&B)`, which
+//! accepts all strings `u` such that `uv` is accepted by `A` for some string `v` accepted by `B`.
+//!
+//! With the `rayon` feature, `Dfa::par_product_construction` does the same as [Dfa::product_construction], but
+//! explores the pairs of states in parallel.
+//!
 //! ### Checking equivalence
 //! Two DFAs `A` and `B` are equivalent if and only if they have the same alphabet and accept the same language.
 //! There are two ways to check equivalence between two DFAs: either by running [Dfa::equivalent_to] or by
 //! checking if the symmetric difference is empty. One can check if DFA accepts any strings with
 //! [Dfa::has_reachable_accepting_state]. Testing has shown that constructing the symmetric difference instead of using
 //! [Dfa::equivalent_to] leads to a performance penalty of around 3964%, since [Dfa::equivalent_to] doesn't actually
-//! construct any new automatas.
+//! construct any new automatas. If the DFAs aren't equivalent, [Dfa::equivalence_witness] gives a shortest word
+//! which is accepted by exactly one of them.
+//!
+//! To instead check if two DFAs have the same structure, and not just the same language, use [Dfa::is_isomorphic_to].
+//! [Dfa::isomorphism] also gives the correspondence between the states of the two DFAs.
 //!
 //! ### Additional operations
 //! In addition to the above-mentioned operations, you can:
-//! * [Get the alphabet](Dfa::alphabet) of the DFA,
+//! * [Get the alphabet](Dfa::alphabet) of the DFA, or [rename its elements](Dfa::map_alphabet),
 //! * [Get the states](Dfa::states) and [initial state](Dfa::initial_state) of the DFA,
 //! * [Convert it to a table](Dfa::to_table), possibly [in ascii-only](Dfa::ascii_table), both of which
 //!   can be parsed by Dandy into this very same DFA again,
+//! * [Convert it to a Mermaid state diagram](Dfa::to_mermaid), for embedding in Markdown, to a
+//!   [Graphviz DOT graph](Dfa::to_dot), or to a [TikZ picture](Dfa::to_tikz) for LaTeX,
+//! * [Encode it](Dfa::to_bytes) in a compact binary format, which can be [decoded](Dfa::from_bytes) again,
+//! * [Convert it to a ByteDfa](Dfa::to_byte_dfa) with a flat transition table, for fast matching of byte strings,
+//! * [Convert it to a regular expression](Dfa::to_regex),
+//! * Construct the inverse image of its language under a [homomorphism](Dfa::inverse_homomorphism),
+//! * Compute its [transition monoid](Dfa::transition_monoid), and check if its language is
+//!   [star-free](Dfa::is_star_free),
+//! * Check if it accepts a string with elements of several characters without splitting it first, using
+//!   [Dfa::accepts_tokenized],
 //! * Find all [reachable](Dfa::reachable_states) and [non-reachable](Dfa::unreachable_states) states,
+//! * Check if its language is [finite](Dfa::is_finite) or [cofinite](Dfa::is_cofinite),
+//! * [Sample](Dfa::sample_uniform) accepted strings of a given length uniformly at random,
 //! * [Clone](Dfa::clone) it, which isn't super expensive since the alphabet and state names doesn't need new
 //!   allocations to be cloned (no strings at all are actually copied, just some `vec`s with `bool`s and `usize`s)
+#[cfg(feature = "std")]
+use crate::diagram::DiagramState;
+#[cfg(feature = "std")]
+use crate::dot;
+#[cfg(feature = "std")]
+use crate::mermaid;
 use crate::nfa::{Nfa, NfaState};
+#[cfg(feature = "std")]
 pub use crate::parser::dfa as parse;
+#[cfg(feature = "std")]
+use crate::regex::Regex;
+#[cfg(feature = "std")]
 use crate::table::Table;
+#[cfg(feature = "std")]
+use crate::tikz;
+use crate::util;
+#[cfg(feature = "std")]
 use crate::util::alphabet_equal;
+use alloc::rc::Rc;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+pub use byte_dfa::ByteDfa;
+#[cfg(feature = "std")]
+pub use bytes::DecodeError;
 pub use eval::DfaEvaluator;
+#[cfg(feature = "std")]
+pub use monoid::{MonoidElement, TransitionMonoid};
+#[cfg(feature = "std")]
 pub use parse::DfaParseError;
-use std::collections::{HashMap, HashSet};
-use std::rc::Rc;
+#[cfg(feature = "std")]
+pub use partition::Partition;
+#[cfg(feature = "std")]
+use rand::Rng;
+#[cfg(feature = "std")]
+use std::collections::hash_map::Entry;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet, VecDeque};
+#[cfg(feature = "std")]
+use std::fmt;
+#[cfg(feature = "std")]
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::{iter, mem};
+use thiserror::Error;
 use unicode_segmentation::UnicodeSegmentation;
 
+#[cfg(feature = "std")]
+pub mod byte_dfa;
+#[cfg(feature = "std")]
+pub mod bytes;
 pub mod eval;
+#[cfg(feature = "std")]
+pub mod monoid;
+#[cfg(feature = "std")]
 pub mod parse;
+#[cfg(feature = "std")]
+pub mod partition;
+
+/// The error returned by operations on two automata which requires them to have the same alphabet (not considering
+/// ordering), when they don't
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("The alphabets of the automata differ")]
+pub struct AlphabetMismatch;
+
+/// The error returned by [Dfa::intersection_all] and [Dfa::union_all]
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum ProductAllError {
+    #[error("No automata were given")]
+    Empty,
+    #[error("The alphabet of automaton {0} differs from the alphabet of the first automaton")]
+    AlphabetMismatch(usize),
+}
+
+/// The error returned by [Dfa::tokenize] and [Dfa::accepts_tokenized] when no element of the alphabet matches the
+/// input at some position
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("No element of the alphabet matches the input at byte {position}")]
+pub struct TokenizeError {
+    /// The byte offset into the input where no element matched
+    pub position: usize,
+}
+
+/// The error returned by [Dfa::trace] when the word contains an element which isn't in the alphabet
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("Element {position} of the word is not in the alphabet")]
+pub struct TraceError {
+    /// The index in the word of the first element not in the alphabet
+    pub position: usize,
+}
+
+/// The error returned by [Dfa::map_alphabet] and [Nfa::map_alphabet] when two distinct elements of the alphabet are
+/// mapped to the same string
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("Both '{first}' and '{second}' are mapped to '{mapped}'")]
+pub struct AlphabetCollision {
+    pub first: Rc<str>,
+    pub second: Rc<str>,
+    pub mapped: Rc<str>,
+}
+
+/// The error returned by [Dfa::retain_states] and [Nfa::retain_states] when the states to keep don't form a valid
+/// automaton
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum RetainError {
+    #[error("State {0} is the initial state, which can't be removed")]
+    InitialStateRemoved(usize),
+    #[error("State {0} has a transition to the removed state {1}")]
+    TransitionToRemovedState(usize, usize),
+}
+
+/// The error returned by [Dfa::from_parts] and [Nfa::from_parts] when the parts don't form a valid automaton. These
+/// are the same checks as when converting a parsed automaton.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ConstructionError {
+    #[error("Wrong number of transitions for state '{0}': has {1} expected {2}")]
+    WrongNumberOfTransitions(Rc<str>, usize, usize),
+    #[error("State {1} does not exist (in transition from state '{0}')")]
+    TransitionDoesNotExist(Rc<str>, usize),
+    #[error("Initial state {0} does not exist")]
+    InitialStateDoesNotExist(usize),
+    #[error("There is no state marked as initial")]
+    MissingInitialState,
+    #[error("There are two (or more) states marked as initial")]
+    MultipleInitialStates,
+    #[error("State {0} is the initial state, but isn't marked as initial")]
+    InitialStateNotMarked(usize),
+    #[error("'{0}' appears twice in the alphabet")]
+    DuplicateAlphabetSymbol(Rc<str>),
+    #[error("State '{0}' defined multiple times")]
+    DuplicateStateDefinition(Rc<str>),
+}
+
+impl ConstructionError {
+    /// Checks the parts which DFAs and NFAs have in common: the alphabet and the state names must not contain
+    /// duplicates, and exactly one state must be marked as initial, which must be the one at `initial_state`. The
+    /// states are given by their names and whether they are marked as initial.
+    pub(crate) fn check_common<'a>(
+        alphabet: &[Rc<str>],
+        states: impl Iterator<Item = (&'a Rc<str>, bool)>,
+        initial_state: usize,
+    ) -> Result<(), ConstructionError> {
+        let mut seen = util::Set::new();
+        if let Some(elem) = alphabet.iter().find(|elem| !seen.insert(*elem)) {
+            return Err(ConstructionError::DuplicateAlphabetSymbol(elem.clone()));
+        }
+
+        let mut seen = util::Set::new();
+        let mut marked_initial = None;
+        let mut num_states = 0;
+        for (idx, (name, initial)) in states.enumerate() {
+            if !seen.insert(name) {
+                return Err(ConstructionError::DuplicateStateDefinition(name.clone()));
+            }
+            if initial {
+                if marked_initial.is_some() {
+                    return Err(ConstructionError::MultipleInitialStates);
+                }
+                marked_initial = Some(idx);
+            }
+            num_states += 1;
+        }
+
+        if initial_state >= num_states {
+            return Err(ConstructionError::InitialStateDoesNotExist(initial_state));
+        }
+        match marked_initial {
+            None => Err(ConstructionError::MissingInitialState),
+            Some(idx) if idx != initial_state => {
+                Err(ConstructionError::InitialStateNotMarked(initial_state))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
 
 /// A [Deterministic finite automaton](https://en.wikipedia.org/wiki/Deterministic_finite_automaton),
 /// defined by its *alphabet*, a *set of states*, one of the states being its *initial state*, a subset of its states
 /// being *final states*/*accepting states*, and a *transition function* from each state upon seeing each element of
 /// the alphabet to any state. See the [module-level documentation](crate::dfa) for more info.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Dfa {
     pub(crate) alphabet: Rc<[Rc<str>]>,
     pub(crate) states: Vec<DfaState>,
@@ -241,6 +438,7 @@ pub struct Dfa {
 /// A state in a DFA automata, which consists of its name, if it is the initial state or not, if it is accepting
 /// or not, and the transition for each element of the alphabet
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DfaState {
     pub(crate) name: Rc<str>,
     pub(crate) initial: bool,
@@ -249,6 +447,34 @@ pub struct DfaState {
 }
 
 impl DfaState {
+    /// Creates a state with the given name, whether it is the initial state and accepting, and its transitions as
+    /// state indices for each element of the alphabet. The states can be made into a DFA with [Dfa::from_parts].
+    ///
+    /// ```
+    /// use dandy::dfa::{Dfa, DfaState};
+    ///
+    /// let states = vec![
+    ///     DfaState::new("even", true, true, vec![1]),
+    ///     DfaState::new("odd", false, false, vec![0]),
+    /// ];
+    /// let dfa = Dfa::from_parts(vec!["a".into()], states, 0).unwrap();
+    /// assert!(dfa.accepts_graphemes("aa"));
+    /// assert!(!dfa.accepts_graphemes("aaa"));
+    /// ```
+    pub fn new(
+        name: impl Into<Rc<str>>,
+        initial: bool,
+        accepting: bool,
+        transitions: Vec<usize>,
+    ) -> Self {
+        DfaState {
+            name: name.into(),
+            initial,
+            accepting,
+            transitions,
+        }
+    }
+
     /// Gets the name of this state
     pub fn name(&self) -> &str {
         &self.name
@@ -294,7 +520,141 @@ impl From<Dfa> for Nfa {
     }
 }
 
+/// Writes the same table as [Dfa::to_table]
+///
+/// ```
+/// use dandy::dfa::Dfa;
+/// use dandy::parser;
+///
+/// let dfa: Dfa = parser::dfa("
+///       a  b
+/// -> s₀ s₁ s₀
+///  * s₁ s₁ s₀
+/// ").unwrap().try_into().unwrap();
+/// assert_eq!(dfa.to_string(), dfa.to_table());
+/// ```
+#[cfg(feature = "std")]
+impl Display for Dfa {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_table())
+    }
+}
+
+#[cfg(feature = "std")]
 impl Dfa {
+    /// Constructs a DFA accepting exactly the strings of the same length as `word` which differ from it in at most
+    /// `max_subs` positions, that is, the strings within [Hamming distance](https://en.wikipedia.org/wiki/Hamming_distance)
+    /// `max_subs` of the word. The alphabet of the DFA is the given alphabet, followed by any elements of the word not
+    /// in it.
+    ///
+    /// The states are named `s{position}_{substitutions}`, and all strings which are too long or have too many
+    /// substitutions end up in the state `trap`. Unreachable states (with more substitutions than positions) aren't
+    /// included. To find the words of a dictionary within the substitution budget, intersect this DFA with one
+    /// accepting the dictionary.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa = Dfa::hamming_ball(&["r", "o", "s", "e"], 1, &["a", "e", "o", "r", "s", "t"]);
+    /// assert!(dfa.accepts_graphemes("rose"));
+    /// assert!(dfa.accepts_graphemes("rase"));
+    /// assert!(!dfa.accepts_graphemes("rise")); // "i" isn't in the alphabet
+    /// assert!(dfa.accepts_graphemes("rost"));
+    /// assert!(!dfa.accepts_graphemes("rats")); // Two substitutions
+    /// assert!(!dfa.accepts_graphemes("roses")); // Different length
+    /// ```
+    pub fn hamming_ball(word: &[&str], max_subs: usize, alphabet: &[&str]) -> Dfa {
+        let mut elements = Vec::<&str>::new();
+        for &elem in alphabet.iter().chain(word) {
+            if !elements.contains(&elem) {
+                elements.push(elem);
+            }
+        }
+
+        let idx = |position: usize, subs: usize| position * (max_subs + 1) + subs;
+        let trap = idx(word.len() + 1, 0);
+        let mut states = (0..=word.len())
+            .flat_map(|position| (0..=max_subs).map(move |subs| (position, subs)))
+            .map(|(position, subs)| {
+                let transitions = elements
+                    .iter()
+                    .map(|&elem| match word.get(position) {
+                        Some(&expected) if expected == elem => idx(position + 1, subs),
+                        Some(_) if subs < max_subs => idx(position + 1, subs + 1),
+                        _ => trap,
+                    })
+                    .collect();
+                DfaState {
+                    name: Rc::from(format!("s{position}_{subs}")),
+                    initial: position == 0 && subs == 0,
+                    accepting: position == word.len(),
+                    transitions,
+                }
+            })
+            .collect::<Vec<_>>();
+        states.push(DfaState {
+            name: Rc::from("trap"),
+            initial: false,
+            accepting: false,
+            transitions: vec![trap; elements.len()],
+        });
+
+        let mut dfa = Dfa {
+            alphabet: elements.into_iter().map(Rc::from).collect(),
+            states,
+            initial_state: 0,
+        };
+        dfa.remove_unreachable_states();
+        dfa
+    }
+
+    /// Constructs a random DFA with `num_states` states named `s0`, `s1` and so on, where `s0` is the initial state,
+    /// each state is accepting with probability ½ and each transition goes to a uniformly random state. Duplicates in
+    /// the alphabet are removed. With a seeded random number generator, the same DFA is constructed every time, which
+    /// makes this useful for reproducible test corpora.
+    ///
+    /// Note that the DFA may have unreachable states, and may accept nothing at all.
+    ///
+    /// # Panics
+    /// Panics if `num_states` is zero, since a DFA needs an initial state.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let dfa = Dfa::random(&mut StdRng::seed_from_u64(7), 5, &["a", "b"]);
+    /// assert_eq!(dfa.states().len(), 5);
+    /// assert_eq!(dfa.alphabet().len(), 2);
+    /// assert_eq!(dfa, Dfa::random(&mut StdRng::seed_from_u64(7), 5, &["a", "b"]));
+    /// ```
+    pub fn random(rng: &mut impl Rng, num_states: usize, alphabet: &[&str]) -> Dfa {
+        assert!(num_states > 0, "a DFA needs at least one state");
+        let mut elements = Vec::<&str>::new();
+        for &elem in alphabet {
+            if !elements.contains(&elem) {
+                elements.push(elem);
+            }
+        }
+
+        let states = (0..num_states)
+            .map(|idx| DfaState {
+                name: Rc::from(format!("s{idx}")),
+                initial: idx == 0,
+                accepting: rng.gen(),
+                transitions: elements
+                    .iter()
+                    .map(|_| rng.gen_range(0..num_states))
+                    .collect(),
+            })
+            .collect();
+        Dfa {
+            alphabet: elements.into_iter().map(Rc::from).collect(),
+            states,
+            initial_state: 0,
+        }
+    }
+
     /// Inverts this automata, which makes accepting states non-accepting and non-accepting states accepting. This means
     /// that the automata after the inversion accepts all strings not previously accepted, and rejects all strings
     /// that were previously accepted (assuming all strings are of the alphabet)
@@ -320,6 +680,148 @@ impl Dfa {
             .for_each(|s| s.accepting = !s.accepting)
     }
 
+    /// Constructs a DFA for the prefix closure of the language of this DFA, that is, a DFA accepting every string which
+    /// can be extended to a string accepted by this DFA. This is done by making every state that isn't
+    /// [dead](Dfa::dead_state_idx) accepting, which makes it useful for checking if incomplete input may still become
+    /// valid.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// // Accepts exactly "abc"
+    /// let abc: Dfa = parser::dfa("
+    ///            a    b    c
+    /// -> s₀      s₁   trap trap
+    ///    s₁      trap s₂   trap
+    ///    s₂      trap trap s₃
+    ///  * s₃      trap trap trap
+    ///    trap    trap trap trap
+    /// ").unwrap().try_into().unwrap();
+    /// let prefixes = abc.prefix_closure();
+    /// assert!(prefixes.accepts_graphemes(""));
+    /// assert!(prefixes.accepts_graphemes("ab"));
+    /// assert!(prefixes.accepts_graphemes("abc"));
+    /// assert!(!prefixes.accepts_graphemes("ac"));
+    /// ```
+    pub fn prefix_closure(&self) -> Dfa {
+        let dead = self.dead_state_idx();
+        let mut closure = self.clone();
+        for (idx, state) in closure.states.iter_mut().enumerate() {
+            state.accepting = !dead.contains(&idx);
+        }
+        closure
+    }
+
+    /// Renames every element of the alphabet using `f`, keeping all transitions as they are. This may be used to make
+    /// two DFAs over alphabets that are spelled differently compatible, for example for [Dfa::union]. If two distinct
+    /// elements are mapped to the same string, an error is returned and the DFA is left unchanged.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let mut dfa: Dfa = parser::dfa("
+    ///          0  1
+    /// -> * s₀  s₀ s₁
+    ///      s₁  s₁ s₀
+    /// ").unwrap().try_into().unwrap();
+    /// dfa.map_alphabet(|elem| if elem == "0" { "a".to_string() } else { "b".to_string() }).unwrap();
+    /// assert!(dfa.accepts_graphemes("abab"));
+    /// assert!(!dfa.accepts_graphemes("aab"));
+    ///
+    /// assert!(dfa.map_alphabet(|_| "c".to_string()).is_err());
+    /// assert_eq!(dfa.alphabet()[0].as_ref(), "a");
+    /// ```
+    pub fn map_alphabet(&mut self, f: impl FnMut(&str) -> String) -> Result<(), AlphabetCollision> {
+        self.alphabet = util::map_alphabet(&self.alphabet, f)?;
+        Ok(())
+    }
+
+    /// Constructs a DFA over `new_alphabet` for the inverse image of the language of this DFA under the homomorphism
+    /// `h`, that is, a DFA accepting exactly the strings `w` such that this DFA accepts `h(w)`. The new DFA has the
+    /// same states as this one, and its transition from a state upon seeing `b` goes to the state this DFA reaches
+    /// from that state after reading `h(b)`. If `h(b)` contains an element not in the alphabet of this DFA, the
+    /// transitions upon seeing `b` go to a new non-accepting trap state instead. Duplicates in `new_alphabet` are
+    /// ignored.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// // Strings of a's and b's with an even number of a's
+    /// let dfa = parser::regex("(b*ab*a)*b*").unwrap().to_dfa();
+    /// let preimage = dfa.inverse_homomorphism(
+    ///     |elem| match elem {
+    ///         "x" => vec!["a"],
+    ///         "y" => vec!["a", "b", "a"],
+    ///         _ => vec!["c"],
+    ///     },
+    ///     &["x", "y", "z"],
+    /// );
+    /// assert!(preimage.accepts_graphemes("xyx"));
+    /// assert!(preimage.accepts_graphemes("yy"));
+    /// assert!(!preimage.accepts_graphemes("xyy"));
+    /// assert!(!preimage.accepts_graphemes("z")); // "c" is never accepted by the DFA
+    /// ```
+    pub fn inverse_homomorphism(
+        &self,
+        h: impl Fn(&str) -> Vec<&str>,
+        new_alphabet: &[&str],
+    ) -> Dfa {
+        let mut elements = Vec::<&str>::new();
+        for &elem in new_alphabet {
+            if !elements.contains(&elem) {
+                elements.push(elem);
+            }
+        }
+
+        // The image of each new element as indices into the alphabet, or None if it isn't a string over it
+        let images = elements
+            .iter()
+            .map(|&elem| {
+                h(elem)
+                    .into_iter()
+                    .map(|e| self.alphabet.iter().position(|a| a.as_ref() == e))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Vec<_>>();
+
+        let trap = self.states.len();
+        let mut states = self
+            .states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| DfaState {
+                name: state.name.clone(),
+                initial: state.initial,
+                accepting: state.accepting,
+                transitions: images
+                    .iter()
+                    .map(|image| match image {
+                        Some(image) => image
+                            .iter()
+                            .fold(idx, |current, &e| self.states[current].transitions[e]),
+                        None => trap,
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        if images.iter().any(Option::is_none) {
+            states.push(DfaState {
+                name: self.fresh_name("trap"),
+                initial: false,
+                accepting: false,
+                transitions: vec![trap; elements.len()],
+            });
+        }
+
+        Dfa {
+            alphabet: elements.into_iter().map(Rc::from).collect(),
+            states,
+            initial_state: self.initial_state,
+        }
+    }
+
     /// Constructs the union of two DFAs, that is, a new DFA that accepts exactly those strings that are accepted by
     /// the first, second or both DFAs. This returns `None` if and only if the alphabets of the two DFAs are unequal
     /// (not considering ordering).
@@ -348,7 +850,7 @@ impl Dfa {
     /// assert!(any.accepts_graphemes("bb"));
     /// ```
     pub fn union(&self, other: &Self) -> Option<Self> {
-        self.product_construction(other, |s1, s2| s1.accepting || s2.accepting)
+        self.product_construction(other, |s1, s2| s1 || s2).ok()
     }
 
     /// Constructs the intersection of two DFAs, that is, a new DFA that accepts exactly those strings that are accepted
@@ -379,7 +881,65 @@ impl Dfa {
     /// assert!(!both.accepts_graphemes("bb"));
     /// ```
     pub fn intersection(&self, other: &Self) -> Option<Self> {
-        self.product_construction(other, |s1, s2| s1.accepting && s2.accepting)
+        self.product_construction(other, |s1, s2| s1 && s2).ok()
+    }
+
+    /// Checks if some string is accepted by both this DFA and `other`, that is, if their intersection is non-empty,
+    /// or gives an error if the alphabets of the two DFAs differ (not considering ordering). This is the same as
+    /// `self.intersection(other).unwrap().has_reachable_accepting_state()`, but the product is explored on the fly with
+    /// a breadth-first search which stops as soon as a pair of accepting states is reached, so the product DFA is never
+    /// built.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let ends_with_a = "
+    ///      a b
+    /// -> n y n
+    ///  * y y n";
+    /// let starts_with_b = "
+    ///      a b
+    /// -> i n y
+    ///    n n n
+    ///  * y y y";
+    /// let only_b = "
+    ///      a b
+    /// -> * y n y
+    ///      n n n";
+    /// let ends_with_a: Dfa = parser::dfa(ends_with_a).unwrap().try_into().unwrap();
+    /// let starts_with_b: Dfa = parser::dfa(starts_with_b).unwrap().try_into().unwrap();
+    /// let only_b: Dfa = parser::dfa(only_b).unwrap().try_into().unwrap();
+    /// assert_eq!(ends_with_a.intersects(&starts_with_b), Ok(true));
+    /// assert_eq!(ends_with_a.intersects(&only_b), Ok(false));
+    /// ```
+    pub fn intersects(&self, other: &Dfa) -> Result<bool, AlphabetMismatch> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return Err(AlphabetMismatch);
+        }
+
+        // other_idx[i] is the index of the i'th element of our alphabet in the alphabet of 'other'
+        let other_idx = self
+            .alphabet
+            .iter()
+            .map(|elem| other.alphabet.iter().position(|e| e == elem).unwrap())
+            .collect::<Vec<_>>();
+
+        let initial = (self.initial_state, other.initial_state);
+        let mut visited = HashSet::from([initial]);
+        let mut queue = VecDeque::from([initial]);
+        while let Some((s1, s2)) = queue.pop_front() {
+            if self.states[s1].accepting && other.states[s2].accepting {
+                return Ok(true);
+            }
+            for (elem, &target) in self.states[s1].transitions.iter().enumerate() {
+                let next = (target, other.states[s2].transitions[other_idx[elem]]);
+                if visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        Ok(false)
     }
 
     /// Constructs the difference of two DFAs, that is, a new DFA that accepts exactly those strings that are accepted
@@ -410,7 +970,7 @@ impl Dfa {
     /// assert!(!a_not_b.accepts_graphemes("bb"));
     /// ```
     pub fn difference(&self, other: &Self) -> Option<Self> {
-        self.product_construction(other, |s1, s2| s1.accepting && !s2.accepting)
+        self.product_construction(other, |s1, s2| s1 && !s2).ok()
     }
 
     /// Constructs the symmetric difference of two DFAs, that is, a new DFA that accepts exactly those strings that are
@@ -441,22 +1001,251 @@ impl Dfa {
     /// assert!(a_or_b.accepts_graphemes("bb"));
     /// ```
     pub fn symmetric_difference(&self, other: &Self) -> Option<Self> {
-        self.product_construction(other, |s1, s2| s1.accepting != s2.accepting)
+        self.product_construction(other, |s1, s2| s1 != s2).ok()
+    }
+
+    /// Constructs the right quotient of the language of this DFA by the language of `other`, that is, a DFA accepting
+    /// the strings `u` for which there is some `v` accepted by `other` such that `uv` is accepted by this DFA. The
+    /// result has the same states and transitions as this DFA, where a state is accepting if some string accepted by
+    /// `other` leads from it to an accepting state. Errors if the alphabets of the two DFAs differ (not considering
+    /// ordering).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// // a*b
+    /// let a_then_b: Dfa = parser::dfa("
+    ///          a    b
+    /// -> s     s    end
+    ///  * end   trap trap
+    ///    trap  trap trap
+    /// ").unwrap().try_into().unwrap();
+    /// let just_b: Dfa = parser::dfa("
+    ///          a    b
+    /// -> s     trap end
+    ///  * end   trap trap
+    ///    trap  trap trap
+    /// ").unwrap().try_into().unwrap();
+    /// // Removing the b at the end of a*b gives a*
+    /// let quotient = a_then_b.right_quotient(&just_b).unwrap();
+    /// assert!(quotient.accepts_graphemes(""));
+    /// assert!(quotient.accepts_graphemes("aaa"));
+    /// assert!(!quotient.accepts_graphemes("ab"));
+    /// ```
+    pub fn right_quotient(&self, other: &Dfa) -> Result<Dfa, AlphabetMismatch> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return Err(AlphabetMismatch);
+        }
+        // other_idx[i] is the index of the i'th element of our alphabet in the alphabet of 'other'
+        let other_idx = self
+            .alphabet
+            .iter()
+            .map(|elem| other.alphabet.iter().position(|e| e == elem).unwrap())
+            .collect::<Vec<_>>();
+
+        // Explore the pairs of states reachable from any state of this DFA together with the initial state of 'other'
+        let mut pairs = (0..self.states.len())
+            .map(|state| (state, other.initial_state))
+            .collect::<Vec<_>>();
+        let mut indices = pairs
+            .iter()
+            .enumerate()
+            .map(|(idx, &pair)| (pair, idx))
+            .collect::<HashMap<_, _>>();
+        let mut predecessors: Vec<Vec<usize>> = vec![vec![]; pairs.len()];
+        let mut next = 0;
+        while next < pairs.len() {
+            let (s1, s2) = pairs[next];
+            for (elem, &other_elem) in other_idx.iter().enumerate() {
+                let pair = (
+                    self.states[s1].transitions[elem],
+                    other.states[s2].transitions[other_elem],
+                );
+                let idx = *indices.entry(pair).or_insert_with(|| {
+                    pairs.push(pair);
+                    predecessors.push(vec![]);
+                    pairs.len() - 1
+                });
+                predecessors[idx].push(next);
+            }
+            next += 1;
+        }
+
+        // Then go backwards from the pairs where both states are accepting
+        let mut to_visit = (0..pairs.len())
+            .filter(|&idx| {
+                let (s1, s2) = pairs[idx];
+                self.states[s1].accepting && other.states[s2].accepting
+            })
+            .collect::<Vec<_>>();
+        let mut good = vec![false; pairs.len()];
+        to_visit.iter().for_each(|&idx| good[idx] = true);
+        while let Some(idx) = to_visit.pop() {
+            for &pred in &predecessors[idx] {
+                if !good[pred] {
+                    good[pred] = true;
+                    to_visit.push(pred);
+                }
+            }
+        }
+
+        let mut quotient = self.clone();
+        for (idx, state) in quotient.states.iter_mut().enumerate() {
+            // The pairs were numbered with the pairs (state, initial state of 'other') first
+            state.accepting = good[idx];
+        }
+        Ok(quotient)
+    }
+
+    /// Constructs the union of two DFAs over the union of their alphabets. Both DFAs are first extended to the combined
+    /// alphabet, where each symbol a DFA lacks leads to a new non-accepting trap state, and then the union is
+    /// constructed as with [Dfa::union]. Unlike [Dfa::union] this never fails.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let only_a_b = "
+    ///      a b
+    /// -> * s s s";
+    /// let only_b_c = "
+    ///      b c
+    /// -> * s s s";
+    /// let only_a_b: Dfa = parser::dfa(only_a_b).unwrap().try_into().unwrap();
+    /// let only_b_c: Dfa = parser::dfa(only_b_c).unwrap().try_into().unwrap();
+    ///
+    /// let either = only_a_b.union_extend(&only_b_c);
+    /// assert_eq!(either.alphabet().len(), 3);
+    /// assert!(either.accepts_graphemes("abba"));
+    /// assert!(either.accepts_graphemes("bccb"));
+    /// assert!(!either.accepts_graphemes("abc"));
+    /// ```
+    pub fn union_extend(&self, other: &Self) -> Self {
+        let (first, second) = self.extend_to_common_alphabet(other);
+        first
+            .union(&second)
+            .expect("Both DFAs should have the same alphabet")
+    }
+
+    /// Constructs the intersection of two DFAs over the union of their alphabets. Both DFAs are first extended to the
+    /// combined alphabet, where each symbol a DFA lacks leads to a new non-accepting trap state, and then the
+    /// intersection is constructed as with [Dfa::intersection]. Unlike [Dfa::intersection] this never fails.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let only_a_b = "
+    ///      a b
+    /// -> * s s s";
+    /// let only_b_c = "
+    ///      b c
+    /// -> * s s s";
+    /// let only_a_b: Dfa = parser::dfa(only_a_b).unwrap().try_into().unwrap();
+    /// let only_b_c: Dfa = parser::dfa(only_b_c).unwrap().try_into().unwrap();
+    ///
+    /// let both = only_a_b.intersection_extend(&only_b_c);
+    /// assert_eq!(both.alphabet().len(), 3);
+    /// assert!(both.accepts_graphemes("bbb"));
+    /// assert!(!both.accepts_graphemes("ab"));
+    /// assert!(!both.accepts_graphemes("bc"));
+    /// ```
+    pub fn intersection_extend(&self, other: &Self) -> Self {
+        let (first, second) = self.extend_to_common_alphabet(other);
+        first
+            .intersection(&second)
+            .expect("Both DFAs should have the same alphabet")
+    }
+
+    fn extend_to_common_alphabet(&self, other: &Self) -> (Self, Self) {
+        let alphabet = self
+            .alphabet
+            .iter()
+            .chain(
+                other
+                    .alphabet
+                    .iter()
+                    .filter(|elem| !self.alphabet.contains(elem)),
+            )
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut first = self.clone();
+        first.extend_alphabet(&alphabet);
+        let mut second = other.clone();
+        second.extend_alphabet(&alphabet);
+        (first, second)
     }
 
     /// Constructs a new DFA from two DFAs using the product construction. That is a new DFA with states corresponding
     /// to both the state the first DFA and the second DFA would be in on any given input. If that state is an accepting
-    /// state or not is given by the `combinator` function, combining the state from the first parser and the second
-    /// parser. `self.product_construction(other, |s1, s2| s1.is_accepting() && s2.is_accepting())` corresponds to
-    /// the intersection between the two.
+    /// state or not is given by the `accept` function, from whether the state of the first DFA and the state of the
+    /// second DFA are accepting. `self.product_construction(other, |s1, s2| s1 && s2)` corresponds to the intersection
+    /// between the two, and `self.product_construction(other, |s1, s2| !s1 || s2)` to the strings which are accepted by
+    /// `other` if they are accepted by `self`. This returns an error if the alphabets of the two DFAs differ (not
+    /// considering ordering).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::{AlphabetMismatch, Dfa};
+    ///
+    /// let ends_with_a: Dfa = parser::dfa("a b \n -> n y n \n * y y n").unwrap().try_into().unwrap();
+    /// let even_length: Dfa = parser::dfa("a b \n -> * e o o \n o e e").unwrap().try_into().unwrap();
+    /// // Not both ending with a and of even length
+    /// let nand = ends_with_a.product_construction(&even_length, |s1, s2| !(s1 && s2)).unwrap();
+    /// assert!(nand.accepts_graphemes("a"));
+    /// assert!(nand.accepts_graphemes("ab"));
+    /// assert!(!nand.accepts_graphemes("ba"));
+    ///
+    /// let other: Dfa = parser::dfa("a c \n -> * s s s").unwrap().try_into().unwrap();
+    /// assert_eq!(ends_with_a.product_construction(&other, |s1, s2| s1 && s2), Err(AlphabetMismatch));
+    /// ```
     pub fn product_construction(
         &self,
-        other: &Self,
-        mut combinator: impl FnMut(&DfaState, &DfaState) -> bool,
-    ) -> Option<Self> {
+        other: &Dfa,
+        accept: impl Fn(bool, bool) -> bool,
+    ) -> Result<Dfa, AlphabetMismatch> {
+        self.product_with_mapping(other, accept)
+            .map(|(product, _)| product)
+    }
+
+    /// Like [Dfa::product_construction], but also gives the pair of states `(self_state, other_state)` that each state
+    /// of the product corresponds to, indexed by the states of the product. Whether a state is accepting is given by
+    /// `accept`, from whether the states of the pair are accepting. This returns an error if the alphabets of the two
+    /// DFAs differ (not considering ordering).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let ends_with_a = "
+    ///      a b
+    /// -> n y n
+    ///  * y y n";
+    /// let odd_length = "
+    ///      a b
+    /// -> e o o
+    ///  * o e e";
+    /// let ends_with_a: Dfa = parser::dfa(ends_with_a).unwrap().try_into().unwrap();
+    /// let odd_length: Dfa = parser::dfa(odd_length).unwrap().try_into().unwrap();
+    ///
+    /// let (both, pairs) = ends_with_a.product_with_mapping(&odd_length, |s1, s2| s1 && s2).unwrap();
+    /// assert!(both.accepts(&["b", "b", "a"]));
+    /// assert!(!both.accepts(&["b", "a"]));
+    /// // The product accepts exactly in the pair of the two accepting states
+    /// for (state, &(s1, s2)) in both.states().iter().zip(&pairs) {
+    ///     assert_eq!(state.is_accepting(), (s1, s2) == (1, 1));
+    /// }
+    /// assert_eq!(pairs[both.initial_state_index()], (0, 0));
+    /// ```
+    pub fn product_with_mapping(
+        &self,
+        other: &Dfa,
+        accept: impl Fn(bool, bool) -> bool,
+    ) -> Result<(Dfa, Vec<(usize, usize)>), AlphabetMismatch> {
         //if the alphabets are different, they aren't equivalent
         if !alphabet_equal(&self.alphabet, &other.alphabet) {
-            return None;
+            return Err(AlphabetMismatch);
         }
 
         // initially, we explore the (pair of) initial states
@@ -486,7 +1275,10 @@ impl Dfa {
 
             state_data.push((
                 (s1.current_state_idx(), s2.current_state_idx()),
-                combinator(s1.current_state().unwrap(), s2.current_state().unwrap()),
+                accept(
+                    s1.current_state().unwrap().accepting,
+                    s2.current_state().unwrap().accepting,
+                ),
                 transition_list,
             ));
         }
@@ -524,6 +1316,7 @@ impl Dfa {
         let initial_state = *rev_state_idx_map
             .get(&(q1, q2))
             .expect("Initial state should have an index");
+        let pairs = state_data.iter().map(|(states, _, _)| *states).collect();
 
         let states = state_data
             .into_iter()
@@ -544,77 +1337,443 @@ impl Dfa {
                     .collect(),
             })
             .collect::<Vec<_>>();
-        Some(Dfa {
+        let product = Dfa {
             alphabet: self.alphabet.clone(),
             states,
             initial_state,
-        })
+        };
+        Ok((product, pairs))
     }
 
-    /// Minimizes this DFA by first removing all unreachable states and then merging non-distinguishable states.
+    /// Like [Dfa::product_construction], but explores the pairs of states in parallel, which pays off for large DFAs.
+    /// The pairs are explored breadth-first, one layer at a time, where the transitions from all pairs in a layer are
+    /// computed in parallel and the newly found pairs are then numbered in order. The result is thus deterministic,
+    /// with the states in breadth-first order from the initial pair, and equivalent to the result of
+    /// [Dfa::product_construction] (though the states may be ordered differently).
+    ///
     /// ```
     /// use dandy::parser;
     /// use dandy::dfa::Dfa;
     ///
-    /// // This DFA simply accepts everything and can be minimized to just 1 state
-    /// let accepts_everything = "
-    ///        a b c
-    /// -> * y y y y
-    ///      a b c d
-    ///      b c d a
-    ///    * c a b c
-    ///      d d d c
-    /// ";
-    /// let mut dfa: Dfa = parser::dfa(accepts_everything).unwrap().try_into().unwrap();
-    /// dfa.minimize();
-    /// assert_eq!(dfa.states().len(), 1);
+    /// let ends_with_a: Dfa = parser::dfa("a b \n -> n y n \n * y y n").unwrap().try_into().unwrap();
+    /// let even_length: Dfa = parser::dfa("a b \n -> * e o o \n o e e").unwrap().try_into().unwrap();
+    /// let both = ends_with_a
+    ///     .par_product_construction(&even_length, |s1, s2| s1 && s2)
+    ///     .unwrap();
+    /// assert!(both.equivalent_to(&ends_with_a.intersection(&even_length).unwrap()));
+    /// assert!(both.accepts_graphemes("ba"));
+    /// assert!(!both.accepts_graphemes("aba"));
     /// ```
-    pub fn minimize(&mut self) {
-        self.remove_unreachable_states();
-        self.merge_nondistinguishable_states();
-    }
+    #[cfg(feature = "rayon")]
+    pub fn par_product_construction(
+        &self,
+        other: &Dfa,
+        accept: impl Fn(bool, bool) -> bool,
+    ) -> Result<Dfa, AlphabetMismatch> {
+        use rayon::prelude::*;
 
-    /// Merges the non-distinguishable states of this DFA such that every set of multiple non-distinguishable states
-    /// become just one. Which of multiple non-distinguishable states is left over is non-deterministic
-    pub fn merge_nondistinguishable_states(&mut self) {
-        let mapper = self
-            .state_equivalence_classes_idx()
-            .into_iter()
-            .flat_map(|set| {
-                debug_assert!(!set.is_empty(), "Should not have empty equivalence classes");
-                let mut iter = set.into_iter();
-                let new = iter.next();
-                // safety: iter.map is lazy, so body is only executed if there are elements,
-                // which means new must be non-optional
-                iter.map(move |old| (old, unsafe { new.unwrap_unchecked() }))
-            })
-            .collect::<HashMap<_, _>>();
-        let map = |idx| mapper.get(&idx).copied();
-        self.remap_transitions(map);
-        if let Some(new_initial) = map(self.initial_state) {
-            self.initial_state = new_initial;
-            self.states[new_initial].initial = true;
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return Err(AlphabetMismatch);
         }
-        let to_remove = mapper.into_keys().collect();
-        self.remove_states(to_remove);
+
+        // The states aren't thread safe (they contain `Rc`s), so only the transition tables are shared between
+        // threads, with the transitions of the other DFA in the order of the alphabet of this DFA
+        let first_table = self
+            .states
+            .iter()
+            .map(|state| state.transitions.clone())
+            .collect::<Vec<_>>();
+        let translation = self
+            .alphabet
+            .iter()
+            .map(|elem| other.alphabet.iter().position(|e| e == elem).unwrap())
+            .collect::<Vec<_>>();
+        let second_table = other
+            .states
+            .iter()
+            .map(|state| {
+                translation
+                    .iter()
+                    .map(|&idx| state.transitions[idx])
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let initial = (self.initial_state, other.initial_state);
+        let mut indices = HashMap::from([(initial, 0)]);
+        let mut pairs = vec![initial];
+        let mut transitions = vec![];
+        let mut layer = 0..1;
+        while !layer.is_empty() {
+            let successors = pairs[layer.clone()]
+                .par_iter()
+                .map(|&(s1, s2)| {
+                    first_table[s1]
+                        .iter()
+                        .zip(&second_table[s2])
+                        .map(|(&t1, &t2)| (t1, t2))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            let next_layer = pairs.len();
+            for successors in successors {
+                let row = successors
+                    .into_iter()
+                    .map(|pair| {
+                        *indices.entry(pair).or_insert_with(|| {
+                            pairs.push(pair);
+                            pairs.len() - 1
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                transitions.push(row);
+            }
+            layer = next_layer..pairs.len();
+        }
+
+        // Name the states after their pairs if those names are unique, and by their indices otherwise
+        let mut names = pairs
+            .iter()
+            .map(|&(s1, s2)| {
+                Rc::from(format!(
+                    "({},{})",
+                    self.states[s1].name, other.states[s2].name
+                ))
+            })
+            .collect::<Vec<Rc<str>>>();
+        if names.iter().collect::<HashSet<_>>().len() < names.len() {
+            names = (0..pairs.len())
+                .map(|idx| Rc::from(idx.to_string()))
+                .collect();
+        }
+
+        let states = pairs
+            .iter()
+            .zip(names)
+            .zip(transitions)
+            .enumerate()
+            .map(|(idx, ((&(s1, s2), name), transitions))| DfaState {
+                name,
+                initial: idx == 0,
+                accepting: accept(self.states[s1].accepting, other.states[s2].accepting),
+                transitions,
+            })
+            .collect();
+        Ok(Dfa {
+            alphabet: self.alphabet.clone(),
+            states,
+            initial_state: 0,
+        })
+    }
+
+    /// Constructs the intersection of any number of DFAs, that is, a new DFA that accepts exactly those strings that
+    /// are accepted by all of the DFAs. This is done with a single product construction over all the DFAs at once,
+    /// exploring only reachable tuples of states, rather than by repeated use of [Dfa::intersection]. It errors if no
+    /// DFAs are given, or if some DFA has a different alphabet than the first DFA (not considering ordering), giving
+    /// the index of the first such DFA.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::{Dfa, ProductAllError};
+    ///
+    /// let contains = |c: &str| -> Dfa {
+    ///     let input = match c {
+    ///         "a" => "a b c \n -> n y n n \n * y y y y",
+    ///         "b" => "a b c \n -> n n y n \n * y y y y",
+    ///         _ => "a b c \n -> n n n y \n * y y y y",
+    ///     };
+    ///     parser::dfa(input).unwrap().try_into().unwrap()
+    /// };
+    /// let all = Dfa::intersection_all(&[contains("a"), contains("b"), contains("c")]).unwrap();
+    /// assert!(all.accepts_graphemes("cab"));
+    /// assert!(!all.accepts_graphemes("abba"));
+    ///
+    /// let other_alphabet: Dfa = parser::dfa("a b \n -> * s s s").unwrap().try_into().unwrap();
+    /// let err = Dfa::intersection_all(&[contains("a"), contains("b"), other_alphabet]).unwrap_err();
+    /// assert_eq!(err, ProductAllError::AlphabetMismatch(2));
+    /// assert_eq!(Dfa::intersection_all(&[]).unwrap_err(), ProductAllError::Empty);
+    /// ```
+    pub fn intersection_all(dfas: &[Dfa]) -> Result<Dfa, ProductAllError> {
+        Self::product_construction_all(dfas, |states| states.iter().all(|s| s.accepting))
+    }
+
+    /// Constructs the union of any number of DFAs, that is, a new DFA that accepts exactly those strings that are
+    /// accepted by at least one of the DFAs. This is done with a single product construction, and errors in the same
+    /// way as [Dfa::intersection_all].
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let ends_with = |c: &str| -> Dfa {
+    ///     let input = match c {
+    ///         "a" => "a b c \n -> n y n n \n * y y n n",
+    ///         _ => "a b c \n -> n n y n \n * y n y n",
+    ///     };
+    ///     parser::dfa(input).unwrap().try_into().unwrap()
+    /// };
+    /// let any = Dfa::union_all(&[ends_with("a"), ends_with("b")]).unwrap();
+    /// assert!(any.accepts_graphemes("cba"));
+    /// assert!(any.accepts_graphemes("cab"));
+    /// assert!(!any.accepts_graphemes("abc"));
+    /// ```
+    pub fn union_all(dfas: &[Dfa]) -> Result<Dfa, ProductAllError> {
+        Self::product_construction_all(dfas, |states| states.iter().any(|s| s.accepting))
+    }
+
+    /// Like [Dfa::product_construction], but for any number of DFAs. The states of the new DFA correspond to tuples of
+    /// states of the given DFAs, and `combinator` decides if a tuple is accepting. The alphabet is the alphabet of the
+    /// first DFA, and all other DFAs must have the same alphabet (not considering ordering).
+    pub fn product_construction_all(
+        dfas: &[Dfa],
+        mut combinator: impl FnMut(&[&DfaState]) -> bool,
+    ) -> Result<Dfa, ProductAllError> {
+        let first = dfas.first().ok_or(ProductAllError::Empty)?;
+        if let Some(idx) = dfas
+            .iter()
+            .position(|dfa| !alphabet_equal(&first.alphabet, &dfa.alphabet))
+        {
+            return Err(ProductAllError::AlphabetMismatch(idx));
+        }
+
+        // For each DFA, maps the index of an element in the first alphabet to its index in the DFA's own alphabet
+        let translations = dfas
+            .iter()
+            .map(|dfa| {
+                first
+                    .alphabet
+                    .iter()
+                    .map(|elem| dfa.alphabet.iter().position(|e| e == elem).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let initial = dfas.iter().map(|dfa| dfa.initial_state).collect::<Vec<_>>();
+        let mut indices = HashMap::from([(initial.clone(), 0)]);
+        let mut tuples = vec![initial];
+        let mut transitions = vec![];
+        while transitions.len() < tuples.len() {
+            let tuple = tuples[transitions.len()].clone();
+            let state_transitions = (0..first.alphabet.len())
+                .map(|elem| {
+                    let next = tuple
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &s)| dfas[i].states[s].transitions[translations[i][elem]])
+                        .collect::<Vec<_>>();
+                    *indices.entry(next.clone()).or_insert_with(|| {
+                        tuples.push(next);
+                        tuples.len() - 1
+                    })
+                })
+                .collect::<Vec<_>>();
+            transitions.push(state_transitions);
+        }
+
+        // Try to name the states after the tuples, and use indices if that gives duplicate names
+        let mut names = tuples
+            .iter()
+            .map(|tuple| {
+                let names = tuple
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &s)| &*dfas[i].states[s].name)
+                    .collect::<Vec<_>>();
+                Rc::from(format!("({})", names.join(",")))
+            })
+            .collect::<Vec<Rc<str>>>();
+        if names.iter().collect::<HashSet<_>>().len() < names.len() {
+            names = (0..tuples.len())
+                .map(|idx| Rc::from(idx.to_string()))
+                .collect();
+        }
+
+        let states = tuples
+            .iter()
+            .zip(names)
+            .zip(transitions)
+            .enumerate()
+            .map(|(idx, ((tuple, name), transitions))| {
+                let states = tuple
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &s)| &dfas[i].states[s])
+                    .collect::<Vec<_>>();
+                DfaState {
+                    name,
+                    initial: idx == 0,
+                    accepting: combinator(&states),
+                    transitions,
+                }
+            })
+            .collect();
+
+        Ok(Dfa {
+            alphabet: first.alphabet.clone(),
+            states,
+            initial_state: 0,
+        })
+    }
+
+    /// Minimizes this DFA by first removing all unreachable states and then merging non-distinguishable states.
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// // This DFA simply accepts everything and can be minimized to just 1 state
+    /// let accepts_everything = "
+    ///        a b c
+    /// -> * y y y y
+    ///      a b c d
+    ///      b c d a
+    ///    * c a b c
+    ///      d d d c
+    /// ";
+    /// let mut dfa: Dfa = parser::dfa(accepts_everything).unwrap().try_into().unwrap();
+    /// dfa.minimize();
+    /// assert_eq!(dfa.states().len(), 1);
+    /// ```
+    pub fn minimize(&mut self) {
+        self.remove_unreachable_states();
+        self.merge_nondistinguishable_states();
+    }
+
+    /// Computes the index of the [Myhill–Nerode](https://en.wikipedia.org/wiki/Myhill%E2%80%93Nerode_theorem) relation
+    /// of the language of this DFA, that is, the number of equivalence classes of strings over the alphabet where two
+    /// strings are equivalent if no suffix distinguishes them. This is the number of states of the
+    /// [minimized](Dfa::minimize) DFA (including a trap state, if there is one), but is computed without modifying
+    /// this DFA, by counting the classes of [non-distinguishable](Dfa::state_equivalence_classes_idx) states which
+    /// contain a reachable state.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// // An even number of a's
+    /// let dfa = parser::regex("(b*ab*a)*b*").unwrap().to_dfa();
+    /// assert_eq!(dfa.nerode_index(), 2);
+    /// // The classes are ε, a, ab and the strings which can't be extended to ab
+    /// assert_eq!(parser::regex("ab").unwrap().to_dfa().nerode_index(), 4);
+    /// ```
+    pub fn nerode_index(&self) -> usize {
+        let partition = self.state_equivalence_classes_idx();
+        self.reachable_states_iter()
+            .map(|idx| partition.class_of(idx))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Reorders the states of this DFA in breadth-first order from the initial state (following the transitions in the
+    /// order of the alphabet), and renames them `s0`, `s1` and so on in that order. Unreachable states are placed
+    /// last, in their previous order. After minimizing, this gives the same table for all equivalent DFAs with the
+    /// alphabet in the same order, since the minimal DFA is unique up to the names and order of the states.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let mut dfa: Dfa = parser::dfa("
+    ///          a    b
+    ///  * odd   even even
+    /// -> even  odd  odd
+    /// ").unwrap().try_into().unwrap();
+    /// dfa.canonicalize();
+    /// let names = dfa.states().iter().map(|s| s.name()).collect::<Vec<_>>();
+    /// assert_eq!(names, vec!["s0", "s1"]);
+    /// assert!(dfa.states()[0].is_initial() && !dfa.states()[0].is_accepting());
+    /// assert_eq!(dfa.states()[0].transitions(), &[1, 1]);
+    /// ```
+    pub fn canonicalize(&mut self) {
+        let mut order = self.reachable_states_iter().collect::<Vec<_>>();
+        let mut reachable = vec![false; self.states.len()];
+        order.iter().for_each(|&state| reachable[state] = true);
+        order.extend((0..self.states.len()).filter(|&state| !reachable[state]));
+
+        // order[new] = old, so position[old] = new
+        let mut position = vec![0; order.len()];
+        order
+            .iter()
+            .enumerate()
+            .for_each(|(new, &old)| position[old] = new);
+
+        let mut old_states = mem::take(&mut self.states)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        self.states = order
+            .iter()
+            .enumerate()
+            .map(|(new, &old)| {
+                let mut state = old_states[old].take().unwrap();
+                state.name = Rc::from(format!("s{new}"));
+                state
+                    .transitions
+                    .iter_mut()
+                    .for_each(|trans| *trans = position[*trans]);
+                state
+            })
+            .collect();
+        self.initial_state = 0;
+    }
+
+    /// Merges the non-distinguishable states of this DFA such that every set of multiple non-distinguishable states
+    /// become just one. The state with the lowest index of each set is left over, see [Partition::representative]
+    pub fn merge_nondistinguishable_states(&mut self) {
+        let partition = self.state_equivalence_classes_idx();
+        let mapper = (0..self.states.len())
+            .map(|state| (state, partition.representative(partition.class_of(state))))
+            .filter(|(old, new)| old != new)
+            .collect::<HashMap<_, _>>();
+        let map = |idx| mapper.get(&idx).copied();
+        self.remap_transitions(map);
+        if let Some(new_initial) = map(self.initial_state) {
+            self.initial_state = new_initial;
+            self.states[new_initial].initial = true;
+        }
+        let to_remove = mapper.into_keys().collect();
+        self.remove_states(to_remove);
     }
 
     /// Gives the equivalence classes of the states of this DFA, which is the sets of non-distinguishable states
     pub fn state_equivalence_classes(&self) -> Vec<Vec<&DfaState>> {
         self.state_equivalence_classes_idx()
-            .into_iter()
+            .classes()
+            .iter()
             .map(|class| {
                 class
-                    .into_iter()
-                    .map(|state| &self.states[state])
+                    .iter()
+                    .map(|&state| &self.states[state])
                     .collect::<Vec<_>>()
             })
             .collect()
     }
 
-    /// Gives the equivalence classes of the states of this DFA, which is the sets of non-distinguishable states, by
-    /// their indices
-    pub fn state_equivalence_classes_idx(&self) -> Vec<HashSet<usize>> {
+    /// Gives the equivalence classes of the states of this DFA, which is the sets of non-distinguishable states, as a
+    /// [Partition] of the state indices. For a minimized DFA, these are the
+    /// [Myhill–Nerode](https://en.wikipedia.org/wiki/Myhill%E2%80%93Nerode_theorem) classes of its language.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        a b
+    /// -> s₀ s₁ s₂
+    ///  * s₁ s₁ s₂
+    ///    s₂ s₃ s₂
+    ///  * s₃ s₃ s₀
+    /// ").unwrap().try_into().unwrap();
+    /// let partition = dfa.state_equivalence_classes_idx();
+    /// assert_eq!(partition.classes(), &[vec![0, 2], vec![1, 3]]);
+    /// assert_eq!(partition.class_of(3), 1);
+    /// assert_eq!(partition.representative(1), 1);
+    /// ```
+    pub fn state_equivalence_classes_idx(&self) -> Partition {
+        Partition::new(self.states.len(), self.state_equivalence_class_sets())
+    }
+
+    /// Hopcroft's algorithm for computing the equivalence classes of the states
+    fn state_equivalence_class_sets(&self) -> Vec<HashSet<usize>> {
         let (finals, nonfinals): (HashSet<usize>, HashSet<usize>) =
             (0..self.states.len()).partition(|&idx| self.states[idx].accepting);
         if finals.is_empty() {
@@ -668,6 +1827,51 @@ impl Dfa {
         p
     }
 
+    /// Removes the states for which `keep` (given the index and the state) returns `false`, and reindexes the remaining
+    /// states, keeping their order. Since every state of a DFA must have a transition upon every element of the
+    /// alphabet, this errors if a kept state has a transition to a removed state, as well as if the initial state
+    /// would be removed. On error, the DFA is left unchanged.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::{Dfa, RetainError};
+    ///
+    /// let mut dfa: Dfa = parser::dfa("
+    ///        a  b
+    ///    s₀ s₀ s₁
+    /// -> s₁ s₂ s₁
+    ///  * s₂ s₁ s₂
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.retain_states(|_, state| state.is_accepting()), Err(RetainError::InitialStateRemoved(1)));
+    /// assert_eq!(dfa.retain_states(|idx, _| idx != 2), Err(RetainError::TransitionToRemovedState(1, 2)));
+    ///
+    /// dfa.retain_states(|_, state| state.name() != "s₀").unwrap();
+    /// assert_eq!(dfa.states().len(), 2);
+    /// assert_eq!(dfa.initial_state_index(), 0);
+    /// assert_eq!(dfa.states()[0].transitions(), &[1, 0]);
+    /// ```
+    pub fn retain_states(
+        &mut self,
+        keep: impl Fn(usize, &DfaState) -> bool,
+    ) -> Result<(), RetainError> {
+        let kept = self
+            .states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| keep(idx, state))
+            .collect::<Vec<_>>();
+        if !kept[self.initial_state] {
+            return Err(RetainError::InitialStateRemoved(self.initial_state));
+        }
+        for (idx, state) in self.states.iter().enumerate().filter(|&(idx, _)| kept[idx]) {
+            if let Some(&target) = state.transitions.iter().find(|&&target| !kept[target]) {
+                return Err(RetainError::TransitionToRemovedState(idx, target));
+            }
+        }
+        self.remove_states((0..kept.len()).filter(|&idx| !kept[idx]).collect());
+        Ok(())
+    }
+
     /// Removes the unreachable states of this automata, leaving only states actually reaachable by some input
     pub fn remove_unreachable_states(&mut self) {
         let states = self.unreachable_state_idx().into_iter().collect();
@@ -682,6 +1886,77 @@ impl Dfa {
             .collect()
     }
 
+    /// Removes the dead states of this DFA, that is, the states from which no accepting state can be reached. Since a
+    /// DFA has a transition from every state upon every element of the alphabet, the dead states are only removed
+    /// entirely if no other state has a transition to any of them. Otherwise, they are merged into one trap state
+    /// (the dead state with the lowest index, or the initial state if it is dead) with transitions only to itself. This
+    /// doesn't change the language of the DFA. To get rid of the trap state as well, convert the DFA
+    /// [to a NFA](Dfa::to_nfa) and use [Nfa::remove_dead_states].
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa = "
+    ///          a     b
+    /// -> s₀    s₁    trap₁
+    ///  * s₁    trap₁ trap₂
+    ///    trap₁ trap₂ trap₁
+    ///    trap₂ trap₁ trap₁
+    /// ";
+    /// let mut dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    /// dfa.remove_dead_states();
+    /// assert_eq!(dfa.states().len(), 3);
+    /// assert_eq!(dfa.states()[2].name(), "trap₁");
+    /// assert_eq!(dfa.states()[2].transitions(), &[2, 2]);
+    /// ```
+    pub fn remove_dead_states(&mut self) {
+        let dead = self.dead_state_idx();
+        let needs_trap = self.states.iter().enumerate().any(|(idx, state)| {
+            !dead.contains(&idx) && state.transitions.iter().any(|t| dead.contains(t))
+        });
+        let trap = if dead.contains(&self.initial_state) {
+            Some(self.initial_state)
+        } else if needs_trap {
+            dead.iter().copied().min()
+        } else {
+            None
+        };
+        if let Some(trap) = trap {
+            self.remap_transitions(|idx| dead.contains(&idx).then_some(trap));
+        }
+        let to_remove = dead.into_iter().filter(|&idx| Some(idx) != trap).collect();
+        self.remove_states(to_remove);
+    }
+
+    /// Finds the dead states, that is, all states from which no accepting state can be reached, and returns them as
+    /// indices
+    pub fn dead_state_idx(&self) -> HashSet<usize> {
+        let mut predecessors = vec![vec![]; self.states.len()];
+        for (idx, state) in self.states.iter().enumerate() {
+            for &target in &state.transitions {
+                predecessors[target].push(idx);
+            }
+        }
+        let mut live = self
+            .states
+            .iter()
+            .map(|state| state.accepting)
+            .collect::<Vec<_>>();
+        let mut queue = (0..self.states.len())
+            .filter(|&idx| live[idx])
+            .collect::<Vec<_>>();
+        while let Some(state) = queue.pop() {
+            for &pred in &predecessors[state] {
+                if !live[pred] {
+                    live[pred] = true;
+                    queue.push(pred);
+                }
+            }
+        }
+        (0..self.states.len()).filter(|&idx| !live[idx]).collect()
+    }
+
     /// Finds the unreachable states, that is, all states that cannot be reached by any input to the automata, and
     /// returns them as indices
     pub fn unreachable_state_idx(&self) -> HashSet<usize> {
@@ -694,20 +1969,143 @@ impl Dfa {
     /// Checks if this DFA has an accepting state that is reachable from the initial state, that is, if it has some
     /// input which it accepts
     pub fn has_reachable_accepting_state(&self) -> bool {
-        // Use _idx to not allocate Vec
-        self.reachable_state_idx()
-            .iter()
-            .any(|idx| self.states[*idx].accepting)
+        self.first_reachable_accepting_state().is_some()
+    }
+
+    /// Finds an accepting state that is reachable from the initial state, as a witness that this DFA accepts some
+    /// input. The states are searched in breadth-first order from the initial state (as in
+    /// [Dfa::reachable_states_iter]), so the state found is one of the accepting states closest to the initial state.
+    /// Returns `None` if no accepting state is reachable, that is, if the language of this DFA is empty.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        a  b
+    ///  * s₀ s₀ s₀
+    /// -> s₁ s₂ s₁
+    ///    s₂ s₂ s₃
+    ///  * s₃ s₃ s₃
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.first_reachable_accepting_state(), Some(3));
+    ///
+    /// // The only accepting state can't be reached
+    /// let empty: Dfa = parser::dfa("a \n -> s₀ s₀ \n * s₁ s₀").unwrap().try_into().unwrap();
+    /// assert_eq!(empty.first_reachable_accepting_state(), None);
+    /// ```
+    pub fn first_reachable_accepting_state(&self) -> Option<usize> {
+        self.reachable_states_iter()
+            .find(|&idx| self.states[idx].accepting)
+    }
+
+    /// Checks if the language of this DFA is finite, that is, if it only accepts finitely many strings. This is the
+    /// case exactly when there is no cycle among the states that are both reachable and not
+    /// [dead](Dfa::dead_state_idx), so unreachable and dead states are ignored.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// assert!(parser::regex("ab|a(b|c)").unwrap().to_dfa().is_finite());
+    /// assert!(!parser::regex("ab*").unwrap().to_dfa().is_finite());
+    /// // The cycle on ∅ can't lead to acceptance
+    /// assert!(parser::regex("a∅*").unwrap().to_dfa().is_finite());
+    /// ```
+    pub fn is_finite(&self) -> bool {
+        let dead = self.dead_state_idx();
+        if dead.contains(&self.initial_state) {
+            return true;
+        }
+
+        // Depth-first search for a cycle among the states which aren't dead, starting from the initial state (so only
+        // reachable states are visited). A cycle exists if a state on the stack is found again.
+        let mut on_stack = vec![false; self.states.len()];
+        let mut visited = vec![false; self.states.len()];
+        let mut stack = vec![(self.initial_state, 0)];
+        on_stack[self.initial_state] = true;
+        visited[self.initial_state] = true;
+        while let Some((state, next_elem)) = stack.last_mut() {
+            let Some(&target) = self.states[*state].transitions.get(*next_elem) else {
+                on_stack[*state] = false;
+                stack.pop();
+                continue;
+            };
+            *next_elem += 1;
+            if dead.contains(&target) {
+                continue;
+            }
+            if on_stack[target] {
+                return false;
+            }
+            if !visited[target] {
+                visited[target] = true;
+                on_stack[target] = true;
+                stack.push((target, 0));
+            }
+        }
+        true
+    }
+
+    /// Checks if the language of this DFA is cofinite, that is, if it rejects only finitely many strings over its
+    /// alphabet. This is checked by inverting the DFA and checking if [its language is finite](Dfa::is_finite).
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// // Everything but "a"
+    /// let dfa = parser::regex("ε|b(a|b)*|a(a|b)+").unwrap().to_dfa();
+    /// assert!(dfa.is_cofinite());
+    /// assert!(!parser::regex("a*b").unwrap().to_dfa().is_cofinite());
+    /// // The alphabet of a* is only "a", so it accepts everything
+    /// assert!(parser::regex("a*").unwrap().to_dfa().is_cofinite());
+    /// ```
+    pub fn is_cofinite(&self) -> bool {
+        let mut inverted = self.clone();
+        inverted.invert();
+        inverted.is_finite()
     }
 
-    /// Finds the reachable states, that is, all states that can be reached by some input to the automata
+    /// Finds the reachable states, that is, all states that can be reached by some input to the automata, in the order
+    /// of [Dfa::reachable_states_iter]
     pub fn reachable_states(&self) -> Vec<&DfaState> {
-        self.reachable_state_idx()
-            .into_iter()
+        self.reachable_states_iter()
             .map(|idx| &self.states[idx])
             .collect()
     }
 
+    /// Iterates over the indices of the reachable states, in breadth-first order from the initial state. The
+    /// initial state comes first, and the successors of each state are visited in the order of the alphabet, so the
+    /// order is deterministic. The search is lazy, so stopping the iteration early skips the rest of it.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        a  b
+    ///    s₀ s₀ s₀
+    /// -> s₁ s₃ s₂
+    ///    s₂ s₂ s₂
+    ///  * s₃ s₂ s₁
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.reachable_states_iter().collect::<Vec<_>>(), vec![1, 3, 2]);
+    /// ```
+    pub fn reachable_states_iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut seen = vec![false; self.states.len()];
+        seen[self.initial_state] = true;
+        let mut queue = VecDeque::from([self.initial_state]);
+        iter::from_fn(move || {
+            let state = queue.pop_front()?;
+            for &next in &self.states[state].transitions {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+            Some(state)
+        })
+    }
+
     /// Finds the reachable states, that is, all states that can be reached by some input to the automata, and
     /// returns them as indices
     pub fn reachable_state_idx(&self) -> HashSet<usize> {
@@ -766,105 +2164,429 @@ impl Dfa {
         self.remap_transitions(map);
     }
 
-    /// Converts this DFA to a NFA by simply converting each state to a NFA state. All state names
-    /// are kept. This is a cheap operation, involving no clones but some vector allocations due to
-    /// the vectors required by NFA.
-    pub fn to_nfa(self) -> Nfa {
-        let Dfa {
-            alphabet,
-            states,
-            initial_state,
-        } = self;
-        let states = states.into_iter().map(|s| s.into()).collect();
-        Nfa {
-            alphabet,
-            states,
-            initial_state,
+    /// Extends the alphabet of this DFA with the elements of `alphabet` that it doesn't already contain. All
+    /// transitions on the new elements go to a new non-accepting trap state (which is only added if there are any new
+    /// elements), so the language of the DFA is unchanged.
+    pub(crate) fn extend_alphabet(&mut self, alphabet: &[Rc<str>]) {
+        let new_elems = alphabet
+            .iter()
+            .filter(|elem| !self.alphabet.contains(elem))
+            .cloned()
+            .collect::<Vec<_>>();
+        if new_elems.is_empty() {
+            return;
         }
+
+        let trap = self.states.len();
+        let trap_state = DfaState {
+            name: self.fresh_name("trap"),
+            initial: false,
+            accepting: false,
+            transitions: vec![trap; self.alphabet.len()],
+        };
+        self.states.push(trap_state);
+        self.states.iter_mut().for_each(|state| {
+            state
+                .transitions
+                .resize(state.transitions.len() + new_elems.len(), trap)
+        });
+        self.alphabet = self.alphabet.iter().cloned().chain(new_elems).collect();
     }
 
-    /// Checks if this automaton accepts the given string. This is equivalent to getting the
-    /// evaluator, stepping it multiple times and checking if it is accepting
-    pub fn accepts(&self, string: &[&str]) -> bool {
-        let mut eval = self.evaluator();
-        eval.step_multiple(string);
-        eval.is_accepting()
+    fn fresh_name(&self, wanted: &str) -> Rc<str> {
+        if self.states.iter().all(|s| s.name.as_ref() != wanted) {
+            Rc::from(wanted)
+        } else {
+            (0..)
+                .map(|i| Rc::from(i.to_string().as_str()))
+                .find(|n| self.states.iter().all(|s| &s.name != n))
+                .unwrap()
+        }
     }
 
-    /// Checks if this automaton accepts the given string of graphemes, if every grapheme by
-    /// itself is considered as an element of the alphabet. Note that if the alphabet contains
-    /// elements with multiple graphemes, those won't be recognized. To check if there are
-    /// elements with multiple graphemes, see [Dfa::graphemes_only]. A grapheme is defined to be
-    /// one extended unicode grapheme cluster (which may consist of one or many code points).
+    /// Constructs a NFA accepting the `k`:th power of the language of this DFA, that is, the strings which are the
+    /// concatenation of exactly `k` strings accepted by this DFA. This is done by concatenating `k` copies of this
+    /// DFA, where the copy `i` of the state `q` is named `(q,i)`, so the NFA has `k` times as many states as this DFA.
+    /// For `k = 0`, the NFA has a single state and accepts only the empty string.
     ///
     /// ```
     /// use dandy::parser;
     /// use dandy::dfa::Dfa;
     ///
-    /// let input = "
-    ///            0   1
-    /// -> even even odd
-    ///  * odd  even odd
-    /// ";
-    /// let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
-    /// // The alphabet of this DFA contains single graphemes only
-    /// assert!(dfa.graphemes_only());
-    /// assert!(dfa.accepts_graphemes("001")); // Equivalent to dfa.accepts(&["0", "0", "1"])
+    /// // Strings of a's of odd length
+    /// let odd: Dfa = parser::dfa("a \n -> e o \n * o e").unwrap().try_into().unwrap();
+    /// let cube = odd.power(3);
+    /// assert!(cube.accepts_graphemes("aaa"));
+    /// assert!(cube.accepts_graphemes("aaaaa"));
+    /// assert!(!cube.accepts_graphemes("a"));
+    /// assert!(!cube.accepts_graphemes("aaaa"));
+    /// assert_eq!(cube.states().len(), 6);
+    ///
+    /// let empty = odd.power(0);
+    /// assert!(empty.accepts_graphemes(""));
+    /// assert!(!empty.accepts_graphemes("a"));
     /// ```
-    pub fn accepts_graphemes(&self, string: &str) -> bool {
-        let graphemes = string.graphemes(true).collect::<Vec<_>>();
-        let mut eval = self.evaluator();
-        eval.step_multiple(&graphemes);
-        eval.is_accepting()
+    pub fn power(&self, k: usize) -> Nfa {
+        let copy = |i: usize| {
+            let mut nfa = self.clone().to_nfa();
+            for state in &mut nfa.states {
+                state.name = Rc::from(format!("({},{i})", state.name));
+            }
+            nfa
+        };
+        if k == 0 {
+            return Nfa {
+                alphabet: self.alphabet.clone(),
+                states: vec![NfaState {
+                    name: Rc::from("ε"),
+                    initial: true,
+                    accepting: true,
+                    epsilon_transitions: vec![],
+                    transitions: vec![vec![]; self.alphabet.len()],
+                }],
+                initial_state: 0,
+            };
+        }
+        let mut power = copy(1);
+        for i in 2..=k {
+            power.concatenate(copy(i));
+        }
+        power
     }
 
-    /// Checks if the alphabet of this automaton consists of only single graphemes. If it does, one may use
-    /// [Dfa::accepts_graphemes] instead of [Dfa::accepts] for improved ergonomics. A grapheme is defined to be
-    /// one extended unicode grapheme cluster (which may consist of one or many code points).
+    /// Converts this DFA to a regular expression matching the same language, by converting it to a NFA and using
+    /// [Nfa::to_regex]
     ///
     /// ```
-    /// use dandy::parser;
     /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
     ///
-    /// let single_graphemes = "
-    ///        a b
-    /// -> * s s s
-    /// ";
+    /// // Accepts words with an odd number of a's
+    /// let dfa: Dfa = parser::dfa("
+    ///           a
+    /// -> even   odd
+    ///  * odd    even
+    /// ").unwrap().try_into().unwrap();
+    /// let regex = dfa.to_regex();
+    /// assert!(regex.equivalent_to(&parser::regex("a(aa)*").unwrap()));
+    /// ```
+    pub fn to_regex(&self) -> Regex {
+        self.clone().to_nfa().to_regex()
+    }
+
+    /// Splits the input into elements of the alphabet, by repeatedly taking the longest element of the alphabet that
+    /// the rest of the input starts with. Note that the longest match is always taken even if a shorter one would let
+    /// the rest of the input be split: with the alphabet `a`, `ab` and `bc`, the input `abc` fails since `ab` is taken
+    /// and nothing matches `c`, even though it could be split into `a` and `bc`. Whitespace is not skipped, so it must
+    /// be part of the alphabet for the input to contain it.
     ///
-    /// let single_grapheme_dfa: Dfa = parser::dfa(single_graphemes).unwrap().try_into().unwrap();
-    /// assert!(single_grapheme_dfa.graphemes_only());
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::{Dfa, TokenizeError};
     ///
-    /// let multiple_graphemes = "
-    ///        abc def
-    /// -> * s s   s
-    /// ";
+    /// let dfa: Dfa = parser::dfa("
+    ///        a  ab bc
+    /// -> * s s  s  s
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.tokenize("abaa"), Ok(vec!["ab", "a", "a"]));
+    /// assert_eq!(dfa.tokenize("abc"), Err(TokenizeError { position: 2 }));
+    /// ```
+    pub fn tokenize<'s>(&self, input: &'s str) -> Result<Vec<&'s str>, TokenizeError> {
+        let mut tokens = vec![];
+        let mut position = 0;
+        while position < input.len() {
+            let rest = &input[position..];
+            let len = self
+                .alphabet
+                .iter()
+                .filter(|elem| !elem.is_empty() && rest.starts_with(&***elem))
+                .map(|elem| elem.len())
+                .max()
+                .ok_or(TokenizeError { position })?;
+            tokens.push(&rest[..len]);
+            position += len;
+        }
+        Ok(tokens)
+    }
+
+    /// Checks if this automaton accepts the given input, after splitting it into elements of the alphabet with
+    /// [Dfa::tokenize]. This allows alphabets with elements of several characters, such as `id`, `+` and `<=`, to be
+    /// used without splitting the input beforehand. Overlapping elements are resolved by always taking the longest
+    /// match, so with the alphabet `<`, `=` and `<=`, the input `<=` is one element and never `<` followed by `=`.
     ///
-    /// let multiple_graphemes_dfa: Dfa = parser::dfa(multiple_graphemes).unwrap().try_into().unwrap();
-    /// assert!(!multiple_graphemes_dfa.graphemes_only());
     /// ```
-    pub fn graphemes_only(&self) -> bool {
-        self.alphabet
-            .iter()
-            .all(|str| str.graphemes(true).count() == 1)
+    /// use dandy::parser;
+    /// use dandy::dfa::{Dfa, TokenizeError};
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///          id  <   <=  =
+    /// -> s₀    s₁  s₃  s₃  s₃
+    ///    s₁    s₃  s₂  s₂  s₃
+    ///    s₂    s₄  s₃  s₃  s₃
+    ///    s₃    s₃  s₃  s₃  s₃
+    ///  * s₄    s₃  s₃  s₃  s₃
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.accepts_tokenized("id<=id"), Ok(true));
+    /// assert_eq!(dfa.accepts_tokenized("id<id"), Ok(true));
+    /// assert_eq!(dfa.accepts_tokenized("id<"), Ok(false));
+    /// assert_eq!(dfa.accepts_tokenized("id < id"), Err(TokenizeError { position: 2 }));
+    /// ```
+    pub fn accepts_tokenized(&self, input: &str) -> Result<bool, TokenizeError> {
+        Ok(self.accepts_iter(self.tokenize(input)?))
     }
 
-    /// Gets an evaluator, which is a struct that is used to evaluate strings with the automaton
-    pub fn evaluator(&self) -> DfaEvaluator<'_> {
-        self.into()
+    /// Finds the length of the longest prefix of the input which this automaton accepts, or `None` if no prefix (not
+    /// even the empty one) is accepted. This is the "maximal munch" rule used by lexers. The input is only read until
+    /// a dead state (from which no string is accepted) or an element not in the alphabet is reached, since no longer
+    /// prefix can be accepted after that.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// // a+
+    /// let dfa: Dfa = parser::dfa("
+    ///          a  b
+    /// -> s₀    s₁ s₂
+    ///  * s₁    s₁ s₂
+    ///    s₂    s₂ s₂
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.longest_accepted_prefix(&["a", "a", "b", "a"]), Some(2));
+    /// assert_eq!(dfa.longest_accepted_prefix(&["b", "a"]), None);
+    /// ```
+    pub fn longest_accepted_prefix(&self, input: &[&str]) -> Option<usize> {
+        let dead_states = self.dead_state_idx();
+        let mut eval = self.evaluator();
+        let mut longest = eval.is_accepting().then_some(0);
+        for (idx, elem) in input.iter().enumerate() {
+            if eval.step(elem).is_none() || dead_states.contains(&eval.current_state_idx()) {
+                break;
+            }
+            if eval.is_accepting() {
+                longest = Some(idx + 1);
+            }
+        }
+        longest
+    }
+
+    /// Gets the indices of the states visited when evaluating the word, starting with the initial state, so the result
+    /// always contains one more state than the word has elements. Use [Dfa::state_names] to get the names of the
+    /// states. If the word contains an element not in the alphabet, its position is returned as an error.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::{Dfa, TraceError};
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///            0    1
+    /// -> * even  even odd
+    ///      odd   odd  even
+    /// ").unwrap().try_into().unwrap();
+    /// let trace = dfa.trace(&["1", "0", "1"]).unwrap();
+    /// assert_eq!(trace, vec![0, 1, 1, 0]);
+    /// assert_eq!(dfa.state_names(&trace), vec!["even", "odd", "odd", "even"]);
+    /// assert_eq!(dfa.trace(&["1", "2"]), Err(TraceError { position: 1 }));
+    /// ```
+    pub fn trace(&self, word: &[&str]) -> Result<Vec<usize>, TraceError> {
+        let mut eval = self.evaluator();
+        let mut trace = Vec::with_capacity(word.len() + 1);
+        trace.push(eval.current_state_idx());
+        for (position, elem) in word.iter().enumerate() {
+            if eval.step(elem).is_none() {
+                return Err(TraceError { position });
+            }
+            trace.push(eval.current_state_idx());
+        }
+        Ok(trace)
     }
 
-    /// Generates a table of this DFA suitable for printing, which may be parsed again to this automaton
+    /// Gets the names of the states with the given indices, in the same order. This is useful together with
+    /// [Dfa::trace].
+    ///
+    /// # Panics
+    /// Panics if any index is out of bounds
+    pub fn state_names(&self, indices: &[usize]) -> Vec<&str> {
+        indices
+            .iter()
+            .map(|&idx| self.states[idx].name.as_ref())
+            .collect()
+    }
+
+    /// Generates a table of this DFA suitable for printing, which may be parsed again to this automaton. The columns
+    /// are padded so that they line up, with the transitions under their alphabet element (counting characters, not
+    /// bytes).
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///   a b
+    /// -> * s₀ long_name s₀
+    /// long_name long_name s₀
+    /// ").unwrap().try_into().unwrap();
+    /// let lines = dfa.to_table().lines().map(|l| l.trim_end().to_string()).collect::<Vec<_>>();
+    /// assert_eq!(lines, vec![
+    ///     "              a         b",
+    ///     "→ * s₀        long_name s₀",
+    ///     "    long_name long_name s₀",
+    /// ]);
+    /// ```
     pub fn to_table(&self) -> String {
-        self.gen_table("→")
+        self.gen_table("→", |table| table.to_string(" "))
+    }
+
+    /// Generates a table of this DFA for reading, which may be parsed again to this automaton. Like
+    /// [Dfa::to_table], the state names and transitions are padded so that the columns line up under the alphabet,
+    /// but the widths are counted in graphemes, so that names with combining characters line up as well, and the lines
+    /// have no trailing whitespace.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///     a  b
+    /// -> q̄ q̄  q
+    ///  * q q  q̄
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.to_table_aligned().lines().collect::<Vec<_>>(), vec![
+    ///     "      a b",
+    ///     "→   q̄ q̄ q",
+    ///     "  * q q q̄",
+    /// ]);
+    /// ```
+    pub fn to_table_aligned(&self) -> String {
+        self.gen_table("→", |table| table.to_aligned_string(" "))
     }
 
     /// Generates a table of this DFA suitable for printing, which may be parsed again to this automaton. The arrow for
     /// the initial state is "->"
     pub fn ascii_table(&self) -> String {
-        self.gen_table("->")
+        self.gen_table("->", |table| table.to_string(" "))
     }
 
-    fn gen_table(&self, arrow: &str) -> String {
+    /// Generates a [Mermaid](https://mermaid.js.org/) state diagram of this DFA, which is for example rendered on
+    /// GitHub. The initial state has an arrow from `[*]`, accepting states belong to the class `accepting` (drawn with
+    /// a thicker border) and parallel transitions are merged into one edge with all labels. State names which aren't
+    /// simple identifiers are given generated identifiers, with the name as description.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        a  b
+    /// -> q0  q1 q0
+    ///  * q1  q1 q1
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.to_mermaid(), "\
+    /// stateDiagram-v2
+    ///     q0
+    ///     q1
+    ///     [*] --> q0
+    ///     q0 --> q0 : b
+    ///     q0 --> q1 : a
+    ///     q1 --> q1 : a, b
+    ///     classDef accepting stroke-width:4px
+    ///     class q1 accepting
+    /// ");
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let (states, transitions) = self.diagram_parts();
+        mermaid::state_diagram(&states, &transitions)
+    }
+
+    /// Generates a [Graphviz](https://graphviz.org/) DOT graph of this DFA, which can be rendered with for example
+    /// `dot -Tsvg`. The initial state has an arrow from an invisible node, accepting states are drawn with double
+    /// circles and parallel transitions are merged into one edge with all labels. The state with index `i` has the
+    /// node id `q{i}` and its name as label.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        a  b
+    /// -> q0  q1 q0
+    ///  * q1  q1 q1
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.to_dot(), "\
+    /// digraph {
+    ///     rankdir=LR;
+    ///     node [shape=circle];
+    ///     start [shape=none, label=\"\"];
+    ///     q0 [label=\"q0\"];
+    ///     q1 [label=\"q1\", shape=doublecircle];
+    ///     start -> q0;
+    ///     q0 -> q0 [label=\"b\"];
+    ///     q0 -> q1 [label=\"a\"];
+    ///     q1 -> q1 [label=\"a, b\"];
+    /// }
+    /// ");
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let (states, transitions) = self.diagram_parts();
+        dot::digraph(&states, &transitions)
+    }
+
+    /// Generates a `tikzpicture` of this DFA for LaTeX documents, using the `automata` TikZ library, which is loaded
+    /// with `\usetikzlibrary{automata}` in the preamble. The states are laid out from left to right in order, the
+    /// initial state and accepting states are marked with the `initial` and `accepting` styles, and parallel
+    /// transitions are merged into one edge with all labels. The state with index `i` has the node name `q{i}`, and
+    /// characters which are special in LaTeX are escaped in names and labels.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///           a    b
+    /// -> even   odd  even
+    ///  * odd    even odd
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.to_tikz(), r"\begin{tikzpicture}[->, >=stealth, auto]
+    ///     \node[state, initial] (q0) at (0, 0) {even};
+    ///     \node[state, accepting] (q1) at (2, 0) {odd};
+    ///     \path (q0) edge[loop above] node {b} (q0);
+    ///     \path (q0) edge[bend left] node {a} (q1);
+    ///     \path (q1) edge[bend left] node {a} (q0);
+    ///     \path (q1) edge[loop above] node {b} (q1);
+    /// \end{tikzpicture}
+    /// ");
+    /// ```
+    pub fn to_tikz(&self) -> String {
+        let (states, transitions) = self.diagram_parts();
+        tikz::picture(&states, &transitions)
+    }
+
+    /// Gets the states and the transitions (as `(from, to, label)`) of this DFA, as given to the diagram exporters
+    fn diagram_parts(&self) -> (Vec<DiagramState<'_>>, Vec<(usize, usize, &str)>) {
+        let states = self
+            .states
+            .iter()
+            .map(|state| DiagramState {
+                name: &state.name,
+                initial: state.initial,
+                accepting: state.accepting,
+            })
+            .collect::<Vec<_>>();
+        let transitions = self
+            .states
+            .iter()
+            .enumerate()
+            .flat_map(|(from, state)| {
+                state
+                    .transitions
+                    .iter()
+                    .zip(self.alphabet.iter())
+                    .map(move |(&to, label)| (from, to, label as &str))
+            })
+            .collect::<Vec<_>>();
+        (states, transitions)
+    }
+
+    fn gen_table(&self, arrow: &str, render: impl FnOnce(&Table) -> String) -> String {
         let mut table = Table::default();
 
         let mut alph = vec!["", "", ""];
@@ -888,7 +2610,7 @@ impl Dfa {
                 .for_each(|&c| state.push(&self.states[c].name));
             table.push_row(state);
         }
-        table.to_string(" ")
+        render(&table)
     }
 
     /// Checks if this DFA is equivalent to another DFA, that is, if they accept the same language.
@@ -931,9 +2653,500 @@ impl Dfa {
                 }
             }
         }
-        return None;
+        None
+    }
+
+    /// Checks if this DFA is isomorphic to another DFA, that is, if they have the same structure up to the names and
+    /// order of the states: there must be a one-to-one correspondence between the states, preserving the initial
+    /// state, which states are accepting and every transition. This is stricter than [Dfa::equivalent_to], since
+    /// equivalent DFAs may have different numbers of states or be shaped differently. The order of the alphabet
+    /// doesn't matter, but DFAs with different alphabets are never isomorphic.
+    ///
+    /// Only the reachable states are considered, since unreachable states can't be matched up by following the
+    /// transitions. To compare all states, make sure there are no [unreachable states](Dfa::unreachable_states).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let odd_length: Dfa = parser::dfa("
+    ///         a    b
+    /// -> even odd  odd
+    ///  * odd  even even
+    /// ").unwrap().try_into().unwrap();
+    /// let renamed: Dfa = parser::dfa("
+    ///       b  a
+    ///  * s₁ s₀ s₀
+    /// -> s₀ s₁ s₁
+    /// ").unwrap().try_into().unwrap();
+    /// let unrolled: Dfa = parser::dfa("
+    ///          a     b
+    /// -> even  odd   odd
+    ///  * odd   even2 even2
+    ///    even2 odd   odd
+    /// ").unwrap().try_into().unwrap();
+    /// assert!(odd_length.is_isomorphic_to(&renamed));
+    /// assert!(odd_length.equivalent_to(&unrolled));
+    /// assert!(!odd_length.is_isomorphic_to(&unrolled));
+    /// ```
+    pub fn is_isomorphic_to(&self, other: &Dfa) -> bool {
+        self.reachable_correspondence(other).is_some()
+    }
+
+    /// Finds the isomorphism between this DFA and `other` (see [Dfa::is_isomorphic_to]), as the index in `other` of
+    /// the state corresponding to each state of this DFA. The states are matched up by following the transitions
+    /// breadth-first from the initial states, checking that the correspondence is consistent with the transitions and
+    /// which states are accepting. Returns `None` if the DFAs aren't isomorphic, including when their alphabets
+    /// differ.
+    ///
+    /// Unreachable states can't be matched up by following the transitions, so this also returns `None` if either DFA
+    /// has [unreachable states](Dfa::unreachable_states), even if [Dfa::is_isomorphic_to] holds. To find the
+    /// isomorphism between the reachable parts, [remove the unreachable states](Dfa::remove_unreachable_states) of
+    /// both DFAs first.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let reference: Dfa = parser::dfa("
+    ///          a     b
+    /// -> start a     start
+    ///    a     a     ab
+    ///  * ab    a     start
+    /// ").unwrap().try_into().unwrap();
+    /// let answer: Dfa = parser::dfa("
+    ///       a  b
+    ///  * q₂ q₁ q₀
+    /// -> q₀ q₁ q₀
+    ///    q₁ q₁ q₂
+    /// ").unwrap().try_into().unwrap();
+    /// let bijection = reference.isomorphism(&answer).unwrap();
+    /// assert_eq!(bijection, vec![1, 2, 0]);
+    /// assert_eq!(answer.states()[bijection[2]].name(), "q₂");
+    ///
+    /// let wrong: Dfa = parser::dfa("
+    ///       a  b
+    ///  * q₂ q₁ q₀
+    /// -> q₀ q₁ q₀
+    ///    q₁ q₁ q₁
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(reference.isomorphism(&wrong), None);
+    /// ```
+    pub fn isomorphism(&self, other: &Dfa) -> Option<Vec<usize>> {
+        if self.states.len() != other.states.len() {
+            return None;
+        }
+        // Every state of this DFA being mapped makes the mapping a bijection, since it is one-to-one
+        self.reachable_correspondence(other)?.into_iter().collect()
+    }
+
+    /// Matches up the reachable states of this DFA with those of `other` by following the transitions from the
+    /// initial states, giving the index in `other` of the state corresponding to each reachable state of this DFA, and
+    /// `None` for the unreachable states. Returns `None` if the reachable parts aren't isomorphic.
+    fn reachable_correspondence(&self, other: &Dfa) -> Option<Vec<Option<usize>>> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return None;
+        }
+        // other_idx[i] is the index of the i'th element of our alphabet in the alphabet of 'other'
+        let other_idx = self
+            .alphabet
+            .iter()
+            .map(|elem| other.alphabet.iter().position(|e| e == elem).unwrap())
+            .collect::<Vec<_>>();
+
+        // Since the DFAs are deterministic, following the same transitions from the initial states in both gives the
+        // only possible correspondence, which must be the same in both directions
+        let mut to_other = vec![None; self.states.len()];
+        let mut from_other = vec![None; other.states.len()];
+        to_other[self.initial_state] = Some(other.initial_state);
+        from_other[other.initial_state] = Some(self.initial_state);
+        let mut to_explore = VecDeque::from([(self.initial_state, other.initial_state)]);
+
+        while let Some((s1, s2)) = to_explore.pop_front() {
+            if self.states[s1].accepting != other.states[s2].accepting {
+                return None;
+            }
+            for (elem, &other_elem) in other_idx.iter().enumerate() {
+                let t1 = self.states[s1].transitions[elem];
+                let t2 = other.states[s2].transitions[other_elem];
+                match (to_other[t1], from_other[t2]) {
+                    (None, None) => {
+                        to_other[t1] = Some(t2);
+                        from_other[t2] = Some(t1);
+                        to_explore.push_back((t1, t2));
+                    }
+                    (Some(mapped), Some(_)) if mapped == t2 => {}
+                    _ => return None,
+                }
+            }
+        }
+        Some(to_other)
+    }
+
+    /// Finds a shortest word which is accepted by exactly one of this DFA and `other`, which is a witness of the two
+    /// DFAs not being equivalent. Returns `Ok(None)` if the DFAs are equivalent, and an error if the alphabets of the
+    /// two DFAs differ (not considering ordering). The search is a breadth-first search over the reachable pairs of
+    /// states of the product construction, so no new automaton is constructed.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let ends_with_a = "
+    ///      a b
+    /// -> n y n
+    ///  * y y n";
+    /// let contains_a = "
+    ///      a b
+    /// -> n y n
+    ///  * y y y";
+    /// let ends_with_a: Dfa = parser::dfa(ends_with_a).unwrap().try_into().unwrap();
+    /// let contains_a: Dfa = parser::dfa(contains_a).unwrap().try_into().unwrap();
+    ///
+    /// let witness = ends_with_a.equivalence_witness(&contains_a).unwrap().unwrap();
+    /// assert_eq!(witness.iter().map(|s| s.as_ref()).collect::<Vec<_>>(), vec!["a", "b"]);
+    /// assert_eq!(ends_with_a.equivalence_witness(&ends_with_a), Ok(None));
+    /// ```
+    pub fn equivalence_witness(
+        &self,
+        other: &Dfa,
+    ) -> Result<Option<Vec<Rc<str>>>, AlphabetMismatch> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return Err(AlphabetMismatch);
+        }
+
+        // other_idx[i] is the index of the i'th element of our alphabet in the alphabet of 'other'
+        let other_idx = self
+            .alphabet
+            .iter()
+            .map(|elem| other.alphabet.iter().position(|e| e == elem).unwrap())
+            .collect::<Vec<_>>();
+
+        Ok(self.shortest_distinguishing_word(
+            other,
+            &other_idx,
+            (self.initial_state, other.initial_state),
+        ))
+    }
+
+    /// Finds a shortest word which is accepted by exactly one of this DFA and `other`, over the union of their
+    /// alphabets. Both DFAs are first extended to the combined alphabet as with [Dfa::union_extend], so a word using a
+    /// symbol only one of the DFAs knows is rejected by the other. Returns `None` if the DFAs are equivalent. Unlike
+    /// [Dfa::equivalence_witness] this never fails.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let only_a = "
+    ///        a
+    /// -> * s s";
+    /// let only_a_b = "
+    ///        a b
+    /// -> * s s t
+    ///      t t t";
+    /// let only_a: Dfa = parser::dfa(only_a).unwrap().try_into().unwrap();
+    /// let only_a_b: Dfa = parser::dfa(only_a_b).unwrap().try_into().unwrap();
+    /// let all: Dfa = parser::dfa("
+    ///        a b
+    /// -> * s s s").unwrap().try_into().unwrap();
+    ///
+    /// assert_eq!(only_a.equivalence_witness_extend(&only_a_b), None);
+    /// let witness = only_a.equivalence_witness_extend(&all).unwrap();
+    /// assert_eq!(witness.iter().map(|s| s.as_ref()).collect::<Vec<_>>(), vec!["b"]);
+    /// ```
+    pub fn equivalence_witness_extend(&self, other: &Dfa) -> Option<Vec<Rc<str>>> {
+        let (first, second) = self.extend_to_common_alphabet(other);
+        first
+            .equivalence_witness(&second)
+            .expect("Both DFAs should have the same alphabet")
+    }
+
+    /// Finds a shortest word `w` such that exactly one of the states `s1` and `s2` leads to an accepting state upon
+    /// reading `w`, or `None` if the states are equivalent (so [Dfa::minimize] would merge them, if they're
+    /// reachable). Of the shortest words, the first one in lexicographic order (by the order of the alphabet) is
+    /// given, so the result is deterministic. Panics if any of the states doesn't exist.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        a  b
+    /// -> s₀ s₁ s₂
+    ///    s₁ s₃ s₀
+    ///    s₂ s₃ s₀
+    ///  * s₃ s₃ s₃
+    /// ").unwrap().try_into().unwrap();
+    /// let word = dfa.distinguishing_word(0, 1).unwrap();
+    /// assert_eq!(word.iter().map(|s| s.as_ref()).collect::<Vec<_>>(), vec!["a"]);
+    /// assert_eq!(dfa.distinguishing_word(1, 2), None);
+    /// ```
+    pub fn distinguishing_word(&self, s1: usize, s2: usize) -> Option<Vec<Rc<str>>> {
+        assert!(
+            s1 < self.states.len() && s2 < self.states.len(),
+            "No such state"
+        );
+        let identity = (0..self.alphabet.len()).collect::<Vec<_>>();
+        self.shortest_distinguishing_word(self, &identity, (s1, s2))
+    }
+
+    /// Breadth-first search for a shortest word on which exactly one of the states of the pair (of a state of this DFA
+    /// and a state of 'other') leads to an accepting state. other_idx[i] is the index of the i'th element of our
+    /// alphabet in the alphabet of 'other'.
+    fn shortest_distinguishing_word(
+        &self,
+        other: &Dfa,
+        other_idx: &[usize],
+        initial: (usize, usize),
+    ) -> Option<Vec<Rc<str>>> {
+        // Maps each explored pair of states to the pair it was reached from, and the element it was reached by
+        let mut parents: HashMap<_, Option<(_, usize)>> = HashMap::from([(initial, None)]);
+        let mut to_explore = VecDeque::from([initial]);
+
+        while let Some((s1, s2)) = to_explore.pop_front() {
+            if self.states[s1].accepting != other.states[s2].accepting {
+                let mut word = vec![];
+                let mut current = (s1, s2);
+                while let Some(&Some((parent, elem))) = parents.get(&current) {
+                    word.push(self.alphabet[elem].clone());
+                    current = parent;
+                }
+                word.reverse();
+                return Some(word);
+            }
+
+            for (elem, &o_elem) in other_idx.iter().enumerate() {
+                let next = (
+                    self.states[s1].transitions[elem],
+                    other.states[s2].transitions[o_elem],
+                );
+                if let Entry::Vacant(entry) = parents.entry(next) {
+                    entry.insert(Some(((s1, s2), elem)));
+                    to_explore.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Samples a string of exactly `len` elements uniformly at random from the strings of that length accepted by this
+    /// DFA, or gives `None` if there are no such strings. Rather than walking randomly (which favours strings in parts
+    /// of the DFA with few choices), each element is chosen with a probability proportional to the number of accepted
+    /// strings continuing with it, counted beforehand for every state and remaining length.
+    ///
+    /// The counts are floating point numbers, so the distribution is only approximately uniform when there are more
+    /// than 2⁵³ accepted strings of the given length.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// // Accepts "aa" and all strings starting with b
+    /// let dfa: Dfa = parser::dfa("
+    ///          a    b
+    /// -> s     a    any
+    ///    a     aa   trap
+    ///  * aa    trap trap
+    ///  * any   any  any
+    ///    trap  trap trap
+    /// ").unwrap().try_into().unwrap();
+    /// let mut rng = StdRng::seed_from_u64(1);
+    /// // The accepted strings of length 2 are "aa", "ba" and "bb", so each of them is picked a third of the time
+    /// let word = dfa.sample_uniform(2, &mut rng).unwrap();
+    /// assert!(dfa.accepts(&word.iter().map(|s| s.as_ref()).collect::<Vec<_>>()));
+    /// assert_eq!(dfa.sample_uniform(0, &mut rng), None);
+    /// ```
+    pub fn sample_uniform(&self, len: usize, rng: &mut impl Rng) -> Option<Vec<Rc<str>>> {
+        // counts[remaining][state] is the number of strings of length 'remaining' accepted from 'state'
+        let mut counts = vec![self
+            .states
+            .iter()
+            .map(|state| if state.accepting { 1.0 } else { 0.0 })
+            .collect::<Vec<f64>>()];
+        for remaining in 1..=len {
+            let previous = &counts[remaining - 1];
+            let current = self
+                .states
+                .iter()
+                .map(|state| state.transitions.iter().map(|&t| previous[t]).sum())
+                .collect();
+            counts.push(current);
+        }
+        if counts[len][self.initial_state] == 0.0 {
+            return None;
+        }
+
+        let mut word = Vec::with_capacity(len);
+        let mut state = self.initial_state;
+        for remaining in (1..=len).rev() {
+            let transitions = &self.states[state].transitions;
+            let weights = transitions
+                .iter()
+                .map(|&t| counts[remaining - 1][t])
+                .collect::<Vec<_>>();
+            let mut choice = rng.gen::<f64>() * weights.iter().sum::<f64>();
+            // Fall back to the last possible element, in case of rounding errors
+            let mut elem = weights.iter().rposition(|&w| w > 0.0).unwrap();
+            for (idx, &weight) in weights.iter().enumerate() {
+                if weight > 0.0 && choice < weight {
+                    elem = idx;
+                    break;
+                }
+                choice -= weight;
+            }
+            word.push(self.alphabet[elem].clone());
+            state = transitions[elem];
+        }
+        Some(word)
+    }
+}
+
+// The evaluation path, which only depends on `alloc` and is available without the `std` feature
+impl Dfa {
+    /// Converts this DFA to a NFA by simply converting each state to a NFA state. All state names
+    /// are kept. This is a cheap operation, involving no clones but some vector allocations due to
+    /// the vectors required by NFA.
+    pub fn to_nfa(self) -> Nfa {
+        let Dfa {
+            alphabet,
+            states,
+            initial_state,
+        } = self;
+        let states = states.into_iter().map(|s| s.into()).collect();
+        Nfa {
+            alphabet,
+            states,
+            initial_state,
+        }
+    }
+
+    /// Checks if this automaton accepts the given string. This is equivalent to getting the
+    /// evaluator, stepping it multiple times and checking if it is accepting
+    pub fn accepts(&self, string: &[&str]) -> bool {
+        self.accepts_iter(string)
+    }
+
+    /// Checks if this automaton accepts the string given as an iterator of elements of the alphabet. The elements are
+    /// consumed one at a time, so the string never has to be collected. If an element not in the alphabet is seen,
+    /// the string is rejected without consuming the rest of the iterator.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///          id  +
+    /// -> s₀    s₁  s₂
+    ///  * s₁    s₂  s₀
+    ///    s₂    s₂  s₂
+    /// ").unwrap().try_into().unwrap();
+    /// let symbols = "id + id".split(' ');
+    /// assert!(dfa.accepts_iter(symbols));
+    /// assert!(!dfa.accepts_iter(["id", "-", "id"]));
+    /// ```
+    pub fn accepts_iter<S: AsRef<str>>(&self, string: impl IntoIterator<Item = S>) -> bool {
+        let mut eval = self.evaluator();
+        for elem in string {
+            if eval.step(elem.as_ref()).is_none() {
+                return false;
+            }
+        }
+        eval.is_accepting()
     }
 
+    /// Gets the index of the accepting state reached at the end of the given string, so that the accepting states can
+    /// be used to classify the strings. Returns `None` if the state reached isn't accepting, or if the string contains
+    /// an element not in the alphabet.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///             0      1      x
+    /// -> start    zero   number err
+    ///  * zero     err    err    hex
+    ///  * number   number number err
+    ///  * hex      hex    hex    err
+    ///    err      err    err    err
+    /// ").unwrap().try_into().unwrap();
+    /// let kind = |word: &str| {
+    ///     let state = dfa.accepting_state(&word.split(' ').collect::<Vec<_>>())?;
+    ///     Some(dfa.states()[state].name())
+    /// };
+    /// assert_eq!(kind("1 0 1"), Some("number"));
+    /// assert_eq!(kind("0 x 1 0"), Some("hex"));
+    /// assert_eq!(kind("0"), Some("zero"));
+    /// assert_eq!(kind("1 x"), None);
+    /// assert_eq!(kind("0 y"), None);
+    /// ```
+    pub fn accepting_state(&self, string: &[&str]) -> Option<usize> {
+        let mut eval = self.evaluator();
+        eval.step_multiple(string)?;
+        eval.is_accepting().then(|| eval.current_state_idx())
+    }
+
+    /// Checks if this automaton accepts the given string of graphemes, if every grapheme by
+    /// itself is considered as an element of the alphabet. Note that if the alphabet contains
+    /// elements with multiple graphemes, those won't be recognized. To check if there are
+    /// elements with multiple graphemes, see [Dfa::graphemes_only]. A grapheme is defined to be
+    /// one extended unicode grapheme cluster (which may consist of one or many code points).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let input = "
+    ///            0   1
+    /// -> even even odd
+    ///  * odd  even odd
+    /// ";
+    /// let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    /// // The alphabet of this DFA contains single graphemes only
+    /// assert!(dfa.graphemes_only());
+    /// assert!(dfa.accepts_graphemes("001")); // Equivalent to dfa.accepts(&["0", "0", "1"])
+    /// ```
+    pub fn accepts_graphemes(&self, string: &str) -> bool {
+        self.accepts_iter(string.graphemes(true))
+    }
+
+    /// Checks if the alphabet of this automaton consists of only single graphemes. If it does, one may use
+    /// [Dfa::accepts_graphemes] instead of [Dfa::accepts] for improved ergonomics. A grapheme is defined to be
+    /// one extended unicode grapheme cluster (which may consist of one or many code points).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let single_graphemes = "
+    ///        a b
+    /// -> * s s s
+    /// ";
+    ///
+    /// let single_grapheme_dfa: Dfa = parser::dfa(single_graphemes).unwrap().try_into().unwrap();
+    /// assert!(single_grapheme_dfa.graphemes_only());
+    ///
+    /// let multiple_graphemes = "
+    ///        abc def
+    /// -> * s s   s
+    /// ";
+    ///
+    /// let multiple_graphemes_dfa: Dfa = parser::dfa(multiple_graphemes).unwrap().try_into().unwrap();
+    /// assert!(!multiple_graphemes_dfa.graphemes_only());
+    /// ```
+    pub fn graphemes_only(&self) -> bool {
+        self.alphabet
+            .iter()
+            .all(|str| str.graphemes(true).count() == 1)
+    }
+
+    /// Gets an evaluator, which is a struct that is used to evaluate strings with the automaton
+    pub fn evaluator(&self) -> DfaEvaluator<'_> {
+        self.into()
+    }
     /// Gets the alphabet of this DFA
     pub fn alphabet(&self) -> &[Rc<str>] {
         &self.alphabet
@@ -953,4 +3166,64 @@ impl Dfa {
     pub fn initial_state_index(&self) -> usize {
         self.initial_state
     }
+
+    /// Deconstructs this DFA into its alphabet, its states and the index of its initial state. The DFA can be
+    /// constructed again with [Dfa::from_parts].
+    pub fn into_parts(self) -> (Vec<Rc<str>>, Vec<DfaState>, usize) {
+        (self.alphabet.to_vec(), self.states, self.initial_state)
+    }
+
+    /// Constructs a DFA from its alphabet, its states and the index of its initial state, such as those given by
+    /// [Dfa::into_parts]. The parts are checked in the same way as when converting a [parsed](crate::parser::dfa) DFA:
+    /// the alphabet and the state names must not contain duplicates, every state must have one transition for each
+    /// element of the alphabet to an existing state, and exactly one state (the one at `initial_state`) must be marked
+    /// as initial.
+    ///
+    /// ```
+    /// use dandy::dfa::{ConstructionError, Dfa};
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        a  b
+    /// -> s₀  s₁ s₀
+    ///  * s₁  s₁ s₀
+    /// ").unwrap().try_into().unwrap();
+    /// let (alphabet, states, initial_state) = dfa.clone().into_parts();
+    /// assert_eq!(Dfa::from_parts(alphabet.clone(), states.clone(), initial_state), Ok(dfa));
+    /// assert_eq!(
+    ///     Dfa::from_parts(alphabet, states, 1),
+    ///     Err(ConstructionError::InitialStateNotMarked(1))
+    /// );
+    /// ```
+    pub fn from_parts(
+        alphabet: Vec<Rc<str>>,
+        states: Vec<DfaState>,
+        initial_state: usize,
+    ) -> Result<Dfa, ConstructionError> {
+        ConstructionError::check_common(
+            &alphabet,
+            states.iter().map(|state| (&state.name, state.initial)),
+            initial_state,
+        )?;
+        for state in &states {
+            if state.transitions.len() != alphabet.len() {
+                return Err(ConstructionError::WrongNumberOfTransitions(
+                    state.name.clone(),
+                    state.transitions.len(),
+                    alphabet.len(),
+                ));
+            }
+            if let Some(&target) = state.transitions.iter().find(|&&t| t >= states.len()) {
+                return Err(ConstructionError::TransitionDoesNotExist(
+                    state.name.clone(),
+                    target,
+                ));
+            }
+        }
+        Ok(Dfa {
+            alphabet: alphabet.into(),
+            states,
+            initial_state,
+        })
+    }
 }
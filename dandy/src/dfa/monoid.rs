@@ -0,0 +1,200 @@
+use crate::dfa::Dfa;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// The transition monoid of a [Dfa], which is the set of functions from states to states induced by the words over the
+/// alphabet, with composition as the operation. See [Dfa::transition_monoid].
+///
+/// The elements are ordered by their representative words, in shortlex order (shortest first, then in the order of
+/// the alphabet), so the identity (induced by the empty word) is always the first element.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransitionMonoid {
+    pub(crate) elements: Vec<MonoidElement>,
+    pub(crate) table: Vec<Vec<usize>>,
+}
+
+/// An element of a [TransitionMonoid]: a function from states to states, together with the first word (in shortlex
+/// order) inducing it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MonoidElement {
+    pub(crate) function: Vec<usize>,
+    pub(crate) word: Vec<Rc<str>>,
+}
+
+impl MonoidElement {
+    /// Gets the function as a list of state indices, where the state with index `i` is mapped to `function()[i]`
+    pub fn function(&self) -> &[usize] {
+        &self.function
+    }
+
+    /// Gets the shortest word inducing this function, as elements of the alphabet
+    pub fn word(&self) -> &[Rc<str>] {
+        &self.word
+    }
+}
+
+impl TransitionMonoid {
+    /// Gets the elements of the monoid, with the identity first
+    pub fn elements(&self) -> &[MonoidElement] {
+        &self.elements
+    }
+
+    /// Gets the number of elements of the monoid
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Checks if the monoid is empty, which it never is since it always contains the identity
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Gets the index of the product of two elements (by index), which is the function of first applying the function
+    /// of `first` and then the function of `second`, induced by the word of `first` followed by the word of `second`
+    pub fn multiply(&self, first: usize, second: usize) -> usize {
+        self.table[first][second]
+    }
+
+    /// Gets the multiplication table, where `table()[i][j]` is the index of the product of elements `i` and `j`
+    pub fn table(&self) -> &[Vec<usize>] {
+        &self.table
+    }
+
+    /// Checks if this monoid is aperiodic, that is, if every element `m` has a power with `m^(n+1) = m^n`.
+    /// Equivalently, the monoid contains no non-trivial group.
+    pub fn is_aperiodic(&self) -> bool {
+        (0..self.len()).all(|elem| {
+            // The powers of an element eventually repeat, and the monoid is aperiodic if they get stuck at one element
+            // rather than cycling through several
+            let mut seen = HashSet::from([elem]);
+            let mut power = elem;
+            loop {
+                let next = self.multiply(power, elem);
+                if next == power {
+                    return true;
+                }
+                if !seen.insert(next) {
+                    return false;
+                }
+                power = next;
+            }
+        })
+    }
+}
+
+impl Dfa {
+    /// Computes the transition monoid of this DFA, or `None` if it has more than `max_size` elements. Each element is a
+    /// function from states to states, induced by reading a word from each state, and the operation is composition
+    /// of the functions (corresponding to concatenation of the words).
+    ///
+    /// The monoid is computed for this DFA as it is, so to get the syntactic monoid of its language (the one
+    /// characterising for example star-freeness), [minimize](Dfa::minimize) it first. The size of the monoid may be
+    /// exponential (up to `n^n` for `n` states), and the multiplication table is quadratic in the size, which is why
+    /// the size is capped.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// // Accepts (ab)*
+    /// let dfa: Dfa = parser::dfa("
+    ///          a b
+    /// -> * s₀ s₁ s₂
+    ///      s₁ s₂ s₀
+    ///      s₂ s₂ s₂
+    /// ").unwrap().try_into().unwrap();
+    /// let monoid = dfa.transition_monoid(100).unwrap();
+    /// let words = monoid
+    ///     .elements()
+    ///     .iter()
+    ///     .map(|elem| elem.word().concat())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(words, vec!["", "a", "b", "aa", "ab", "ba"]);
+    /// // "ab" followed by "a" is "aba", which induces the same function as "a"
+    /// assert_eq!(monoid.multiply(4, 1), 1);
+    /// assert!(dfa.transition_monoid(5).is_none());
+    /// ```
+    pub fn transition_monoid(&self, max_size: usize) -> Option<TransitionMonoid> {
+        if max_size == 0 {
+            return None;
+        }
+        let identity = MonoidElement {
+            function: (0..self.states.len()).collect(),
+            word: vec![],
+        };
+        let mut index = HashMap::from([(identity.function.clone(), 0)]);
+        let mut elements = vec![identity];
+
+        // Breadth-first, so every element gets a shortest word in shortlex order as representative
+        let mut next = 0;
+        while next < elements.len() {
+            for (elem_idx, elem) in self.alphabet.iter().enumerate() {
+                let function = elements[next]
+                    .function
+                    .iter()
+                    .map(|&state| self.states[state].transitions[elem_idx])
+                    .collect::<Vec<_>>();
+                if index.contains_key(&function) {
+                    continue;
+                }
+                if elements.len() == max_size {
+                    return None;
+                }
+                let mut word = elements[next].word.clone();
+                word.push(elem.clone());
+                index.insert(function.clone(), elements.len());
+                elements.push(MonoidElement { function, word });
+            }
+            next += 1;
+        }
+
+        let table = elements
+            .iter()
+            .map(|first| {
+                elements
+                    .iter()
+                    .map(|second| {
+                        let product = first
+                            .function
+                            .iter()
+                            .map(|&state| second.function[state])
+                            .collect::<Vec<_>>();
+                        index[&product]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Some(TransitionMonoid { elements, table })
+    }
+
+    /// Checks if the language of this DFA is star-free, that is, if it can be described by a regular expression using
+    /// union, concatenation and complement but no Kleene star. By Schützenberger's theorem, this is the case exactly
+    /// when the syntactic monoid of the language (the [transition monoid](Dfa::transition_monoid) of the minimized
+    /// DFA) is [aperiodic](TransitionMonoid::is_aperiodic).
+    ///
+    /// The monoid may be exponentially large (see [Dfa::transition_monoid]), so this gives `None` if it has more than
+    /// `max_monoid_size` elements.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// // Accepts (aa)*, which needs to count modulo 2
+    /// let even: Dfa = parser::dfa("
+    ///           a
+    /// -> * even odd
+    ///      odd  even
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(even.is_star_free(100), Some(false));
+    /// // The syntactic monoid of (aa)* has two elements
+    /// assert_eq!(even.is_star_free(1), None);
+    /// ```
+    pub fn is_star_free(&self, max_monoid_size: usize) -> Option<bool> {
+        let mut minimized = self.clone();
+        minimized.minimize();
+        minimized
+            .transition_monoid(max_monoid_size)
+            .map(|monoid| monoid.is_aperiodic())
+    }
+}
@@ -1,7 +1,6 @@
 use crate::dfa::{Dfa, DfaState};
 use crate::parser::{ParsedDfa, ParsedDfaState};
 use std::collections::{HashMap, HashSet};
-use std::ops::Not;
 use std::rc::Rc;
 use thiserror::Error;
 
@@ -21,18 +20,106 @@ pub enum DfaParseError<'a> {
     DuplicateStateDefinition(&'a str),
 }
 
+/// The placeholder for a missing transition in [Dfa::try_from_partial]
+const MISSING_TRANSITION: &str = "-";
+
 impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
     type Error = DfaParseError<'a>;
 
     fn try_from(value: ParsedDfa<'a>) -> Result<Self, Self::Error> {
+        Dfa::validate_all(value).map_err(|mut errors| errors.swap_remove(0))
+    }
+}
+
+impl Dfa {
+    /// Converts a [ParsedDfa] of a partial DFA to a [Dfa], completing it with a trap state. Unlike [TryFrom::try_from],
+    /// states may list fewer transitions than there are elements in the alphabet, and `-` may be used as the target
+    /// of a transition. Such missing transitions go to a new non-accepting trap state (which is only added if there are
+    /// missing transitions), which is named `trap` if that name isn't taken. Note that this means `-` can't be used as
+    /// a state name in a partial DFA.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    ///        a  b  c
+    /// -> s₀  s₁ -  s₀
+    ///  * s₁  s₁
+    /// ";
+    /// let dfa = Dfa::try_from_partial(parser::dfa(input).unwrap()).unwrap();
+    /// assert_eq!(dfa.states().len(), 3);
+    /// assert_eq!(dfa.states()[2].name(), "trap");
+    /// assert!(dfa.accepts_graphemes("caaa"));
+    /// assert!(!dfa.accepts_graphemes("ab"));
+    /// assert!(!dfa.accepts_graphemes("ba"));
+    /// ```
+    pub fn try_from_partial(parsed: ParsedDfa<'_>) -> Result<Dfa, DfaParseError<'_>> {
+        let ParsedDfa { head, mut states } = parsed;
+
+        // Missing transitions are first made into self-loops, and then redirected to the trap state
+        let mut missing = vec![];
+        for (idx, state) in states.iter_mut().enumerate() {
+            if state.transitions.len() < head.len() {
+                state.transitions.resize(head.len(), MISSING_TRANSITION);
+            }
+            for (elem, target) in state.transitions.iter_mut().enumerate() {
+                if *target == MISSING_TRANSITION {
+                    missing.push((idx, elem));
+                    *target = state.name;
+                }
+            }
+        }
+
+        let mut dfa = Dfa::try_from(ParsedDfa { head, states })?;
+        if !missing.is_empty() {
+            let trap = dfa.states.len();
+            let trap_state = DfaState {
+                name: dfa.fresh_name("trap"),
+                initial: false,
+                accepting: false,
+                transitions: vec![trap; dfa.alphabet.len()],
+            };
+            dfa.states.push(trap_state);
+            for (idx, elem) in missing {
+                dfa.states[idx].transitions[elem] = trap;
+            }
+        }
+        Ok(dfa)
+    }
+
+    /// Converts a [ParsedDfa] to a [Dfa] like [TryFrom::try_from], but instead of stopping at the first error, this
+    /// collects all errors in the parsed DFA. The first error is the one [TryFrom::try_from] would give.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::dfa::parse::DfaParseError;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    ///      a b a
+    ///    s s t s
+    ///    t s u
+    /// ";
+    /// let errors = Dfa::validate_all(parser::dfa(input).unwrap()).unwrap_err();
+    /// assert_eq!(errors, vec![
+    ///     DfaParseError::DuplicateAlphabetSymbol("a"),
+    ///     DfaParseError::WrongNumberOfTransitions("t", 2, 3),
+    ///     DfaParseError::TransitionDoesNotExist("t", "u"),
+    ///     DfaParseError::MissingInitialState,
+    /// ]);
+    /// ```
+    pub fn validate_all(parsed: ParsedDfa<'_>) -> Result<Dfa, Vec<DfaParseError<'_>>> {
         use DfaParseError::*;
-        let ParsedDfa { head, states } = value;
+        let ParsedDfa { head, states } = parsed;
+        let mut errors = vec![];
 
         {
             let mut alphabet = HashSet::new();
+            let mut reported = HashSet::new();
             head.iter()
-                .try_for_each(|c| alphabet.insert(c).then_some(()).ok_or(c))
-                .map_err(|d| DuplicateAlphabetSymbol(d))?;
+                .filter(|c| !alphabet.insert(**c) && reported.insert(**c))
+                .for_each(|d| errors.push(DuplicateAlphabetSymbol(d)));
         }
 
         let state_name_map: HashMap<_, _> = states
@@ -42,13 +129,13 @@ impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
             .collect();
 
         if state_name_map.len() != states.len() {
-            // We have a duplicate name, let's find it!
+            // We have duplicate names, let's find them!
             let mut seen = HashSet::new();
-            let duplicate = states
+            let mut reported = HashSet::new();
+            states
                 .iter()
-                .find_map(|s| seen.insert(s.name).not().then_some(s.name))
-                .unwrap_or("<unknown>");
-            return Err(DuplicateStateDefinition(duplicate));
+                .filter(|s| !seen.insert(s.name) && reported.insert(s.name))
+                .for_each(|s| errors.push(DuplicateStateDefinition(s.name)));
         }
 
         let mut initial_state = None;
@@ -63,7 +150,7 @@ impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
             } = state;
 
             if transitions.len() != head.len() {
-                return Err(WrongNumberOfTransitions(
+                errors.push(WrongNumberOfTransitions(
                     name,
                     transitions.len(),
                     head.len(),
@@ -75,15 +162,15 @@ impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
                 if let Some(idx) = state_name_map.get(transition) {
                     new_transitions.push(*idx);
                 } else {
-                    return Err(TransitionDoesNotExist(name, transition)); // Target of transition does not exist
+                    errors.push(TransitionDoesNotExist(name, transition)); // Target of transition does not exist
                 }
             }
 
             if initial {
                 if initial_state.is_none() {
                     initial_state = Some(idx);
-                } else {
-                    return Err(MultipleInitialStates);
+                } else if !errors.contains(&MultipleInitialStates) {
+                    errors.push(MultipleInitialStates);
                 }
             }
 
@@ -95,15 +182,17 @@ impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
             });
         }
 
-        if let Some(initial_state) = initial_state {
-            let dfa = Dfa {
+        if initial_state.is_none() {
+            errors.push(MissingInitialState);
+        }
+
+        match initial_state {
+            Some(initial_state) if errors.is_empty() => Ok(Dfa {
                 alphabet: head.into_iter().map(Rc::from).collect(),
                 states: new_states,
                 initial_state,
-            };
-            Ok(dfa)
-        } else {
-            Err(MissingInitialState)
+            }),
+            _ => Err(errors),
         }
     }
 }
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+
+/// A partition of the states of a DFA into classes, such as the classes of non-distinguishable states given by
+/// [Dfa::state_equivalence_classes_idx](crate::dfa::Dfa::state_equivalence_classes_idx). The states are given by
+/// their indices.
+///
+/// The states of each class are sorted, and the classes are sorted by their first state. The first state of each class
+/// is its representative.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Partition {
+    pub(crate) classes: Vec<Vec<usize>>,
+    pub(crate) class_of: Vec<usize>,
+}
+
+impl Partition {
+    /// Creates a partition of the states `0..num_states` from the given classes, which should be disjoint and cover
+    /// all states
+    pub(crate) fn new(num_states: usize, classes: Vec<HashSet<usize>>) -> Self {
+        let mut classes = classes
+            .into_iter()
+            .filter(|class| !class.is_empty())
+            .map(|class| {
+                let mut class = class.into_iter().collect::<Vec<_>>();
+                class.sort_unstable();
+                class
+            })
+            .collect::<Vec<_>>();
+        classes.sort_unstable_by_key(|class| class[0]);
+
+        let mut class_of = vec![0; num_states];
+        for (idx, class) in classes.iter().enumerate() {
+            for &state in class {
+                class_of[state] = idx;
+            }
+        }
+        Self { classes, class_of }
+    }
+
+    /// Gets the classes of this partition, as sorted lists of state indices
+    pub fn classes(&self) -> &[Vec<usize>] {
+        &self.classes
+    }
+
+    /// Gets the index of the class containing the given state
+    pub fn class_of(&self, state: usize) -> usize {
+        self.class_of[state]
+    }
+
+    /// Gets the representative of the class with the given index, which is its state with the lowest index
+    pub fn representative(&self, class: usize) -> usize {
+        self.classes[class][0]
+    }
+
+    /// Gets the number of classes
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Checks if there are no classes, which only happens if there are no states
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+}
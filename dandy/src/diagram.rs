@@ -0,0 +1,61 @@
+//! # Diagram helpers
+//! Helpers shared by the diagram exporters, such as [Dfa::to_dot](crate::dfa::Dfa::to_dot) and the renderers of
+//! `dandy-draw`, so that all of them draw the same edges with the same labels.
+//!
+//! Diagrams draw at most one edge between two states, in each direction. [group_edges] merges the parallel
+//! transitions into such edges, in a deterministic order, so that for example the transitions upon `c`, `b` and `a`
+//! from one state to another are always drawn as one edge labeled `a, b, c`.
+//!
+//! ```
+//! use dandy::diagram::{group_edges, Edge};
+//!
+//! let transitions = [(1, 0, "c"), (0, 1, "b"), (1, 0, "a"), (0, 0, "d")];
+//! assert_eq!(group_edges(transitions), vec![
+//!     Edge { from: 0, to: 0, labels: vec!["d"] },
+//!     Edge { from: 0, to: 1, labels: vec!["b"] },
+//!     Edge { from: 1, to: 0, labels: vec!["a", "c"] },
+//! ]);
+//! ```
+use std::collections::BTreeMap;
+
+/// A state as drawn by the diagram exporters
+pub(crate) struct DiagramState<'a> {
+    pub name: &'a str,
+    pub initial: bool,
+    pub accepting: bool,
+}
+
+/// An edge of a diagram, standing for all the transitions from the state with index `from` to the state with index
+/// `to`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edge<'a> {
+    pub from: usize,
+    pub to: usize,
+    /// The labels of the transitions, sorted
+    pub labels: Vec<&'a str>,
+}
+
+impl Edge<'_> {
+    /// Gets the label of the whole edge, which is the labels of the transitions separated by `, `
+    pub fn label(&self) -> String {
+        self.labels.join(", ")
+    }
+}
+
+/// Merges parallel transitions, given as `(from, to, label)`, into one edge each. The edges are sorted by `from` and
+/// then by `to`, and the labels of each edge are sorted, so the labels don't depend on the order of the alphabet.
+pub fn group_edges<'a>(
+    transitions: impl IntoIterator<Item = (usize, usize, &'a str)>,
+) -> Vec<Edge<'a>> {
+    let mut edges = BTreeMap::<_, Vec<_>>::new();
+    for (from, to, label) in transitions {
+        edges.entry((from, to)).or_default().push(label);
+    }
+    edges
+        .into_iter()
+        .map(|((from, to), mut labels)| {
+            labels.sort();
+            Edge { from, to, labels }
+        })
+        .collect()
+}
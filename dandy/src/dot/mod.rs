@@ -0,0 +1,48 @@
+use crate::diagram::{group_edges, DiagramState};
+use std::fmt::Write;
+
+/// Generates a [Graphviz](https://graphviz.org/) DOT digraph from the given states and transitions (given as
+/// `(from, to, label)`). The state with index `i` gets the node id `q{i}` and its name as label, so any state name is
+/// supported. Parallel transitions are merged into one edge by [group_edges].
+pub fn digraph(states: &[DiagramState], transitions: &[(usize, usize, &str)]) -> String {
+    let mut acc = String::from("digraph {\n    rankdir=LR;\n    node [shape=circle];\n");
+    if states.iter().any(|state| state.initial) {
+        acc.push_str("    start [shape=none, label=\"\"];\n");
+    }
+    for (idx, state) in states.iter().enumerate() {
+        let shape = if state.accepting {
+            ", shape=doublecircle"
+        } else {
+            ""
+        };
+        let _ = writeln!(acc, "    q{idx} [label=\"{}\"{shape}];", escape(state.name));
+    }
+    for (idx, state) in states.iter().enumerate() {
+        if state.initial {
+            let _ = writeln!(acc, "    start -> q{idx};");
+        }
+    }
+    for edge in group_edges(transitions.iter().copied()) {
+        let _ = writeln!(
+            acc,
+            "    q{} -> q{} [label=\"{}\"];",
+            edge.from,
+            edge.to,
+            escape(&edge.label())
+        );
+    }
+    acc.push_str("}\n");
+    acc
+}
+
+/// Escapes quotes and backslashes, so that the string can be used within a quoted DOT string
+pub fn escape(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut acc, c| {
+            if c == '"' || c == '\\' {
+                acc.push('\\');
+            }
+            acc.push(c);
+            acc
+        })
+}
@@ -0,0 +1,212 @@
+use crate::grammar::Grammar;
+use std::collections::{HashMap, HashSet};
+
+/// A symbol in a grammar, where terminals and nonterminals are identified by indices
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Symbol {
+    Terminal(usize),
+    Nonterminal(usize),
+}
+
+/// A grammar in Chomsky normal form (except for the empty word, which is handled separately by `nullable_start`),
+/// where terminals are identified by their index in the terminals of the original grammar, and nonterminals by their
+/// index in the nonterminals of the original grammar. Nonterminals added during the conversion have indices after the
+/// original nonterminals.
+#[derive(Clone, Debug)]
+pub(crate) struct Cnf {
+    pub(crate) nonterminals: usize,
+    pub(crate) start: usize,
+    pub(crate) nullable_start: bool,
+    /// Productions `A → t`, as `(A, t)`
+    pub(crate) terminal_rules: Vec<(usize, usize)>,
+    /// Productions `A → B C`, as `(A, B, C)`
+    pub(crate) binary_rules: Vec<(usize, usize, usize)>,
+}
+
+impl Cnf {
+    /// Converts the grammar to Chomsky normal form, by (in order) replacing terminals in productions with more than one
+    /// symbol with new nonterminals, splitting productions with more than two symbols into productions with two
+    /// symbols, removing ε-productions and finally removing unit productions. Productions are never removed for
+    /// being useless.
+    pub(crate) fn from_grammar(grammar: &Grammar<'_>) -> Cnf {
+        let nt_idx = index_map(&grammar.nonterminals);
+        let t_idx = index_map(&grammar.terminals);
+        let symbol = |s: &str| match nt_idx.get(s) {
+            Some(&idx) => Symbol::Nonterminal(idx),
+            None => Symbol::Terminal(t_idx[s]),
+        };
+
+        let mut nonterminals = grammar.nonterminals.len();
+        let mut rules = grammar
+            .productions
+            .iter()
+            .flat_map(|p| {
+                p.alternatives.iter().map(|alt| {
+                    (
+                        nt_idx[p.name],
+                        alt.iter().map(|s| symbol(s)).collect::<Vec<_>>(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Replace terminals in long productions with new nonterminals
+        let mut terminal_nts = HashMap::new();
+        let mut new_rules = vec![];
+        for (_, alt) in rules.iter_mut().filter(|(_, alt)| alt.len() > 1) {
+            for s in alt.iter_mut() {
+                if let Symbol::Terminal(t) = *s {
+                    let nt = *terminal_nts.entry(t).or_insert_with(|| {
+                        new_rules.push((nonterminals, vec![Symbol::Terminal(t)]));
+                        nonterminals += 1;
+                        nonterminals - 1
+                    });
+                    *s = Symbol::Nonterminal(nt);
+                }
+            }
+        }
+        rules.append(&mut new_rules);
+
+        // Split long productions A → X1 X2 ... Xn into A → X1 A1, A1 → X2 A2, ..., An-2 → Xn-1 Xn
+        let mut binary = vec![];
+        for (nt, alt) in rules {
+            if alt.len() <= 2 {
+                binary.push((nt, alt));
+                continue;
+            }
+            let mut lhs = nt;
+            for &s in &alt[..alt.len() - 2] {
+                binary.push((lhs, vec![s, Symbol::Nonterminal(nonterminals)]));
+                lhs = nonterminals;
+                nonterminals += 1;
+            }
+            binary.push((lhs, alt[alt.len() - 2..].to_vec()));
+        }
+
+        // Remove ε-productions
+        let nullable = {
+            let mut nullable = HashSet::new();
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for (nt, alt) in &binary {
+                    if !nullable.contains(nt)
+                        && alt
+                            .iter()
+                            .all(|s| matches!(s, Symbol::Nonterminal(n) if nullable.contains(n)))
+                    {
+                        nullable.insert(*nt);
+                        changed = true;
+                    }
+                }
+            }
+            nullable
+        };
+        let is_nullable = |s: &Symbol| matches!(s, Symbol::Nonterminal(n) if nullable.contains(n));
+        let mut non_empty = HashSet::new();
+        for (nt, alt) in binary {
+            if let [x, y] = alt[..] {
+                if is_nullable(&x) {
+                    non_empty.insert((nt, vec![y]));
+                }
+                if is_nullable(&y) {
+                    non_empty.insert((nt, vec![x]));
+                }
+            }
+            if !alt.is_empty() {
+                non_empty.insert((nt, alt));
+            }
+        }
+
+        // Remove unit productions by computing the unit closure of each nonterminal
+        let mut unit_closure = (0..nonterminals)
+            .map(|nt| HashSet::from([nt]))
+            .collect::<Vec<_>>();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (nt, alt) in &non_empty {
+                if let [Symbol::Nonterminal(target)] = alt[..] {
+                    let target_closure = unit_closure[target].clone();
+                    let len = unit_closure[*nt].len();
+                    unit_closure[*nt].extend(target_closure);
+                    changed |= unit_closure[*nt].len() != len;
+                }
+            }
+        }
+
+        let mut terminal_rules = HashSet::new();
+        let mut binary_rules = HashSet::new();
+        for (nt, closure) in unit_closure.iter().enumerate() {
+            for (_, alt) in non_empty
+                .iter()
+                .filter(|(target, _)| closure.contains(target))
+            {
+                match alt[..] {
+                    [Symbol::Terminal(t)] => {
+                        terminal_rules.insert((nt, t));
+                    }
+                    [Symbol::Nonterminal(x), Symbol::Nonterminal(y)] => {
+                        binary_rules.insert((nt, x, y));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let start = nt_idx[grammar.start];
+        Cnf {
+            nonterminals,
+            start,
+            nullable_start: nullable.contains(&start),
+            terminal_rules: terminal_rules.into_iter().collect(),
+            binary_rules: binary_rules.into_iter().collect(),
+        }
+    }
+
+    /// Runs the CYK algorithm on a word of terminal indices. The returned table has, at `table[len - 1][start]`, the
+    /// set (as a `Vec<bool>` indexed by nonterminal) of nonterminals deriving the subword of length `len` starting at
+    /// `start`.
+    pub(crate) fn cyk_table(&self, word: &[usize]) -> Vec<Vec<Vec<bool>>> {
+        let n = word.len();
+        let mut table = vec![];
+        table.push(
+            word.iter()
+                .map(|&t| {
+                    let mut derives = vec![false; self.nonterminals];
+                    self.terminal_rules
+                        .iter()
+                        .filter(|(_, rule_t)| *rule_t == t)
+                        .for_each(|&(nt, _)| derives[nt] = true);
+                    derives
+                })
+                .collect::<Vec<_>>(),
+        );
+        for len in 2..=n {
+            let row = (0..=n - len)
+                .map(|start| {
+                    let mut derives = vec![false; self.nonterminals];
+                    for split in 1..len {
+                        let left = &table[split - 1][start];
+                        let right = &table[len - split - 1][start + split];
+                        self.binary_rules
+                            .iter()
+                            .filter(|&&(_, x, y)| left[x] && right[y])
+                            .for_each(|&(nt, _, _)| derives[nt] = true);
+                    }
+                    derives
+                })
+                .collect::<Vec<_>>();
+            table.push(row);
+        }
+        table
+    }
+}
+
+fn index_map<'a>(symbols: &[&'a str]) -> HashMap<&'a str, usize> {
+    symbols
+        .iter()
+        .enumerate()
+        .map(|(idx, &s)| (s, idx))
+        .collect()
+}
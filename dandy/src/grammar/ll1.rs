@@ -0,0 +1,148 @@
+use crate::grammar::{Grammar, END_OF_INPUT, EPSILON};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// An LL(1) parse table of a grammar, as given by [Grammar::ll1_table], which gives the alternative to replace a
+/// nonterminal with when the next terminal of the input (the lookahead) is known
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ll1Table<'a> {
+    /// The non-empty cells, by nonterminal and then by terminal
+    pub(crate) entries: HashMap<&'a str, HashMap<&'a str, Vec<&'a str>>>,
+}
+
+/// The error given by [Grammar::ll1_table] when the grammar isn't LL(1), containing a cell of the table with two
+/// alternatives of the nonterminal
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error(
+    "The grammar is not LL(1): both '{nonterminal} → {}' and '{nonterminal} → {}' apply on {}",
+    alternative_str(.first),
+    alternative_str(.second),
+    lookahead_str(.terminal)
+)]
+pub struct Conflict<'a> {
+    pub nonterminal: &'a str,
+    /// The lookahead terminal, which is [END_OF_INPUT] for the end of the input
+    pub terminal: &'a str,
+    pub first: Vec<&'a str>,
+    pub second: Vec<&'a str>,
+}
+
+fn alternative_str(alternative: &[&str]) -> String {
+    if alternative.is_empty() {
+        "ε".to_string()
+    } else {
+        alternative.join(" ")
+    }
+}
+
+fn lookahead_str(terminal: &str) -> String {
+    if terminal == END_OF_INPUT {
+        "the end of the input".to_string()
+    } else {
+        format!("'{terminal}'")
+    }
+}
+
+impl<'a> Ll1Table<'a> {
+    /// Gets the alternative to replace `nonterminal` with when the lookahead is `terminal`, where [END_OF_INPUT] is
+    /// used for the end of the input. Gives `None` if the input can't be derived, that is, if the cell is empty.
+    pub fn get(&self, nonterminal: &str, terminal: &str) -> Option<&[&'a str]> {
+        self.entries
+            .get(nonterminal)?
+            .get(terminal)
+            .map(|alt| alt.as_slice())
+    }
+
+    /// Iterates over the non-empty cells of the table as `(nonterminal, terminal, alternative)`, in no particular
+    /// order
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str, &[&'a str])> + '_ {
+        self.entries.iter().flat_map(|(&nonterminal, row)| {
+            row.iter()
+                .map(move |(&terminal, alt)| (nonterminal, terminal, alt.as_slice()))
+        })
+    }
+}
+
+impl<'a> Grammar<'a> {
+    /// Builds the LL(1) parse table of this grammar, or gives a [Conflict] if the grammar isn't LL(1). The alternative
+    /// `α` of the nonterminal `A` is put in the cell of `A` and each terminal in FIRST(`α`), and if `α` can derive the
+    /// empty word, also in the cell of `A` and each terminal in FOLLOW(`A`), which includes [END_OF_INPUT] if `A` can
+    /// appear at the end of the input. The grammar is LL(1) if no cell gets more than one alternative.
+    ///
+    /// If there are several conflicts, the one reported is the first found when going through the productions and
+    /// their alternatives in order, and the terminals in the order of the grammar followed by the end of the input.
+    ///
+    /// ```
+    /// use dandy::grammar::{Grammar, END_OF_INPUT};
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: E E' T
+    /// Terminals: + n ( )
+    /// Start: E
+    /// E → T E'
+    /// E' → + T E' |
+    /// T → n | ( E )
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let table = grammar.ll1_table().unwrap();
+    /// assert_eq!(table.get("E'", "+"), Some(&["+", "T", "E'"][..]));
+    /// assert_eq!(table.get("E'", ")"), Some(&[][..]));
+    /// assert_eq!(table.get("E'", END_OF_INPUT), Some(&[][..]));
+    /// assert_eq!(table.get("T", "+"), None);
+    ///
+    /// // Left recursion is never LL(1)
+    /// let input = "
+    /// Nonterminals: E T
+    /// Terminals: + n
+    /// Start: E
+    /// E → E + T | T
+    /// T → n
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let conflict = grammar.ll1_table().unwrap_err();
+    /// assert_eq!(
+    ///     conflict.to_string(),
+    ///     "The grammar is not LL(1): both 'E → E + T' and 'E → T' apply on 'n'"
+    /// );
+    /// ```
+    pub fn ll1_table(&self) -> Result<Ll1Table<'a>, Conflict<'a>> {
+        let first = self.first_sets();
+        let follow = self.follow_sets();
+        let lookaheads = self.terminals.iter().chain([&END_OF_INPUT]);
+
+        let mut entries: HashMap<&'a str, HashMap<&'a str, Vec<&'a str>>> = HashMap::new();
+        for production in &self.productions {
+            for alt in &production.alternatives {
+                let alt_first = self.first_of(alt, &first);
+                let nullable = alt_first.contains(EPSILON);
+                for &terminal in lookaheads.clone() {
+                    let applies = if terminal == END_OF_INPUT {
+                        nullable && follow[production.name].contains(END_OF_INPUT)
+                    } else {
+                        alt_first.contains(terminal)
+                            || nullable && follow[production.name].contains(terminal)
+                    };
+                    if !applies {
+                        continue;
+                    }
+                    let row = entries.entry(production.name).or_default();
+                    match row.get(terminal) {
+                        Some(existing) => {
+                            return Err(Conflict {
+                                nonterminal: production.name,
+                                terminal,
+                                first: existing.clone(),
+                                second: alt.clone(),
+                            })
+                        }
+                        None => {
+                            row.insert(terminal, alt.clone());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Ll1Table { entries })
+    }
+}
@@ -1,9 +1,53 @@
 //! # Context-free grammars
+//! The grammar module includes the [Grammar] struct which represents a
+//! [context-free grammar](https://en.wikipedia.org/wiki/Context-free_grammar), parsed from the format described in
+//! the [parser module](crate::parser) and validated by [TryInto].
+//!
+//! ## Operations
+//! * [Checking if a word is generated by the grammar](Grammar::accepts), using the CYK algorithm
+//! * [Finding a derivation tree](Grammar::parse_tree) of a word generated by the grammar, which can be
+//!   [drawn as a Graphviz DOT graph](ParseNode::to_dot)
+//! * [Removing ε-productions](Grammar::remove_epsilon_productions) and
+//!   [removing unit productions](Grammar::remove_unit_productions)
+//! * Computing the [nullable nonterminals](Grammar::nullable_nonterminals), and [FIRST](Grammar::first_sets) and
+//!   [FOLLOW](Grammar::follow_sets) sets
+//! * [Building the LL(1) parse table](Grammar::ll1_table), or finding out why the grammar isn't LL(1)
+//! * [Converting right-linear grammars to NFAs](Grammar::to_nfa), and any grammar to a [PDA](Grammar::to_pda)
+//! * [Generating random words](Grammar::sample) of the grammar
+//! * Writing the grammar back to the file format with [ToString::to_string]
+//! * Serializing grammars with the `serde` feature, and deserializing them as a [GrammarOwned]
 pub use crate::parser::grammar as parse;
 
+mod cnf;
+mod ll1;
 pub mod parse;
+mod tree;
+
+use crate::nfa::{Nfa, NfaState};
+use crate::parser::{ParsedGrammar, ParsedProduction};
+use crate::pda::{self, Pda, PdaTransition};
+use cnf::Cnf;
+pub use ll1::{Conflict, Ll1Table};
+use parse::GrammarParseError;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use thiserror::Error;
+pub use tree::ParseNode;
+
+/// The marker for ε (the empty word) in the sets given by [Grammar::first_sets]. Since terminals are never empty, this
+/// marker can never be confused with a terminal.
+pub const EPSILON: &str = "";
+
+/// The marker for the end of the input in the sets given by [Grammar::follow_sets] and the lookaheads of
+/// [Grammar::ll1_table]. It is a line break, so it can never be confused with a terminal (since the terminals of a
+/// grammar are separated by whitespace) or with [EPSILON].
+pub const END_OF_INPUT: &str = "\n";
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Grammar<'a> {
     pub(crate) nonterminals: Vec<&'a str>,
     pub(crate) terminals: Vec<&'a str>,
@@ -12,7 +56,919 @@ pub struct Grammar<'a> {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Production<'a> {
     pub(crate) name: &'a str,
     pub(crate) alternatives: Vec<Vec<&'a str>>,
 }
+
+/// A grammar which owns the names of its symbols, unlike [Grammar] which borrows them (usually from the string it was
+/// parsed from). With the `serde` feature, it can be deserialized from the output of serializing a [Grammar], for
+/// example to store a grammar between runs of a program. Use [GrammarOwned::as_grammar] to check that it is valid and
+/// borrow it as a [Grammar].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GrammarOwned {
+    pub nonterminals: Vec<String>,
+    pub terminals: Vec<String>,
+    pub start: String,
+    pub productions: Vec<ProductionOwned>,
+}
+
+/// A production of a [GrammarOwned]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProductionOwned {
+    pub name: String,
+    pub alternatives: Vec<Vec<String>>,
+}
+
+impl GrammarOwned {
+    /// Borrows this grammar as a [Grammar], checking it in the same way as when converting a parsed grammar with
+    /// [TryInto]
+    ///
+    /// ```
+    /// use dandy::grammar::{Grammar, GrammarOwned};
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: S
+    /// Terminals: a b
+    /// Start: S
+    /// S → a S b |
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let owned = GrammarOwned::from(&grammar);
+    /// assert_eq!(owned.as_grammar(), Ok(grammar));
+    /// ```
+    pub fn as_grammar(&self) -> Result<Grammar<'_>, GrammarParseError<'_>> {
+        ParsedGrammar {
+            nonterminals: self.nonterminals.iter().map(String::as_str).collect(),
+            terminals: self.terminals.iter().map(String::as_str).collect(),
+            start: &self.start,
+            productions: self
+                .productions
+                .iter()
+                .map(|p| ParsedProduction {
+                    name: &p.name,
+                    alternatives: p
+                        .alternatives
+                        .iter()
+                        .map(|alt| alt.iter().map(String::as_str).collect())
+                        .collect(),
+                })
+                .collect(),
+        }
+        .try_into()
+    }
+}
+
+impl From<&Grammar<'_>> for GrammarOwned {
+    fn from(grammar: &Grammar<'_>) -> Self {
+        GrammarOwned {
+            nonterminals: grammar.nonterminals.iter().map(|s| s.to_string()).collect(),
+            terminals: grammar.terminals.iter().map(|s| s.to_string()).collect(),
+            start: grammar.start.to_string(),
+            productions: grammar
+                .productions
+                .iter()
+                .map(|p| ProductionOwned {
+                    name: p.name.to_string(),
+                    alternatives: p
+                        .alternatives
+                        .iter()
+                        .map(|alt| alt.iter().map(|s| s.to_string()).collect())
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The error given by [Grammar::to_nfa] when the grammar isn't right-linear, containing the first alternative which
+/// isn't right-linear
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("The production '{name} → {}' is not right-linear", .alternative.join(" "))]
+pub struct NotRegularError<'a> {
+    pub name: &'a str,
+    pub alternative: Vec<&'a str>,
+}
+
+impl<'a> Grammar<'a> {
+    /// Checks if this grammar generates the given word of terminals, using the
+    /// [CYK algorithm](https://en.wikipedia.org/wiki/CYK_algorithm). The grammar is converted to Chomsky normal form
+    /// internally, so it doesn't need to be in Chomsky normal form itself. The empty word is accepted if and only if
+    /// the start symbol is nullable, and words containing symbols that aren't terminals of this grammar are never
+    /// accepted.
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: S
+    /// Terminals: ( )
+    /// Start: S
+    /// S → ( S ) S |
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// assert!(grammar.accepts(&["(", "(", ")", ")", "(", ")"]));
+    /// assert!(grammar.accepts(&[]));
+    /// assert!(!grammar.accepts(&["(", ")", ")"]));
+    /// assert!(!grammar.accepts(&["(", "x", ")"]));
+    /// ```
+    pub fn accepts(&self, word: &[&str]) -> bool {
+        let Some(word) = word
+            .iter()
+            .map(|s| self.terminals.iter().position(|t| t == s))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+
+        let cnf = Cnf::from_grammar(self);
+        if word.is_empty() {
+            return cnf.nullable_start;
+        }
+        let table = cnf.cyk_table(&word);
+        table[word.len() - 1][0][cnf.start]
+    }
+
+    /// Creates an equivalent grammar without ε-productions (empty alternatives), except for possibly the start symbol
+    /// which keeps an empty alternative if it is nullable (to not change the language). This is done by computing the
+    /// set of nullable nonterminals, which are the nonterminals that can derive the empty word, and then replacing
+    /// each alternative with all versions of it where the nullable symbols are present or absent. Productions without
+    /// any alternatives left are removed.
+    ///
+    /// Note that if the start symbol is nullable and appears in some alternative, the start symbol keeps its empty
+    /// alternative but may still appear in other alternatives.
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: S A
+    /// Terminals: a b
+    /// Start: S
+    /// S → A b A
+    /// A → a |
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let expected = "
+    /// Nonterminals: S A
+    /// Terminals: a b
+    /// Start: S
+    /// S → A b A | b A | A b | b
+    /// A → a
+    /// ";
+    /// let expected: Grammar = parser::grammar(expected).unwrap().try_into().unwrap();
+    /// assert_eq!(grammar.remove_epsilon_productions(), expected);
+    /// ```
+    pub fn remove_epsilon_productions(&self) -> Grammar<'a> {
+        let nullable = self.nullable_nonterminals();
+        let productions = self
+            .productions
+            .iter()
+            .filter_map(|production| {
+                let mut alternatives: Vec<Vec<&'a str>> = vec![];
+                for alt in &production.alternatives {
+                    // Each element in 'versions' is one version of alt, with some nullable symbols removed
+                    let mut versions = vec![vec![]];
+                    for &symbol in alt {
+                        if nullable.contains(symbol) {
+                            let without = versions.clone();
+                            versions.iter_mut().for_each(|v| v.push(symbol));
+                            versions.extend(without);
+                        } else {
+                            versions.iter_mut().for_each(|v| v.push(symbol));
+                        }
+                    }
+                    for version in versions {
+                        if !alternatives.contains(&version) {
+                            alternatives.push(version);
+                        }
+                    }
+                }
+
+                alternatives.retain(|alt| !alt.is_empty());
+                if production.name == self.start && nullable.contains(self.start) {
+                    alternatives.push(vec![]);
+                }
+                (!alternatives.is_empty()).then_some(Production {
+                    name: production.name,
+                    alternatives,
+                })
+            })
+            .collect();
+
+        Grammar {
+            nonterminals: self.nonterminals.clone(),
+            terminals: self.terminals.clone(),
+            start: self.start,
+            productions,
+        }
+    }
+
+    /// Creates an equivalent grammar without unit productions, that is, alternatives consisting of a single
+    /// nonterminal such as `A → B`. This is done by computing the unit closure of each nonterminal `A` (the
+    /// nonterminals reachable from `A` by only unit productions) and replacing the unit productions of `A` with all
+    /// non-unit alternatives of the nonterminals in its unit closure. The terminals, nonterminals and start symbol are
+    /// unchanged, and productions without any alternatives left are removed.
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: S A B
+    /// Terminals: a b
+    /// Start: S
+    /// S → A | b S
+    /// A → B | a A
+    /// B → S | b
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let expected = "
+    /// Nonterminals: S A B
+    /// Terminals: a b
+    /// Start: S
+    /// S → b S | a A | b
+    /// A → a A | b | b S
+    /// B → b | b S | a A
+    /// ";
+    /// let expected: Grammar = parser::grammar(expected).unwrap().try_into().unwrap();
+    /// assert_eq!(grammar.remove_unit_productions(), expected);
+    /// ```
+    pub fn remove_unit_productions(&self) -> Grammar<'a> {
+        let is_unit = |alt: &[&str]| alt.len() == 1 && self.nonterminals.contains(&alt[0]);
+        let production = |name| self.productions.iter().find(|p| p.name == name);
+
+        let productions = self
+            .productions
+            .iter()
+            .filter_map(|start| {
+                // The unit closure of this production, in the order the nonterminals are found
+                let mut closure = vec![start.name];
+                let mut idx = 0;
+                while let Some(&name) = closure.get(idx) {
+                    for alt in production(name).iter().flat_map(|p| &p.alternatives) {
+                        if is_unit(alt) && !closure.contains(&alt[0]) {
+                            closure.push(alt[0]);
+                        }
+                    }
+                    idx += 1;
+                }
+
+                let mut alternatives = vec![];
+                for alt in closure
+                    .iter()
+                    .filter_map(|&name| production(name))
+                    .flat_map(|p| &p.alternatives)
+                {
+                    if !is_unit(alt) && !alternatives.contains(alt) {
+                        alternatives.push(alt.clone());
+                    }
+                }
+                (!alternatives.is_empty()).then_some(Production {
+                    name: start.name,
+                    alternatives,
+                })
+            })
+            .collect();
+
+        Grammar {
+            nonterminals: self.nonterminals.clone(),
+            terminals: self.terminals.clone(),
+            start: self.start,
+            productions,
+        }
+    }
+
+    /// Creates an equivalent grammar without left recursion, for LL parsing. Immediate left recursion
+    /// `A → A α₁ | … | A αₙ | β₁ | … | βₘ` is replaced by `A → β₁ A' | … | βₘ A'` and `A' → α₁ A' | … | αₙ A' | ε`,
+    /// where `A'` is a new nonterminal (with more `'` added if the name is taken). Indirect left recursion is removed
+    /// with Paull's algorithm: going through the nonterminals in order, alternatives of `Aᵢ` starting with an earlier
+    /// nonterminal `Aⱼ` are first replaced by the alternatives of `Aⱼ` followed by the rest of the alternative, and
+    /// then the immediate left recursion of `Aᵢ` is removed. Alternatives `A → A` are removed since they don't change
+    /// the language.
+    ///
+    /// The new grammar always generates the same language, but it is only guaranteed to be free of left recursion if
+    /// this grammar has no ε-productions and no cycles `A ⇒⁺ A`, which can be removed first with
+    /// [Grammar::remove_epsilon_productions] and [Grammar::remove_unit_productions]. The new nonterminals are placed
+    /// after the nonterminals they were created for.
+    ///
+    /// Since the symbols of a grammar are borrowed, and the new nonterminals aren't symbols of this grammar, the new
+    /// grammar is returned as a [GrammarOwned], which can be borrowed as a [Grammar] with [GrammarOwned::as_grammar].
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: E T F
+    /// Terminals: + * ( ) n
+    /// Start: E
+    /// E → E + T | T
+    /// T → T * F | F
+    /// F → ( E ) | n
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let expected = "
+    /// Nonterminals: E E' T T' F
+    /// Terminals: + * ( ) n
+    /// Start: E
+    /// E → T E'
+    /// E' → + T E' |
+    /// T → F T'
+    /// T' → * F T' |
+    /// F → ( E ) | n
+    /// ";
+    /// let expected: Grammar = parser::grammar(expected).unwrap().try_into().unwrap();
+    /// let eliminated = grammar.eliminate_left_recursion();
+    /// assert_eq!(eliminated.as_grammar(), Ok(expected));
+    /// assert_eq!(eliminated.nonterminals, ["E", "E'", "T", "T'", "F"]);
+    /// ```
+    pub fn eliminate_left_recursion(&self) -> GrammarOwned {
+        /// A symbol of the new grammar, which is either a symbol of this grammar or a new nonterminal
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        enum Symbol<'a> {
+            Old(&'a str),
+            New(usize),
+        }
+
+        let mut alternatives = self
+            .nonterminals
+            .iter()
+            .map(|&nt| {
+                self.productions.iter().find(|p| p.name == nt).map(|p| {
+                    p.alternatives
+                        .iter()
+                        .map(|alt| alt.iter().map(|&s| Symbol::Old(s)).collect::<Vec<_>>())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut new_names: Vec<String> = vec![];
+        // The new nonterminal created for each nonterminal, and its alternatives
+        let mut primes = vec![None; self.nonterminals.len()];
+
+        for i in 0..self.nonterminals.len() {
+            let Some(alts) = alternatives[i].take() else {
+                continue;
+            };
+            let mut substituted = alts;
+            for (&earlier, earlier_alts) in self.nonterminals.iter().zip(&alternatives).take(i) {
+                let earlier = Symbol::Old(earlier);
+                let mut new_alts = vec![];
+                for alt in substituted {
+                    let replaced = if alt.first() == Some(&earlier) {
+                        earlier_alts
+                            .iter()
+                            .flatten()
+                            .map(|delta| delta.iter().chain(&alt[1..]).copied().collect())
+                            .collect()
+                    } else {
+                        vec![alt]
+                    };
+                    for alt in replaced {
+                        if !new_alts.contains(&alt) {
+                            new_alts.push(alt);
+                        }
+                    }
+                }
+                substituted = new_alts;
+            }
+
+            let this = Symbol::Old(self.nonterminals[i]);
+            let (recursive, other): (Vec<_>, Vec<_>) = substituted
+                .into_iter()
+                .filter(|alt| alt[..] != [this])
+                .partition(|alt| alt.first() == Some(&this));
+            if recursive.is_empty() || other.is_empty() {
+                // Without alternatives which aren't left recursive, the nonterminal can't derive any word
+                alternatives[i] = (!other.is_empty()).then_some(other);
+                continue;
+            }
+
+            let mut name = format!("{}'", self.nonterminals[i]);
+            while self.nonterminals.contains(&name.as_str())
+                || self.terminals.contains(&name.as_str())
+                || new_names.contains(&name)
+            {
+                name.push('\'');
+            }
+            let prime = Symbol::New(new_names.len());
+            new_names.push(name);
+            alternatives[i] = Some(
+                other
+                    .into_iter()
+                    .map(|mut beta| {
+                        beta.push(prime);
+                        beta
+                    })
+                    .collect(),
+            );
+            let mut prime_alts = recursive
+                .into_iter()
+                .map(|mut alpha| {
+                    alpha.remove(0);
+                    alpha.push(prime);
+                    alpha
+                })
+                .collect::<Vec<_>>();
+            prime_alts.push(vec![]);
+            primes[i] = Some((prime, prime_alts));
+        }
+
+        let name = |symbol: Symbol<'a>| match symbol {
+            Symbol::Old(s) => s.to_string(),
+            Symbol::New(idx) => new_names[idx].clone(),
+        };
+        let production = |nt: Symbol<'a>, alts: Vec<Vec<Symbol<'a>>>| ProductionOwned {
+            name: name(nt),
+            alternatives: alts
+                .into_iter()
+                .map(|alt| alt.into_iter().map(name).collect())
+                .collect(),
+        };
+
+        let mut nonterminals = vec![];
+        let mut productions = vec![];
+        for ((&nt, alts), prime) in self.nonterminals.iter().zip(alternatives).zip(primes) {
+            nonterminals.push(nt.to_string());
+            if let Some(alts) = alts {
+                productions.push(production(Symbol::Old(nt), alts));
+            }
+            if let Some((prime, prime_alts)) = prime {
+                nonterminals.push(name(prime));
+                productions.push(production(prime, prime_alts));
+            }
+        }
+
+        GrammarOwned {
+            nonterminals,
+            terminals: self.terminals.iter().map(|s| s.to_string()).collect(),
+            start: self.start.to_string(),
+            productions,
+        }
+    }
+
+    /// Computes the set of nullable nonterminals, that is, the nonterminals which can derive the empty word. A
+    /// nonterminal is nullable if it has an alternative consisting only of nullable nonterminals (such as an empty
+    /// alternative), which is repeated until no more nullable nonterminals are found.
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::parser;
+    /// use std::collections::HashSet;
+    ///
+    /// let input = "
+    /// Nonterminals: S A B
+    /// Terminals: a b
+    /// Start: S
+    /// S → A B | b
+    /// A → a A |
+    /// B → b | A A
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// assert_eq!(grammar.nullable_nonterminals(), HashSet::from(["S", "A", "B"]));
+    /// ```
+    pub fn nullable_nonterminals(&self) -> HashSet<&'a str> {
+        let mut nullable = HashSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                if !nullable.contains(production.name)
+                    && production
+                        .alternatives
+                        .iter()
+                        .any(|alt| alt.iter().all(|s| nullable.contains(s)))
+                {
+                    nullable.insert(production.name);
+                    changed = true;
+                }
+            }
+        }
+        nullable
+    }
+
+    /// Computes the FIRST set of each nonterminal, that is, the set of terminals that can begin a word derived from
+    /// the nonterminal. If the nonterminal can derive the empty word, the set also includes [EPSILON] (which is the
+    /// empty string).
+    ///
+    /// ```
+    /// use dandy::grammar::{Grammar, EPSILON};
+    /// use dandy::parser;
+    /// use std::collections::HashSet;
+    ///
+    /// let input = "
+    /// Nonterminals: E T
+    /// Terminals: n + ( )
+    /// Start: E
+    /// E → T + E | T
+    /// T → n | ( E ) |
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let first = grammar.first_sets();
+    /// assert_eq!(first["T"], HashSet::from(["n", "(", EPSILON]));
+    /// assert_eq!(first["E"], HashSet::from(["n", "(", "+", EPSILON]));
+    /// ```
+    pub fn first_sets(&self) -> HashMap<&'a str, HashSet<&'a str>> {
+        let mut first = self
+            .nonterminals
+            .iter()
+            .map(|&nt| (nt, HashSet::new()))
+            .collect::<HashMap<_, _>>();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                for alt in &production.alternatives {
+                    let alt_first = self.first_of(alt, &first);
+                    let set = first.get_mut(production.name).unwrap();
+                    let len = set.len();
+                    set.extend(alt_first);
+                    changed |= set.len() != len;
+                }
+            }
+        }
+        first
+    }
+
+    /// Computes the FOLLOW set of each nonterminal, that is, the set of terminals that can appear directly after the
+    /// nonterminal in some sentential form derived from the start symbol. If the nonterminal can appear at the end of
+    /// such a sentential form (which the start symbol always can), the set also includes [END_OF_INPUT].
+    ///
+    /// ```
+    /// use dandy::grammar::{Grammar, END_OF_INPUT};
+    /// use dandy::parser;
+    /// use std::collections::HashSet;
+    ///
+    /// let input = "
+    /// Nonterminals: E T
+    /// Terminals: n + ( )
+    /// Start: E
+    /// E → T + E | T
+    /// T → n | ( E ) |
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let follow = grammar.follow_sets();
+    /// assert_eq!(follow["E"], HashSet::from([")", END_OF_INPUT]));
+    /// assert_eq!(follow["T"], HashSet::from(["+", ")", END_OF_INPUT]));
+    /// ```
+    pub fn follow_sets(&self) -> HashMap<&'a str, HashSet<&'a str>> {
+        let first = self.first_sets();
+        let mut follow = self
+            .nonterminals
+            .iter()
+            .map(|&nt| (nt, HashSet::new()))
+            .collect::<HashMap<_, _>>();
+        follow.get_mut(self.start).unwrap().insert(END_OF_INPUT);
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                for alt in &production.alternatives {
+                    for (idx, &symbol) in alt.iter().enumerate() {
+                        if !self.nonterminals.contains(&symbol) {
+                            continue;
+                        }
+                        // FOLLOW(symbol) includes FIRST of the rest of the alternative, and if the rest of the
+                        // alternative is nullable, FOLLOW of the production
+                        let mut to_add = self.first_of(&alt[idx + 1..], &first);
+                        if to_add.remove(EPSILON) {
+                            to_add.extend(&follow[production.name]);
+                        }
+                        let set = follow.get_mut(symbol).unwrap();
+                        let len = set.len();
+                        set.extend(to_add);
+                        changed |= set.len() != len;
+                    }
+                }
+            }
+        }
+        follow
+    }
+
+    /// Converts this grammar to a NFA, if the grammar is right-linear, that is, if every alternative is of the form
+    /// `A → a B`, `A → a` or `A → ε` (where `a` is a terminal and `B` is a nonterminal). The alphabet of the NFA is the
+    /// terminals of the grammar, and each nonterminal becomes a state with the same name (the start symbol being the
+    /// initial state). A production `A → a B` becomes a transition from `A` to `B` upon `a`, `A → a` becomes a
+    /// transition from `A` to an additional accepting state upon `a`, and `A → ε` makes `A` accepting.
+    ///
+    /// If some alternative isn't right-linear, an error containing the first such alternative is returned.
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::parser;
+    ///
+    /// // Words of a:s and b:s ending with "ab"
+    /// let input = "
+    /// Nonterminals: S A
+    /// Terminals: a b
+    /// Start: S
+    /// S → a S | b S | a A
+    /// A → b
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let nfa = grammar.to_nfa().unwrap();
+    /// assert!(nfa.accepts_graphemes("bbab"));
+    /// assert!(!nfa.accepts_graphemes("abba"));
+    /// ```
+    pub fn to_nfa(&self) -> Result<Nfa, NotRegularError<'a>> {
+        let state_idx = |name| self.nonterminals.iter().position(|&nt| nt == name);
+        let terminal_idx = |name| self.terminals.iter().position(|&t| t == name);
+        let final_state = self.nonterminals.len();
+
+        let mut states = self
+            .nonterminals
+            .iter()
+            .map(|&nt| NfaState {
+                name: Rc::from(nt),
+                initial: nt == self.start,
+                accepting: false,
+                epsilon_transitions: vec![],
+                transitions: vec![vec![]; self.terminals.len()],
+            })
+            .collect::<Vec<_>>();
+
+        for production in &self.productions {
+            let from = state_idx(production.name).unwrap();
+            for alt in &production.alternatives {
+                match alt[..] {
+                    [] => states[from].accepting = true,
+                    [t] if terminal_idx(t).is_some() => {
+                        states[from].transitions[terminal_idx(t).unwrap()].push(final_state);
+                    }
+                    [t, nt] if terminal_idx(t).is_some() && state_idx(nt).is_some() => {
+                        let to = state_idx(nt).unwrap();
+                        states[from].transitions[terminal_idx(t).unwrap()].push(to);
+                    }
+                    _ => {
+                        return Err(NotRegularError {
+                            name: production.name,
+                            alternative: alt.clone(),
+                        })
+                    }
+                }
+            }
+        }
+
+        let final_name = if self.nonterminals.contains(&"final") {
+            (0..)
+                .map(|i| i.to_string())
+                .find(|n| !self.nonterminals.contains(&n.as_str()))
+                .unwrap()
+        } else {
+            "final".to_string()
+        };
+        states.push(NfaState {
+            name: Rc::from(final_name),
+            initial: false,
+            accepting: true,
+            epsilon_transitions: vec![],
+            transitions: vec![vec![]; self.terminals.len()],
+        });
+
+        Ok(Nfa {
+            alphabet: self.terminals.iter().map(|&t| Rc::from(t)).collect(),
+            states,
+            initial_state: state_idx(self.start).unwrap(),
+        })
+    }
+
+    /// Generates a random word of this grammar by a random leftmost derivation from the start symbol, where the
+    /// derivation tree has at most `max_depth` levels of nonterminals. Gives `None` if the start symbol can't derive
+    /// any word within that bound.
+    ///
+    /// Each nonterminal is replaced by a randomly chosen alternative, but alternatives which can't derive a word of
+    /// terminals within the remaining depth are never chosen, so the derivation always finishes once started. The
+    /// closer the derivation gets to `max_depth`, the more often one of the alternatives finishing soonest is chosen,
+    /// so that words don't grow exponentially with the depth for grammars such as `S → S S | a`.
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::parser;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let input = "
+    /// Nonterminals: S
+    /// Terminals: ( )
+    /// Start: S
+    /// S → ( S ) S |
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let mut rng = StdRng::seed_from_u64(3);
+    /// for _ in 0..10 {
+    ///     let word = grammar.sample(&mut rng, 5).unwrap();
+    ///     assert!(grammar.accepts(&word));
+    /// }
+    /// // S needs at least one level to be replaced by ε
+    /// assert_eq!(grammar.sample(&mut rng, 0), None);
+    /// ```
+    pub fn sample(&self, rng: &mut impl Rng, max_depth: usize) -> Option<Vec<&'a str>> {
+        let heights = self.min_heights();
+        if heights.get(self.start).is_none_or(|&h| h > max_depth) {
+            return None;
+        }
+
+        let mut word = vec![];
+        // The symbols left to derive, with the leftmost symbol last, and the number of levels they may use
+        let mut stack = vec![(self.start, max_depth)];
+        while let Some((symbol, depth)) = stack.pop() {
+            if !self.nonterminals.contains(&symbol) {
+                word.push(symbol);
+                continue;
+            }
+            let possible = self
+                .productions
+                .iter()
+                .filter(|p| p.name == symbol)
+                .flat_map(|p| &p.alternatives)
+                .filter_map(|alt| {
+                    self.alt_height(alt, &heights)
+                        .filter(|&h| h <= depth)
+                        .map(|h| (alt, h))
+                })
+                .collect::<Vec<_>>();
+            let finish_soon = rng.gen_range(0..max_depth) >= depth;
+            let (alt, _) = if finish_soon {
+                let lowest = possible.iter().map(|&(_, h)| h).min().unwrap();
+                let lowest = possible
+                    .iter()
+                    .filter(|&&(_, h)| h == lowest)
+                    .collect::<Vec<_>>();
+                *lowest[rng.gen_range(0..lowest.len())]
+            } else {
+                possible[rng.gen_range(0..possible.len())]
+            };
+            stack.extend(alt.iter().rev().map(|&s| (s, depth - 1)));
+        }
+        Some(word)
+    }
+
+    /// Computes the minimum number of levels of nonterminals in a derivation tree of a word of terminals from each
+    /// nonterminal. Nonterminals which can't derive any word of terminals are left out.
+    fn min_heights(&self) -> HashMap<&'a str, usize> {
+        let mut heights: HashMap<&'a str, usize> = HashMap::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                for alt in &production.alternatives {
+                    if let Some(height) = self.alt_height(alt, &heights) {
+                        if heights.get(production.name).is_none_or(|&h| height < h) {
+                            heights.insert(production.name, height);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+        heights
+    }
+
+    /// Computes the minimum number of levels needed to derive a word of terminals from an alternative, which is one
+    /// more than for its deepest nonterminal, given the minimum heights of the nonterminals found so far
+    fn alt_height(&self, alt: &[&'a str], heights: &HashMap<&'a str, usize>) -> Option<usize> {
+        alt.iter()
+            .filter(|s| self.nonterminals.contains(s))
+            .map(|s| heights.get(s).copied())
+            .try_fold(1, |height, h| h.map(|h| height.max(h + 1)))
+    }
+
+    /// Converts this grammar to a PDA accepting by empty stack, using the standard construction with a single state
+    /// `q`. The input alphabet is the terminals, and the stack alphabet is the nonterminals followed by the terminals.
+    /// The stack initially contains the start symbol. For each alternative `A → α` there is an ε-transition replacing
+    /// `A` on top of the stack with `α`, and for each terminal `a` there is a transition reading `a` and popping `a`
+    /// from the stack. The PDA thereby simulates leftmost derivations of the grammar.
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: S
+    /// Terminals: a b
+    /// Start: S
+    /// S → a S b |
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let pda = grammar.to_pda();
+    /// assert_eq!(pda.states().len(), 1);
+    /// assert_eq!(pda.transitions().len(), 4);
+    /// assert!(pda.accepts(&["a", "a", "b", "b"]));
+    /// assert!(!pda.accepts(&["a", "b", "b"]));
+    /// ```
+    pub fn to_pda(&self) -> Pda {
+        let stack_symbols = self
+            .nonterminals
+            .iter()
+            .chain(&self.terminals)
+            .copied()
+            .collect::<Vec<_>>();
+        let symbol_idx = |name: &str| stack_symbols.iter().position(|&s| s == name).unwrap();
+
+        let mut transitions = vec![];
+        for production in &self.productions {
+            for alt in &production.alternatives {
+                transitions.push(PdaTransition {
+                    from: 0,
+                    input: None,
+                    pop: symbol_idx(production.name),
+                    to: 0,
+                    push: alt.iter().map(|s| symbol_idx(s)).collect(),
+                });
+            }
+        }
+        for (idx, terminal) in self.terminals.iter().enumerate() {
+            transitions.push(PdaTransition {
+                from: 0,
+                input: Some(idx),
+                pop: symbol_idx(terminal),
+                to: 0,
+                push: vec![],
+            });
+        }
+
+        Pda {
+            alphabet: self.terminals.iter().map(|&t| Rc::from(t)).collect(),
+            initial_stack: vec![symbol_idx(self.start)],
+            stack_alphabet: stack_symbols.iter().map(|&s| Rc::from(s)).collect(),
+            states: vec![Rc::from("q")],
+            initial_state: 0,
+            transitions,
+            max_depth: pda::DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Computes FIRST of a sequence of symbols given the FIRST sets of the nonterminals (which may be incomplete while
+    /// computing them). The result includes [EPSILON] if every symbol in the sequence is nullable (according to the
+    /// given FIRST sets).
+    pub(crate) fn first_of(
+        &self,
+        symbols: &[&'a str],
+        first: &HashMap<&'a str, HashSet<&'a str>>,
+    ) -> HashSet<&'a str> {
+        let mut result = HashSet::new();
+        for symbol in symbols {
+            match first.get(symbol) {
+                Some(symbol_first) => {
+                    result.extend(symbol_first.iter().filter(|&&s| s != EPSILON));
+                    if !symbol_first.contains(EPSILON) {
+                        return result;
+                    }
+                }
+                None => {
+                    // Terminal
+                    result.insert(*symbol);
+                    return result;
+                }
+            }
+        }
+        result.insert(EPSILON);
+        result
+    }
+}
+
+/// Writes the grammar in the format described in the [parser module](crate::parser), so that parsing the output with
+/// [crate::parser::grammar] gives back the same grammar. Alternatives are separated by `|`, and an empty alternative
+/// is written as nothing at all. Note that the format requires at least one production, so a grammar without
+/// productions can't be parsed back.
+///
+/// ```
+/// use dandy::grammar::Grammar;
+/// use dandy::parser;
+///
+/// let input = "
+/// Nonterminals: S
+/// Terminals: a b
+/// Start: S
+/// S → a S b |
+/// ";
+/// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+/// let output = grammar.to_string();
+/// assert_eq!(output, "Nonterminals: S\nTerminals: a b\nStart: S\nS → a S b | \n");
+/// let reparsed: Grammar = parser::grammar(&output).unwrap().try_into().unwrap();
+/// assert_eq!(grammar, reparsed);
+/// ```
+impl Display for Grammar<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Nonterminals: {}", self.nonterminals.join(" "))?;
+        writeln!(f, "Terminals: {}", self.terminals.join(" "))?;
+        writeln!(f, "Start: {}", self.start)?;
+        for production in &self.productions {
+            let alternatives = production
+                .alternatives
+                .iter()
+                .map(|alt| alt.join(" "))
+                .collect::<Vec<_>>();
+            writeln!(f, "{} → {}", production.name, alternatives.join(" | "))?;
+        }
+        Ok(())
+    }
+}
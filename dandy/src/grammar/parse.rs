@@ -25,49 +25,79 @@ impl<'a> TryFrom<ParsedGrammar<'a>> for Grammar<'a> {
     type Error = GrammarParseError<'a>;
 
     fn try_from(value: ParsedGrammar<'a>) -> Result<Self, Self::Error> {
+        Grammar::validate_all(value).map_err(|mut errors| errors.swap_remove(0))
+    }
+}
+
+impl<'a> Grammar<'a> {
+    /// Converts a [ParsedGrammar] to a [Grammar] like [TryFrom::try_from], but instead of stopping at the first error,
+    /// this collects all errors in the parsed grammar. The first error is the one [TryFrom::try_from] would give.
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::grammar::parse::GrammarParseError;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: S
+    /// Terminals: a a
+    /// Start: S
+    /// S → a T | b
+    /// ";
+    /// let errors = Grammar::validate_all(parser::grammar(input).unwrap()).unwrap_err();
+    /// assert_eq!(errors, vec![
+    ///     GrammarParseError::DuplicateTerminal("a"),
+    ///     GrammarParseError::ProductionsNotSymbol("T"),
+    ///     GrammarParseError::ProductionsNotSymbol("b"),
+    /// ]);
+    /// ```
+    pub fn validate_all(parsed: ParsedGrammar<'a>) -> Result<Self, Vec<GrammarParseError<'a>>> {
         use GrammarParseError::*;
-        let ParsedGrammar { nonterminals, terminals, start, productions } = value;
+        let ParsedGrammar { nonterminals, terminals, start, productions } = parsed;
+        let mut errors = vec![];
 
-        {
-            let mut nonterminals_set = HashSet::new();
-            nonterminals
-                .iter()
-                .try_for_each(|c| nonterminals_set.insert(c).then_some(()).ok_or(c))
-                .map_err(|d| DuplicateNonterminal(d))?;
+        let mut nonterminals_set = HashSet::new();
+        let mut reported = HashSet::new();
+        nonterminals
+            .iter()
+            .filter(|c| !nonterminals_set.insert(**c) && reported.insert(**c))
+            .for_each(|d| errors.push(DuplicateNonterminal(d)));
 
-            let mut terminals_set = HashSet::new();
-            terminals
-                .iter()
-                .try_for_each(|c| terminals_set.insert(c).then_some(()).ok_or(c))
-                .map_err(|d| DuplicateTerminal(d))?;
+        let mut terminals_set = HashSet::new();
+        let mut reported = HashSet::new();
+        terminals
+            .iter()
+            .filter(|c| !terminals_set.insert(**c) && reported.insert(**c))
+            .for_each(|d| errors.push(DuplicateTerminal(d)));
 
-            if let Some(x) = nonterminals_set.intersection(&terminals_set).next() {
-                return Err(TerminalNonterminal(x));
-            }
+        nonterminals
+            .iter()
+            .filter(|x| terminals_set.contains(*x))
+            .for_each(|x| errors.push(TerminalNonterminal(x)));
 
-            if !nonterminals_set.contains(&start) {
-                return Err(StartNotNonterminal);
-            }
+        if !nonterminals_set.contains(&start) {
+            errors.push(StartNotNonterminal);
+        }
 
-            let mut productions_set = HashSet::new();
-            productions
-                .iter()
-                .try_for_each(|p| {
-                    if !productions_set.insert(p.name) {
-                        return Err(DuplicateProduction(p.name));
-                    }
-                    if !nonterminals_set.contains(&p.name) {
-                        return Err(ProductionsNotNonterminal(p.name));
-                    }
-                    for alt in p.alternatives.iter() {
-                        for s in alt {
-                            if !(nonterminals_set.contains(&s) || terminals_set.contains(&s)) {
-                                return Err(ProductionsNotSymbol(s));
-                            }
-                        }
+        let mut productions_set = HashSet::new();
+        for p in productions.iter() {
+            if !productions_set.insert(p.name) {
+                errors.push(DuplicateProduction(p.name));
+            }
+            if !nonterminals_set.contains(&p.name) {
+                errors.push(ProductionsNotNonterminal(p.name));
+            }
+            for alt in p.alternatives.iter() {
+                for s in alt {
+                    if !(nonterminals_set.contains(s) || terminals_set.contains(s)) {
+                        errors.push(ProductionsNotSymbol(s));
                     }
-                    Ok(())
-                })?;
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
         }
 
         let grammar = Grammar {
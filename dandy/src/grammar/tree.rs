@@ -0,0 +1,228 @@
+use crate::dot;
+use crate::grammar::Grammar;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// A node in a derivation tree of a grammar, as given by [Grammar::parse_tree]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseNode<'a> {
+    /// A nonterminal, whose children are the symbols of the alternative it was replaced by (no children for an empty
+    /// alternative)
+    Nonterminal(&'a str, Vec<ParseNode<'a>>),
+    /// A terminal, which is always a leaf
+    Terminal(&'a str),
+}
+
+impl<'a> ParseNode<'a> {
+    /// Gets the symbol of this node
+    pub fn symbol(&self) -> &'a str {
+        match self {
+            ParseNode::Nonterminal(symbol, _) | ParseNode::Terminal(symbol) => symbol,
+        }
+    }
+
+    /// Gets the word derived by this node, that is, its terminal leaves from left to right
+    pub fn word(&self) -> Vec<&'a str> {
+        match self {
+            ParseNode::Nonterminal(_, children) => children.iter().flat_map(|c| c.word()).collect(),
+            ParseNode::Terminal(terminal) => vec![terminal],
+        }
+    }
+
+    /// Generates a [Graphviz](https://graphviz.org/) DOT graph of this tree, which can be rendered with for example
+    /// `dot -Tsvg`. Nodes are numbered in preorder, so the node with number `i` gets the node id `n{i}` and its
+    /// symbol as label, which keeps repeated symbols apart. Terminals are drawn as boxes, and a nonterminal replaced by
+    /// an empty alternative gets a single child `ε` drawn without a border. The children are kept in order.
+    ///
+    /// ```
+    /// use dandy::grammar::ParseNode;
+    ///
+    /// let tree = ParseNode::Nonterminal("S", vec![
+    ///     ParseNode::Terminal("a"),
+    ///     ParseNode::Nonterminal("S", vec![]),
+    ///     ParseNode::Terminal("a"),
+    /// ]);
+    /// assert_eq!(tree.to_dot(), "\
+    /// digraph {
+    ///     ordering=out;
+    ///     n0 [label=\"S\"];
+    ///     n1 [label=\"a\", shape=box];
+    ///     n2 [label=\"S\"];
+    ///     n3 [label=\"ε\", shape=none];
+    ///     n4 [label=\"a\", shape=box];
+    ///     n0 -> n1;
+    ///     n0 -> n2;
+    ///     n2 -> n3;
+    ///     n0 -> n4;
+    /// }
+    /// ");
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut nodes = String::new();
+        let mut edges = String::new();
+        self.write_dot(&mut 0, &mut nodes, &mut edges);
+        format!("digraph {{\n    ordering=out;\n{nodes}{edges}}}\n")
+    }
+
+    /// Writes the nodes and edges of this tree in DOT, where `next_id` is the number of the next node
+    fn write_dot(&self, next_id: &mut usize, nodes: &mut String, edges: &mut String) {
+        let id = *next_id;
+        *next_id += 1;
+        match self {
+            ParseNode::Nonterminal(name, children) => {
+                let _ = writeln!(nodes, "    n{id} [label=\"{}\"];", dot::escape(name));
+                if children.is_empty() {
+                    let _ = writeln!(nodes, "    n{} [label=\"ε\", shape=none];", *next_id);
+                    let _ = writeln!(edges, "    n{id} -> n{};", *next_id);
+                    *next_id += 1;
+                }
+                for child in children {
+                    let _ = writeln!(edges, "    n{id} -> n{};", *next_id);
+                    child.write_dot(next_id, nodes, edges);
+                }
+            }
+            ParseNode::Terminal(terminal) => {
+                let _ = writeln!(
+                    nodes,
+                    "    n{id} [label=\"{}\", shape=box];",
+                    dot::escape(terminal)
+                );
+            }
+        }
+    }
+}
+
+/// How a nonterminal derives a subword: the alternative used, and the position in the word where each of the symbols
+/// of the alternative ends
+type Witness<'a, 'b> = (&'b [&'a str], Vec<usize>);
+
+impl<'a> Grammar<'a> {
+    /// Finds a derivation tree of the given word of terminals, if this grammar generates it. The root of the tree is
+    /// the start symbol, and the children of each nonterminal are the symbols of one of its alternatives, in order.
+    /// Unlike [Grammar::accepts], this works directly on the grammar rather than on its Chomsky normal form, so the
+    /// tree only uses the productions of this grammar.
+    ///
+    /// If the grammar is ambiguous, only one of the derivation trees of the word is returned. Which one is unspecified,
+    /// but it is the same every time for the same grammar and word.
+    ///
+    /// ```
+    /// use dandy::grammar::{Grammar, ParseNode};
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: S
+    /// Terminals: ( )
+    /// Start: S
+    /// S → ( S ) S |
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let empty = || ParseNode::Nonterminal("S", vec![]);
+    /// assert_eq!(
+    ///     grammar.parse_tree(&["(", ")"]),
+    ///     Some(ParseNode::Nonterminal("S", vec![
+    ///         ParseNode::Terminal("("),
+    ///         empty(),
+    ///         ParseNode::Terminal(")"),
+    ///         empty(),
+    ///     ]))
+    /// );
+    /// assert_eq!(grammar.parse_tree(&["(", ")", ")"]), None);
+    /// ```
+    pub fn parse_tree(&self, word: &[&str]) -> Option<ParseNode<'a>> {
+        if word.iter().any(|s| !self.terminals.contains(s)) {
+            return None;
+        }
+
+        // witnesses[start][end] has, for each nonterminal deriving word[start..end], how it derives it. Subwords are
+        // processed from the shortest, and the nonterminals deriving each subword are found by iterating until nothing
+        // changes, since they may depend on each other through other symbols deriving ε. A witness only refers to
+        // witnesses found before it, so the tree built from them is finite.
+        let n = word.len();
+        let mut witnesses: Vec<Vec<HashMap<&'a str, Witness<'a, '_>>>> =
+            vec![vec![HashMap::new(); n + 1]; n + 1];
+        for len in 0..=n {
+            for start in 0..=n - len {
+                let end = start + len;
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for production in &self.productions {
+                        if witnesses[start][end].contains_key(production.name) {
+                            continue;
+                        }
+                        let found = production.alternatives.iter().find_map(|alt| {
+                            split(alt, word, start, end, &witnesses).map(|ends| (&alt[..], ends))
+                        });
+                        if let Some(witness) = found {
+                            witnesses[start][end].insert(production.name, witness);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        witnesses[0][n]
+            .contains_key(self.start)
+            .then(|| build_tree(self.start, 0, n, &witnesses))
+    }
+}
+
+/// Finds where each symbol of the alternative ends if the alternative derives `word[start..end]`, using the
+/// derivations found so far
+fn split(
+    alt: &[&str],
+    word: &[&str],
+    start: usize,
+    end: usize,
+    witnesses: &[Vec<HashMap<&str, Witness<'_, '_>>>],
+) -> Option<Vec<usize>> {
+    // previous[p][pos] is where symbol p - 1 starts if the first p symbols derive word[start..pos]
+    let mut previous = vec![vec![None; end + 1]; alt.len() + 1];
+    previous[0][start] = Some(start);
+    for (p, symbol) in alt.iter().enumerate() {
+        for from in start..=end {
+            if previous[p][from].is_none() {
+                continue;
+            }
+            if from < end && word[from] == *symbol && previous[p + 1][from + 1].is_none() {
+                previous[p + 1][from + 1] = Some(from);
+            }
+            for to in from..=end {
+                if witnesses[from][to].contains_key(symbol) && previous[p + 1][to].is_none() {
+                    previous[p + 1][to] = Some(from);
+                }
+            }
+        }
+    }
+
+    previous[alt.len()][end]?;
+    let mut ends = vec![end; alt.len()];
+    let mut pos = end;
+    for p in (1..alt.len()).rev() {
+        pos = previous[p + 1][pos].unwrap();
+        ends[p - 1] = pos;
+    }
+    Some(ends)
+}
+
+fn build_tree<'a>(
+    nonterminal: &'a str,
+    start: usize,
+    end: usize,
+    witnesses: &[Vec<HashMap<&'a str, Witness<'a, '_>>>],
+) -> ParseNode<'a> {
+    let (alt, ends) = &witnesses[start][end][nonterminal];
+    let mut children = vec![];
+    let mut pos = start;
+    for (&symbol, &symbol_end) in alt.iter().zip(ends) {
+        // Terminals always derive exactly themselves, and nonterminals always have a witness
+        if witnesses[pos][symbol_end].contains_key(symbol) {
+            children.push(build_tree(symbol, pos, symbol_end, witnesses));
+        } else {
+            children.push(ParseNode::Terminal(symbol));
+        }
+        pos = symbol_end;
+    }
+    ParseNode::Nonterminal(nonterminal, children)
+}
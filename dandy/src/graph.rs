@@ -0,0 +1,105 @@
+//! # Conversion to petgraph
+//! With the `petgraph` feature enabled, DFAs and NFAs can be converted to [petgraph](https://docs.rs/petgraph) graphs
+//! with [Dfa::to_petgraph] and [Nfa::to_petgraph], to run graph algorithms such as finding strongly connected
+//! components on them. The node with index `i` in the graph is the state with index `i` in the automaton, and the
+//! edges of each state are added in the order of the alphabet (with ε-moves first).
+//!
+//! ```
+//! use dandy::dfa::Dfa;
+//! use dandy::parser;
+//! use petgraph::algo::kosaraju_scc;
+//!
+//! let dfa: Dfa = parser::dfa("
+//!        a  b
+//! -> s₀ s₁ s₀
+//!  * s₁ s₂ s₀
+//!    s₂ s₂ s₂
+//! ").unwrap().try_into().unwrap();
+//! let graph = dfa.to_petgraph();
+//! assert_eq!(graph.node_count(), 3);
+//! assert_eq!(graph.edge_count(), 6);
+//! // s₀ and s₁ can reach each other, while s₂ only reaches itself
+//! assert_eq!(kosaraju_scc(&graph).len(), 2);
+//! ```
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use petgraph::graph::{Graph, NodeIndex};
+use std::rc::Rc;
+
+/// The weight of a node in a graph given by [Dfa::to_petgraph] or [Nfa::to_petgraph]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StateLabel {
+    pub name: Rc<str>,
+    pub initial: bool,
+    pub accepting: bool,
+}
+
+/// The weight of an edge in a graph given by [Dfa::to_petgraph] or [Nfa::to_petgraph]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeLabel {
+    /// A transition upon seeing an element of the alphabet
+    Element(Rc<str>),
+    /// An ε-move
+    Epsilon,
+}
+
+impl Dfa {
+    /// Converts this DFA to a directed petgraph graph, where node `i` is state `i`, with one edge per transition. See
+    /// the [module-level documentation](crate::graph) for more info.
+    pub fn to_petgraph(&self) -> Graph<StateLabel, EdgeLabel> {
+        let mut graph =
+            Graph::with_capacity(self.states.len(), self.states.len() * self.alphabet.len());
+        for state in &self.states {
+            graph.add_node(StateLabel {
+                name: state.name.clone(),
+                initial: state.initial,
+                accepting: state.accepting,
+            });
+        }
+        for (from, state) in self.states.iter().enumerate() {
+            for (&to, elem) in state.transitions.iter().zip(self.alphabet.iter()) {
+                graph.add_edge(
+                    NodeIndex::new(from),
+                    NodeIndex::new(to),
+                    EdgeLabel::Element(elem.clone()),
+                );
+            }
+        }
+        graph
+    }
+}
+
+impl Nfa {
+    /// Converts this NFA to a directed petgraph graph, where node `i` is state `i`, with one edge per target of each
+    /// transition and ε-move. See the [module-level documentation](crate::graph) for more info.
+    pub fn to_petgraph(&self) -> Graph<StateLabel, EdgeLabel> {
+        let mut graph = Graph::with_capacity(self.states.len(), 0);
+        for state in &self.states {
+            graph.add_node(StateLabel {
+                name: state.name.clone(),
+                initial: state.initial,
+                accepting: state.accepting,
+            });
+        }
+        for (from, state) in self.states.iter().enumerate() {
+            let epsilon = state
+                .epsilon_transitions
+                .iter()
+                .map(|&to| (to, EdgeLabel::Epsilon));
+            let transitions =
+                state
+                    .transitions
+                    .iter()
+                    .zip(self.alphabet.iter())
+                    .flat_map(|(targets, elem)| {
+                        targets
+                            .iter()
+                            .map(|&to| (to, EdgeLabel::Element(elem.clone())))
+                    });
+            for (to, label) in epsilon.chain(transitions) {
+                graph.add_edge(NodeIndex::new(from), NodeIndex::new(to), label);
+            }
+        }
+        graph
+    }
+}
@@ -108,18 +108,50 @@
 //! * [Product construction](nfa::Nfa::product_construction) for NFAs
 //! * [Enumerating all words](nfa::Nfa::words) accepted by a NFA
 //! * [Removing epsilon moves](nfa::Nfa::remove_epsilon_moves) from a NFA
-//! * [Parsing regular expressions](parser::regex)
-//! * [Converting regular expressions to NFAs](regex::Regex::to_nfa)
+//! * [Parsing regular expressions](parser::regex), optionally [extended](parser::extended_regex) with intersection
+//!   and complement
+//! * [Converting regular expressions to NFAs](regex::Regex::to_nfa), or [directly to DFAs](regex::Regex::to_dfa)
+//!   using [derivatives](regex::Regex::derivative)
+//! * Converting [NFAs](nfa::Nfa::to_regex) and [DFAs](dfa::Dfa::to_regex) back to regular expressions
+//! * [Converting context-free grammars to pushdown automata](grammar::Grammar::to_pda)
+//! * Exporting DFAs and NFAs as [Mermaid state diagrams](dfa::Dfa::to_mermaid),
+//!   [Graphviz DOT graphs](dfa::Dfa::to_dot) and [TikZ pictures](dfa::Dfa::to_tikz) for LaTeX
+//! * Converting DFAs and NFAs to [petgraph](https://docs.rs/petgraph) graphs, with the `petgraph` feature
+//! * Serializing DFAs, NFAs and grammars with [serde](https://serde.rs), for example to JSON, with the `serde`
+//!   feature
+//! * Constructing DFAs and NFAs [from their parts](dfa::Dfa::from_parts) and checking if they accept strings without
+//!   the standard library (only `alloc`), by disabling the default `std` feature. Everything else, including the
+//!   parser, requires `std`
 //!
 //! See the documentation for [DFAs](dfa), [NFAs](nfa) and [Regular Expressions](regex) for more detailed
 //! information about each data type and their operations, together with some code examples
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod dfa;
 pub mod nfa;
+#[cfg(feature = "std")]
 pub mod parser;
+#[cfg(feature = "std")]
 pub mod regex;
+#[cfg(feature = "std")]
 pub mod grammar;
+#[cfg(feature = "std")]
+pub mod pda;
+#[cfg(feature = "std")]
+pub mod diagram;
+#[cfg(feature = "petgraph")]
+pub mod graph;
+#[cfg(feature = "std")]
+mod dot;
+#[cfg(feature = "std")]
+mod mermaid;
+#[cfg(feature = "std")]
 mod table;
-#[cfg(test)]
+#[cfg(feature = "std")]
+mod tikz;
+#[cfg(all(test, feature = "std"))]
 mod tests;
 mod util;
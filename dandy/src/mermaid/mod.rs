@@ -0,0 +1,92 @@
+use crate::diagram::{group_edges, DiagramState};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Generates a Mermaid `stateDiagram-v2` from the given states and transitions (given as `(from, to, label)`).
+/// Parallel transitions are merged into one edge by [group_edges].
+pub fn state_diagram(states: &[DiagramState], transitions: &[(usize, usize, &str)]) -> String {
+    let ids = state_ids(states);
+
+    let mut acc = String::from("stateDiagram-v2\n");
+    for (state, id) in states.iter().zip(&ids) {
+        if state.name == id {
+            let _ = writeln!(acc, "    {id}");
+        } else {
+            let _ = writeln!(acc, "    state \"{}\" as {id}", escape(state.name));
+        }
+    }
+    for (state, id) in states.iter().zip(&ids) {
+        if state.initial {
+            let _ = writeln!(acc, "    [*] --> {id}");
+        }
+    }
+    for edge in group_edges(transitions.iter().copied()) {
+        let labels = edge
+            .labels
+            .iter()
+            .map(|label| escape(label))
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            acc,
+            "    {} --> {} : {}",
+            ids[edge.from],
+            ids[edge.to],
+            labels.join(", ")
+        );
+    }
+
+    let accepting = states
+        .iter()
+        .zip(&ids)
+        .filter(|(state, _)| state.accepting)
+        .map(|(_, id)| id.as_str())
+        .collect::<Vec<_>>();
+    if !accepting.is_empty() {
+        acc.push_str("    classDef accepting stroke-width:4px\n");
+        let _ = writeln!(acc, "    class {} accepting", accepting.join(","));
+    }
+    acc
+}
+
+/// State names which are simple identifiers are used as they are, while other states get a generated identifier
+/// (which doesn't clash with any other state) and the name as description
+fn state_ids(states: &[DiagramState]) -> Vec<String> {
+    let is_simple = |name: &str| {
+        name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+    };
+    let taken = states
+        .iter()
+        .map(|state| state.name)
+        .filter(|name| is_simple(name))
+        .collect::<HashSet<_>>();
+    states
+        .iter()
+        .enumerate()
+        .map(|(idx, state)| {
+            if is_simple(state.name) {
+                state.name.to_string()
+            } else {
+                let mut id = format!("state{idx}");
+                while taken.contains(id.as_str()) {
+                    id.push('_');
+                }
+                id
+            }
+        })
+        .collect()
+}
+
+/// Replaces characters that have a special meaning in Mermaid with entity codes
+fn escape(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut acc, c| {
+            match c {
+                '"' | '#' | ';' | ':' | '<' | '>' | '{' | '}' | '[' | ']' | '|' => {
+                    let _ = write!(acc, "#{};", c as u32);
+                }
+                c => acc.push(c),
+            }
+            acc
+        })
+}
@@ -1,36 +1,42 @@
 use crate::nfa::{Nfa, NfaState};
-use std::collections::{HashMap, HashSet};
-use std::iter;
-use std::rc::Rc;
+use crate::util::Map;
+use alloc::collections::BTreeSet;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::{iter, mem};
 
+/// Evaluates a string step-by-step on a [Nfa], keeping track of the set of states the NFA currently is in. The set
+/// always includes its ε-closure. If an element not in the alphabet is seen, the set of current states is cleared and
+/// the evaluator will never accept again.
 #[derive(Clone, Debug)]
 pub struct NfaEvaluator<'a> {
     nfa: &'a Nfa,
-    rev_map: Rc<HashMap<&'a str, usize>>,
-    current_states: HashSet<usize>,
-    unknown_elem_seen: bool,
+    rev_map: Rc<Map<&'a str, usize>>,
+    current_states: BTreeSet<usize>,
 }
 
 impl<'a> NfaEvaluator<'a> {
+    /// Checks if any of the current states is accepting
     pub fn is_accepting(&self) -> bool {
-        if self.unknown_elem_seen {
-            false
-        } else {
-            self.current_states().iter().any(|s| s.accepting)
-        }
+        self.current_states
+            .iter()
+            .any(|&s| self.nfa.states[s].accepting)
+    }
+
+    /// Gets the indices of the states the NFA currently is in. The set is empty if there are no states that the NFA
+    /// could be in, for example after seeing an element that isn't in the alphabet.
+    pub fn current_states(&self) -> &BTreeSet<usize> {
+        &self.current_states
     }
 
-    pub fn current_states(&self) -> Vec<&NfaState> {
+    /// Gets the states the NFA currently is in, ordered by their indices
+    pub fn current_nfa_states(&self) -> Vec<&NfaState> {
         self.current_states
             .iter()
             .map(|&s| &self.nfa.states[s])
             .collect()
     }
 
-    pub fn current_states_idx(&self) -> &HashSet<usize> {
-        &self.current_states
-    }
-
     pub fn step_all(&self) -> Vec<NfaEvaluator<'a>> {
         iter::repeat(self.clone())
             .zip(self.nfa.alphabet())
@@ -41,10 +47,12 @@ impl<'a> NfaEvaluator<'a> {
             .collect()
     }
 
+    /// Steps the evaluator on one element of the alphabet. If the element isn't part of the alphabet, the set of
+    /// current states is cleared and `None` is returned.
     pub fn step(&mut self, elem: &str) -> Option<()> {
         match self.rev_map.get(elem) {
             None => {
-                self.unknown_elem_seen = true;
+                self.current_states.clear();
                 None
             }
             Some(&idx) => {
@@ -61,29 +69,105 @@ impl<'a> NfaEvaluator<'a> {
     }
 
     pub fn step_multiple(&mut self, elems: &[&str]) -> Option<()> {
-        match elems.iter().try_for_each(|e| self.step(e)) {
-            None => {
-                self.unknown_elem_seen = true;
-                None
+        elems.iter().try_for_each(|e| self.step(e))
+    }
+
+    fn include_closure(&mut self) {
+        let mut to_visit = self.current_states.iter().copied().collect::<Vec<_>>();
+        while let Some(state) = to_visit.pop() {
+            for &epsilon_state in &self.nfa.states[state].epsilon_transitions {
+                if self.current_states.insert(epsilon_state) {
+                    to_visit.push(epsilon_state);
+                }
             }
-            Some(_) => Some(())
         }
     }
+}
 
-    fn include_closure(&mut self) {
-        let mut updated = true;
-        let mut to_push = HashSet::new();
-        while updated {
-            updated = false;
-            for state in self.current_states.iter() {
-                for epsilon_state in self.nfa.states[*state].epsilon_transitions.iter() {
-                    if !self.current_states.contains(epsilon_state) {
-                        updated = true;
-                        to_push.insert(epsilon_state);
-                    }
+/// Reusable buffers for checking acceptance of many strings with [Nfa::accepts_with], so that no memory is allocated
+/// per string once the buffers have grown to the size of the NFA. The same scratch may be used with different NFAs,
+/// and it's cheap to create (it doesn't allocate until first used).
+///
+/// ```
+/// use dandy::nfa::{Nfa, NfaScratch};
+/// use dandy::parser;
+///
+/// let nfa: Nfa = parser::regex("(a|b)*abb").unwrap().to_nfa();
+/// let mut scratch = NfaScratch::new();
+/// let words: [&[&str]; 4] = [&["a", "b", "b"], &["b", "a", "b", "b"], &["a", "b"], &["b", "b", "a"]];
+/// for word in words {
+///     assert_eq!(nfa.accepts_with(word, &mut scratch), nfa.accepts(word));
+/// }
+/// assert!(!nfa.accepts_with(["a", "b", "c"], &mut scratch));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NfaScratch {
+    current: Vec<usize>,
+    next: Vec<usize>,
+    seen: Vec<bool>,
+}
+
+impl NfaScratch {
+    /// Creates empty buffers
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Nfa {
+    /// Checks if this automaton accepts the string given as an iterator of elements of the alphabet, like
+    /// [Nfa::accepts_iter], but keeping track of the current states in the buffers of `scratch` instead of allocating
+    /// new ones. This is useful when checking many strings in a row. Elements are looked up by a linear search in the
+    /// alphabet, which is faster than hashing for small alphabets.
+    pub fn accepts_with<S: AsRef<str>>(
+        &self,
+        string: impl IntoIterator<Item = S>,
+        scratch: &mut NfaScratch,
+    ) -> bool {
+        let NfaScratch {
+            current,
+            next,
+            seen,
+        } = scratch;
+        current.clear();
+        seen.clear();
+        seen.resize(self.states.len(), false);
+        self.insert_closure(self.initial_state, current, seen);
+
+        for elem in string {
+            let Some(elem_idx) = self.alphabet.iter().position(|e| **e == *elem.as_ref()) else {
+                return false;
+            };
+            for &state in current.iter() {
+                seen[state] = false;
+            }
+            next.clear();
+            for &state in current.iter() {
+                for &target in &self.states[state].transitions[elem_idx] {
+                    self.insert_closure(target, next, seen);
+                }
+            }
+            mem::swap(current, next);
+        }
+        current.iter().any(|&state| self.states[state].accepting)
+    }
+
+    /// Adds the state and its ε-closure to `set`, skipping states already marked as seen
+    fn insert_closure(&self, start: usize, set: &mut Vec<usize>, seen: &mut [bool]) {
+        if seen[start] {
+            return;
+        }
+        seen[start] = true;
+        let mut idx = set.len();
+        set.push(start);
+        while idx < set.len() {
+            for &target in &self.states[set[idx]].epsilon_transitions {
+                if !seen[target] {
+                    seen[target] = true;
+                    set.push(target);
                 }
             }
-            self.current_states.extend(to_push.drain());
+            idx += 1;
         }
     }
 }
@@ -99,10 +183,8 @@ impl<'a> From<&'a Nfa> for NfaEvaluator<'a> {
         let mut evaluator = Self {
             nfa: value,
             rev_map: Rc::new(map),
-            current_states: HashSet::new(),
-            unknown_elem_seen: false,
+            current_states: BTreeSet::from([value.initial_state]),
         };
-        evaluator.current_states.insert(value.initial_state);
         evaluator.include_closure();
         evaluator
     }
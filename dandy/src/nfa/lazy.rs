@@ -0,0 +1,135 @@
+use crate::nfa::Nfa;
+use fixedbitset::FixedBitSet;
+use std::collections::HashMap;
+
+/// A DFA which is determinized from a [Nfa] on demand, as given by [Nfa::lazy_dfa]. Each state of the DFA is a set of
+/// states of the NFA (always including its ε-closure), and is only created once a transition to it is taken. Both the
+/// states and the transitions are memoized, so taking a transition again is just a lookup.
+///
+/// The states are numbered in the order they are discovered, so the initial state is always `0`. Unlike
+/// [Nfa::to_dfa], this never builds more of the DFA than what is actually visited, which for large NFAs may be much
+/// less than the whole (possibly exponentially large) DFA.
+#[derive(Clone, Debug)]
+pub struct LazyDfa<'a> {
+    nfa: &'a Nfa,
+    /// The ε-closure of every state of the NFA
+    closures: Vec<FixedBitSet>,
+    /// The index of each element of the alphabet
+    symbols: HashMap<&'a str, usize>,
+    /// The set of NFA states of each DFA state discovered so far
+    states: Vec<FixedBitSet>,
+    /// The index of the DFA state of each set of NFA states discovered so far
+    ids: HashMap<FixedBitSet, usize>,
+    /// The transitions taken so far, where `transitions[s][e]` is the target of state `s` upon element `e`
+    transitions: Vec<Vec<Option<usize>>>,
+}
+
+impl Nfa {
+    /// Creates a [LazyDfa] for this NFA, which determinizes it one transition at a time as the transitions are taken
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// // Strings whose third to last element is 'a'
+    /// let nfa: Nfa = parser::nfa("
+    ///         a       b
+    /// -> s₀   {s₀ s₁} {s₀}
+    ///    s₁   {s₂}    {s₂}
+    ///    s₂   {s₃}    {s₃}
+    ///  * s₃   {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// let mut dfa = nfa.lazy_dfa();
+    /// assert!(dfa.accepts(&["b", "a", "b", "b"]));
+    /// assert!(!dfa.accepts(&["a", "b", "b", "b"]));
+    /// // Only the states visited so far have been created
+    /// assert!(dfa.num_states() < nfa.to_dfa().states().len());
+    ///
+    /// let s = dfa.step(dfa.initial_state_index(), "a").unwrap();
+    /// assert_eq!(dfa.nfa_states(s), vec![0, 1]);
+    /// assert_eq!(dfa.step(s, "c"), None);
+    /// ```
+    pub fn lazy_dfa(&self) -> LazyDfa<'_> {
+        let closures = self.epsilon_closures();
+        let initial = closures[self.initial_state].clone();
+        LazyDfa {
+            nfa: self,
+            symbols: self
+                .alphabet
+                .iter()
+                .enumerate()
+                .map(|(idx, elem)| (elem as &str, idx))
+                .collect(),
+            states: vec![initial.clone()],
+            ids: HashMap::from([(initial, 0)]),
+            transitions: vec![vec![None; self.alphabet.len()]],
+            closures,
+        }
+    }
+}
+
+impl LazyDfa<'_> {
+    /// Gets the index of the initial state, which is always `0`
+    pub fn initial_state_index(&self) -> usize {
+        0
+    }
+
+    /// Gets the index of the state entered from the state `state` upon seeing `elem`, creating the state if it hasn't
+    /// been discovered yet. Returns `None` if `elem` isn't in the alphabet. Panics if `state` hasn't been discovered.
+    pub fn step(&mut self, state: usize, elem: &str) -> Option<usize> {
+        let &elem = self.symbols.get(elem)?;
+        if let Some(target) = self.transitions[state][elem] {
+            return Some(target);
+        }
+
+        let mut set = FixedBitSet::with_capacity(self.nfa.states.len());
+        for nfa_state in self.states[state].ones() {
+            for &target in &self.nfa.states[nfa_state].transitions[elem] {
+                set.union_with(&self.closures[target]);
+            }
+        }
+        let target = match self.ids.get(&set) {
+            Some(&target) => target,
+            None => {
+                let target = self.states.len();
+                self.ids.insert(set.clone(), target);
+                self.states.push(set);
+                self.transitions.push(vec![None; self.nfa.alphabet.len()]);
+                target
+            }
+        };
+        self.transitions[state][elem] = Some(target);
+        Some(target)
+    }
+
+    /// Checks if the state with the given index is accepting, that is, if it includes an accepting state of the NFA.
+    /// Panics if the state hasn't been discovered.
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.states[state]
+            .ones()
+            .any(|s| self.nfa.states[s].accepting)
+    }
+
+    /// Checks if the DFA accepts the given string, creating the states on the way if needed
+    pub fn accepts(&mut self, elems: &[&str]) -> bool {
+        let mut state = self.initial_state_index();
+        for elem in elems {
+            match self.step(state, elem) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+        self.is_accepting(state)
+    }
+
+    /// Gets the number of states discovered so far
+    pub fn num_states(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Gets the indices of the NFA states making up the state with the given index, in increasing order. Panics if the
+    /// state hasn't been discovered.
+    pub fn nfa_states(&self, state: usize) -> Vec<usize> {
+        self.states[state].ones().collect()
+    }
+}
@@ -2,7 +2,8 @@
 //! The NFA module includes the [Nfa] struct which represents a
 //! [Nondeterministic finite automaton](https://en.wikipedia.org/wiki/Nondeterministic_finite_automaton) with or without
 //! ε-moves. Currently, the only ways to create such an instance is by converting a [Dfa](Dfa::to_nfa) or
-//! [Regex](crate::regex::Regex::to_nfa) to a NFA or by parsing from a string.
+//! [Regex](crate::regex::Regex::to_nfa) to a NFA, by parsing from a string, by constructing a
+//! [Levenshtein automaton](Nfa::levenshtein) or by [generating one randomly](Nfa::random).
 //!
 //! ## Example
 //! You may parse a state transition table in text form to a NFA. The parsing is done in two steps, the first one
@@ -126,6 +127,12 @@
 //! evaluator.step("b");
 //! // We should be accepting this input
 //! assert!(evaluator.is_accepting());
+//! // The NFA is in states s1 and s4 (by index) at the same time
+//! assert_eq!(evaluator.current_states().iter().copied().collect::<Vec<_>>(), vec![0, 3]);
+//! // Stepping on an element not in the alphabet leaves the NFA in no states at all
+//! evaluator.step("c");
+//! assert!(evaluator.current_states().is_empty());
+//! assert!(!evaluator.is_accepting());
 //! ```
 //!
 //! ### Conversions
@@ -143,7 +150,9 @@
 //!
 //! Unlike for DFAs, there doesn't exist one unique minimized NFA which accepts the language of another NFA.
 //! Furthermore, no polynomial time algorithms are known (and none exists under the assumption `P != PSPACE`). This
-//! version of Dandy doesn't include a NFA minimization algorithm, but one may be added in the future.
+//! version of Dandy doesn't include a NFA minimization algorithm, but one may be added in the future. There is,
+//! however, a canonical NFA for each language which is often much smaller than the minimal DFA: the canonical residual
+//! finite-state automaton, given by [Nfa::to_residual_automaton].
 //!
 //! The [Nfa] struct represents both NFAs with and without ε-moves, and one could check if the NFA has ε-moves by
 //! the [Nfa::has_epsilon_moves] method (which simply loops through the states and checks if any of them has any
@@ -209,6 +218,9 @@
 //! the product construction be used to find the symmetrical difference. Therefore, those ways of checking equivalence
 //! of two NFAs are infeasible. Dandy provides [Nfa::equivalent_to] which tracks all states reachable at the same time
 //! of the two provided NFAs, and if one is accepting while the other one is not, it rejects the NFAs as not equivalent.
+//! To only check if the language of one NFA is included in the language of another, [Nfa::is_subset_of] uses the
+//! antichain algorithm, which avoids determinizing the NFAs and often is much faster. Checking inclusion in both
+//! directions gives [Nfa::equivalent_to_antichain], an alternative to [Nfa::equivalent_to].
 //!
 //! ### Enumerating words
 //! An algorithm by [Margareta Ackerman and Jeffrey Shallit](https://maya-ackerman.com/wp-content/uploads/2018/09/Enumeration_AckermanShallit2.pdf)
@@ -232,33 +244,74 @@
 //!
 //! ### Additional operations
 //! In addition to the above-mentioned operations, you can:
-//! * [Get the alphabet](Nfa::alphabet) of the NFA,
+//! * [Get the alphabet](Nfa::alphabet) of the NFA, or [rename its elements](Nfa::map_alphabet),
 //! * [Get the states](Nfa::states) and [initial state](Nfa::initial_state) of the NFA,
 //! * [Convert it to a table](Nfa::to_table), possibly [in ascii-only](Nfa::ascii_table), both of which
 //!   can be parsed by Dandy into this very same NFA again,
+//! * [Convert it to a Mermaid state diagram](Nfa::to_mermaid), for embedding in Markdown, to a
+//!   [Graphviz DOT graph](Nfa::to_dot), or to a [TikZ picture](Nfa::to_tikz) for LaTeX,
+//! * [Convert it to a regular expression](Nfa::to_regex),
+//! * [Determinize it lazily](Nfa::lazy_dfa), creating only the DFA states which are actually visited,
+//! * Construct the image of its language under a [homomorphism](Nfa::apply_homomorphism),
 //! * Find all [reachable](Nfa::reachable_states) and [non-reachable](Nfa::unreachable_states) states,
+//! * Remove its [dead states](Nfa::remove_dead_states), from which no accepting state can be reached,
+//! * Check if it [is unambiguous](Nfa::is_unambiguous), i.e. if every accepted word has only one accepting run,
 //! * [Clone](Nfa::clone) it, which isn't super expensive since the alphabet and state names doesn't need new
 //!   allocations to be cloned (no strings at all are actually copied, just some `vec`s with `bool`s and `usize`s).
 //!   Note that since NFAs can have multiple transitions upon seeing each symbol, cloning a NFA inherently clones more
 //!   `vec`s and is more expensive than cloning a DFA.
 
-use crate::dfa::{Dfa, DfaState};
+use crate::dfa::ConstructionError;
+#[cfg(feature = "std")]
+use crate::dfa::{AlphabetCollision, AlphabetMismatch, Dfa, DfaState, RetainError};
+#[cfg(feature = "std")]
+use crate::diagram::DiagramState;
+#[cfg(feature = "std")]
+use crate::dot;
+#[cfg(feature = "std")]
+use crate::mermaid;
+#[cfg(feature = "std")]
 use crate::nfa::words::{WordComponentIndices, WordComponents, Words};
+#[cfg(feature = "std")]
+use crate::regex::{Regex, RegexChar, RegexTree};
+#[cfg(feature = "std")]
 use crate::table::Table;
+#[cfg(feature = "std")]
+use crate::tikz;
+#[cfg(feature = "std")]
+use crate::util;
+#[cfg(feature = "std")]
 use crate::util::alphabet_equal;
-pub use eval::NfaEvaluator;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+pub use eval::{NfaEvaluator, NfaScratch};
+#[cfg(feature = "std")]
+use fixedbitset::FixedBitSet;
+#[cfg(feature = "std")]
+pub use lazy::LazyDfa;
+#[cfg(feature = "std")]
 pub use parse::NfaParseError;
-use std::collections::{HashMap, HashSet};
-use std::rc::Rc;
-use std::{iter, mem};
+#[cfg(feature = "std")]
+use rand::Rng;
+#[cfg(feature = "std")]
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+#[cfg(feature = "std")]
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::{fmt, iter, mem};
 use unicode_segmentation::UnicodeSegmentation;
 
 pub mod eval;
+#[cfg(feature = "std")]
+pub mod lazy;
+#[cfg(feature = "std")]
 pub mod parse;
+#[cfg(feature = "std")]
 pub mod words;
 
 /// A non-deterministic finite automata, denoted by its alphabet, states and the initial state
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Nfa {
     pub(crate) alphabet: Rc<[Rc<str>]>,
     pub(crate) states: Vec<NfaState>,
@@ -268,6 +321,7 @@ pub struct Nfa {
 /// A state in a NFA automata, which consists of its name, if it is the initial state or not, if it is accepting
 /// or not, any amount of epsilon transitions and any amount of transitions for each element in alphabet
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct NfaState {
     pub(crate) name: Rc<str>,
     pub(crate) initial: bool,
@@ -277,6 +331,39 @@ pub struct NfaState {
 }
 
 impl NfaState {
+    /// Creates a state with the given name, whether it is the initial state and accepting, its epsilon transitions as
+    /// state indices, and its transitions as sets of state indices for each element of the alphabet. The states can be
+    /// made into a NFA with [Nfa::from_parts].
+    ///
+    /// ```
+    /// use dandy::nfa::{Nfa, NfaState};
+    ///
+    /// // Strings of a's and b's ending with ab
+    /// let states = vec![
+    ///     NfaState::new("s₀", true, false, vec![], vec![vec![0, 1], vec![0]]),
+    ///     NfaState::new("s₁", false, false, vec![], vec![vec![], vec![2]]),
+    ///     NfaState::new("s₂", false, true, vec![], vec![vec![], vec![]]),
+    /// ];
+    /// let nfa = Nfa::from_parts(vec!["a".into(), "b".into()], states, 0).unwrap();
+    /// assert!(nfa.accepts_graphemes("bab"));
+    /// assert!(!nfa.accepts_graphemes("aba"));
+    /// ```
+    pub fn new(
+        name: impl Into<Rc<str>>,
+        initial: bool,
+        accepting: bool,
+        epsilon_transitions: Vec<usize>,
+        transitions: Vec<Vec<usize>>,
+    ) -> Self {
+        NfaState {
+            name: name.into(),
+            initial,
+            accepting,
+            epsilon_transitions,
+            transitions,
+        }
+    }
+
     /// Gets the name of this state
     pub fn name(&self) -> &str {
         &self.name
@@ -304,7 +391,260 @@ impl NfaState {
     }
 }
 
+#[cfg(feature = "std")]
 impl Nfa {
+    /// Constructs a [Levenshtein automaton](https://en.wikipedia.org/wiki/Levenshtein_automaton), which accepts exactly
+    /// the strings within edit distance `max_edits` of `word`, where an edit is the substitution, insertion or deletion
+    /// of one element. The alphabet of the NFA is the given alphabet, followed by any elements of the word not in it.
+    ///
+    /// The states are named `s{position}_{edits}`, with the NFA being in state `sP_E` after having read a string
+    /// which can be turned into the first `P` elements of the word with `E` edits. Deletions are ε-moves, so to get
+    /// fuzzy matches against a dictionary, intersect this NFA with one accepting the dictionary.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let alphabet = ('a'..='z').map(|c| c.to_string()).collect::<Vec<_>>();
+    /// let alphabet = alphabet.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    /// let nfa = Nfa::levenshtein(&["k", "i", "t", "t", "e", "n"], 2, &alphabet);
+    /// assert!(nfa.accepts_graphemes("kitten"));
+    /// assert!(nfa.accepts_graphemes("sitten")); // One substitution
+    /// assert!(nfa.accepts_graphemes("kiten")); // One deletion
+    /// assert!(nfa.accepts_graphemes("skitten")); // One insertion
+    /// assert!(nfa.accepts_graphemes("sittin"));
+    /// assert!(!nfa.accepts_graphemes("sitting")); // Three edits
+    /// ```
+    pub fn levenshtein(word: &[&str], max_edits: usize, alphabet: &[&str]) -> Nfa {
+        let mut elements = Vec::<&str>::new();
+        for &elem in alphabet.iter().chain(word) {
+            if !elements.contains(&elem) {
+                elements.push(elem);
+            }
+        }
+
+        let idx = |position: usize, edits: usize| position * (max_edits + 1) + edits;
+        let states = (0..=word.len())
+            .flat_map(|position| (0..=max_edits).map(move |edits| (position, edits)))
+            .map(|(position, edits)| {
+                let can_edit = edits < max_edits;
+                let transitions = elements
+                    .iter()
+                    .map(|&elem| {
+                        let mut targets = vec![];
+                        match word.get(position) {
+                            Some(&expected) if expected == elem => {
+                                targets.push(idx(position + 1, edits))
+                            }
+                            // Substitution
+                            Some(_) if can_edit => targets.push(idx(position + 1, edits + 1)),
+                            _ => {}
+                        }
+                        // Insertion
+                        if can_edit {
+                            targets.push(idx(position, edits + 1));
+                        }
+                        targets
+                    })
+                    .collect();
+                // Deletion
+                let epsilon_transitions = if can_edit && position < word.len() {
+                    vec![idx(position + 1, edits + 1)]
+                } else {
+                    vec![]
+                };
+                NfaState {
+                    name: Rc::from(format!("s{position}_{edits}")),
+                    initial: position == 0 && edits == 0,
+                    accepting: position == word.len(),
+                    epsilon_transitions,
+                    transitions,
+                }
+            })
+            .collect();
+
+        Nfa {
+            alphabet: elements.into_iter().map(Rc::from).collect(),
+            states,
+            initial_state: 0,
+        }
+    }
+
+    /// Constructs a random NFA with `num_states` states named `s0`, `s1` and so on, where `s0` is the initial state and
+    /// each state is accepting with probability ½. Each possible transition (from any state upon seeing any element to
+    /// any state) is included with probability `density`, as is each possible ε-move if `epsilon` is set. Duplicates in
+    /// the alphabet are removed. With a seeded random number generator, the same NFA is constructed every time, which
+    /// makes this useful for reproducible test corpora.
+    ///
+    /// # Panics
+    /// Panics if `num_states` is zero, since a NFA needs an initial state, or if `density` isn't between 0 and 1.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let nfa = Nfa::random(&mut StdRng::seed_from_u64(7), 5, &["a", "b"], 0.3, false);
+    /// assert_eq!(nfa.states().len(), 5);
+    /// assert!(!nfa.has_epsilon_moves());
+    /// ```
+    pub fn random(
+        rng: &mut impl Rng,
+        num_states: usize,
+        alphabet: &[&str],
+        density: f64,
+        epsilon: bool,
+    ) -> Nfa {
+        assert!(num_states > 0, "a NFA needs at least one state");
+        let mut elements = Vec::<&str>::new();
+        for &elem in alphabet {
+            if !elements.contains(&elem) {
+                elements.push(elem);
+            }
+        }
+
+        fn targets(rng: &mut impl Rng, num_states: usize, density: f64) -> Vec<usize> {
+            (0..num_states).filter(|_| rng.gen_bool(density)).collect()
+        }
+        let states = (0..num_states)
+            .map(|idx| NfaState {
+                name: Rc::from(format!("s{idx}")),
+                initial: idx == 0,
+                accepting: rng.gen(),
+                epsilon_transitions: if epsilon {
+                    targets(rng, num_states, density)
+                } else {
+                    vec![]
+                },
+                transitions: elements
+                    .iter()
+                    .map(|_| targets(rng, num_states, density))
+                    .collect(),
+            })
+            .collect();
+        Nfa {
+            alphabet: elements.into_iter().map(Rc::from).collect(),
+            states,
+            initial_state: 0,
+        }
+    }
+
+    /// Renames every element of the alphabet using `f`, keeping all transitions as they are. This may be used to make
+    /// two NFAs over alphabets that are spelled differently compatible, for example for [Nfa::union]. If two distinct
+    /// elements are mapped to the same string, an error is returned and the NFA is left unchanged.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let mut nfa: Nfa = parser::nfa("
+    ///          0    1
+    /// -> s₀    {s₀} {s₀ s₁}
+    ///  * s₁    {}   {}
+    /// ").unwrap().try_into().unwrap();
+    /// nfa.map_alphabet(|elem| format!("<{elem}>")).unwrap();
+    /// assert!(nfa.accepts(&["<0>", "<1>"]));
+    /// assert!(nfa.map_alphabet(|_| String::new()).is_err());
+    /// ```
+    pub fn map_alphabet(&mut self, f: impl FnMut(&str) -> String) -> Result<(), AlphabetCollision> {
+        self.alphabet = util::map_alphabet(&self.alphabet, f)?;
+        Ok(())
+    }
+
+    /// Constructs a NFA for the image of the language of this NFA under the homomorphism `h`, that is, a NFA accepting
+    /// `h(a₁)h(a₂)...h(aₙ)` for every string `a₁a₂...aₙ` accepted by this NFA. Every transition upon seeing `a` is
+    /// replaced by a chain of new states spelling out `h(a)`, or by an epsilon transition if `h(a)` is empty. The
+    /// alphabet of the new NFA is all elements appearing in the images of the elements of the alphabet, in order of
+    /// their first appearance.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// let nfa = parser::regex("(ab)*c").unwrap().to_nfa();
+    /// let image = nfa.apply_homomorphism(|elem| match elem {
+    ///     "a" => vec!["x", "y"],
+    ///     "b" => vec![],
+    ///     _ => vec!["z"],
+    /// });
+    /// assert!(image.accepts_graphemes("xyxyz"));
+    /// assert!(image.accepts_graphemes("z"));
+    /// assert!(!image.accepts_graphemes("xz"));
+    /// ```
+    pub fn apply_homomorphism(&self, h: impl Fn(&str) -> Vec<&str>) -> Nfa {
+        let images = self.alphabet.iter().map(|elem| h(elem)).collect::<Vec<_>>();
+        let mut alphabet: Vec<Rc<str>> = vec![];
+        for &elem in images.iter().flatten() {
+            if alphabet.iter().all(|e| e.as_ref() != elem) {
+                alphabet.push(Rc::from(elem));
+            }
+        }
+        let elem_idx = |elem: &str| alphabet.iter().position(|e| e.as_ref() == elem).unwrap();
+
+        let mut names = self
+            .states
+            .iter()
+            .map(|s| s.name.clone())
+            .collect::<HashSet<_>>();
+        let mut states = self
+            .states
+            .iter()
+            .map(|state| NfaState {
+                name: state.name.clone(),
+                initial: state.initial,
+                accepting: state.accepting,
+                epsilon_transitions: state.epsilon_transitions.clone(),
+                transitions: vec![vec![]; alphabet.len()],
+            })
+            .collect::<Vec<_>>();
+
+        for (from, state) in self.states.iter().enumerate() {
+            for (image, targets) in images.iter().zip(&state.transitions) {
+                if targets.is_empty() {
+                    continue;
+                }
+                let Some((&last, init)) = image.split_last() else {
+                    states[from].epsilon_transitions.extend(targets);
+                    continue;
+                };
+                // The chain is shared by all targets, and only the last element branches out to them
+                let mut current = from;
+                for (k, &elem) in init.iter().enumerate() {
+                    let mut name = format!("{}-{}", state.name, image[..=k].concat());
+                    while names.contains(name.as_str()) {
+                        name.push('\'');
+                    }
+                    let name: Rc<str> = Rc::from(name);
+                    names.insert(name.clone());
+                    let next = states.len();
+                    states.push(NfaState {
+                        name,
+                        initial: false,
+                        accepting: false,
+                        epsilon_transitions: vec![],
+                        transitions: vec![vec![]; alphabet.len()],
+                    });
+                    states[current].transitions[elem_idx(elem)].push(next);
+                    current = next;
+                }
+                states[current].transitions[elem_idx(last)].extend(targets);
+            }
+        }
+        // Several elements may have the same image, giving duplicate targets
+        for state in &mut states {
+            let all_targets =
+                iter::once(&mut state.epsilon_transitions).chain(&mut state.transitions);
+            for targets in all_targets {
+                targets.sort_unstable();
+                targets.dedup();
+            }
+        }
+
+        Nfa {
+            alphabet: alphabet.into(),
+            states,
+            initial_state: self.initial_state,
+        }
+    }
+
     /// Constructs the intersection of two NFAs, that is, a new NFA that accepts exactly those strings that are accepted
     /// by either the first, the second NFA, or both. This returns `None` if and only if the alphabets of the two NFAs
     /// are unequal (not considering ordering). This is done by adding a new initial state that has epsilon transitions
@@ -415,6 +755,22 @@ impl Nfa {
         Ok(self)
     }
 
+    /// Concatenates `other` to the end of this NFA, so that it accepts the strings `uv` where `u` is accepted by this
+    /// NFA and `v` by `other`. This is done by adding epsilon transitions from the accepting states of this NFA to the
+    /// initial state of `other`, which then are no longer accepting. The alphabets must be the same, in the same order,
+    /// and the state names must be unique among both NFAs.
+    pub(crate) fn concatenate(&mut self, mut other: Nfa) {
+        let offset = self.states.len();
+        other.remap_transitions(|idx| Some(idx + offset));
+        let other_initial = other.initial_state + offset;
+        other.states[other.initial_state].initial = false;
+        for state in self.states.iter_mut().filter(|state| state.accepting) {
+            state.accepting = false;
+            state.epsilon_transitions.push(other_initial);
+        }
+        self.states.extend(other.states);
+    }
+
     /// Constructs the intersection of two NFAs, that is, a new NFA that accepts exactly those strings that are accepted
     /// by both the first and second NFAs. This returns `None` if and only if the alphabets of the two NFAs are unequal
     /// (not considering ordering). This is done by the product construction.
@@ -450,7 +806,7 @@ impl Nfa {
     pub fn intersection(&self, other: &Self) -> Option<Self> {
         self.product_construction(other, |s1, s2| {
             s1.zip(s2)
-                .map_or(false, |(s1, s2)| s1.accepting && s2.accepting)
+                .is_some_and(|(s1, s2)| s1.accepting && s2.accepting)
         })
     }
 
@@ -513,9 +869,7 @@ impl Nfa {
                     + s2.map_or(0, |s2| other.states[s2].epsilon_transitions.len()),
             );
 
-            for elem in 0..self.alphabet.len() {
-                let other_elem = alphabet_translation[elem];
-
+            for (elem, &other_elem) in alphabet_translation.iter().enumerate() {
                 let mut elem_transitions = Vec::with_capacity(
                     s1.map_or(1, |s1| self.states[s1].transitions[elem].len())
                         * s2.map_or(1, |s2| other.states[s2].transitions[other_elem].len()),
@@ -650,7 +1004,7 @@ impl Nfa {
                             transition_list
                                 .iter()
                                 .map(|states| {
-                                    *rev_state_idx_map.get(&states).expect(
+                                    *rev_state_idx_map.get(states).expect(
                                         "Each state pair with transition to it should have a idx",
                                     )
                                 })
@@ -759,22 +1113,18 @@ impl Nfa {
         }
 
         // Pre-calculate all epsilon closures
-        let closures = (0..self.states.len())
-            .filter_map(|idx| self.closure(idx))
-            .collect::<Vec<_>>();
+        let closures = self.epsilon_closures();
 
         // first, inline all epsilon closures
+        let mut set = FixedBitSet::with_capacity(self.states.len());
         self.states.iter_mut().for_each(|state| {
             state.transitions.iter_mut().for_each(|transition_set| {
                 // On transition from a to b, transition from a to eps closure of b
-                *transition_set = transition_set
-                    .iter()
-                    .fold(HashSet::new(), |mut set, transition| {
-                        set.extend(&closures[*transition]);
-                        set
-                    })
-                    .drain()
-                    .collect();
+                set.clear();
+                for &transition in transition_set.iter() {
+                    set.union_with(&closures[transition]);
+                }
+                *transition_set = set.ones().collect();
             });
             state.epsilon_transitions.clear();
         });
@@ -804,8 +1154,7 @@ impl Nfa {
         // Thirdly, figure out if we need a new initial state (which we would need if
         // our initial state has epsilon transitions to other than dead states)
         let init_closure = closures[self.initial_state]
-            .iter()
-            .copied()
+            .ones()
             .filter(|x| !dead_states.contains(x))
             .collect::<HashSet<_>>();
         if init_closure.len() > 1 {
@@ -946,6 +1295,51 @@ impl Nfa {
         }
     }
 
+    /// Removes the states for which `keep` (given the index and the state) returns `false`, and reindexes the remaining
+    /// states, keeping their order. All transitions and epsilon transitions to the removed states are removed as well.
+    /// This errors if the initial state would be removed, in which case the NFA is left unchanged.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::RetainError;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let mut nfa: Nfa = parser::nfa("
+    ///          ε    a       b
+    /// -> s₀    {}   {s₀ s₁} {s₀}
+    ///    s₁    {s₂} {}      {s₂}
+    ///  * s₂    {}   {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.retain_states(|idx, _| idx != 0), Err(RetainError::InitialStateRemoved(0)));
+    ///
+    /// nfa.retain_states(|_, state| state.name() != "s₁").unwrap();
+    /// assert_eq!(nfa.states().len(), 2);
+    /// assert_eq!(nfa.states()[0].transitions(), &[vec![0], vec![0]]);
+    /// assert!(!nfa.accepts_graphemes("ab"));
+    /// ```
+    pub fn retain_states(
+        &mut self,
+        keep: impl Fn(usize, &NfaState) -> bool,
+    ) -> Result<(), RetainError> {
+        let kept = self
+            .states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| keep(idx, state))
+            .collect::<Vec<_>>();
+        if !kept[self.initial_state] {
+            return Err(RetainError::InitialStateRemoved(self.initial_state));
+        }
+        for state in &mut self.states {
+            for targets in &mut state.transitions {
+                targets.retain(|&target| kept[target]);
+            }
+            state.epsilon_transitions.retain(|&target| kept[target]);
+        }
+        self.remove_states((0..kept.len()).filter(|&idx| !kept[idx]).collect());
+        Ok(())
+    }
+
     /// Removes the unreachable states of this NFA, that is, all states that cannot be reached by any input to
     /// the automata. See [Nfa::unreachable_states] to get the unreachable states
     pub fn remove_unreachable_states(&mut self) {
@@ -961,6 +1355,58 @@ impl Nfa {
             .collect()
     }
 
+    /// Removes the dead states of this NFA, that is, the states from which no accepting state can be reached, along
+    /// with all transitions to them. The initial state is never removed, but if it is dead all its transitions are.
+    /// This doesn't change the language of the NFA, and together with [Nfa::remove_unreachable_states] it trims the
+    /// NFA.
+    pub fn remove_dead_states(&mut self) {
+        let dead = self.dead_state_idx();
+        for state in self.states.iter_mut() {
+            state.epsilon_transitions.retain(|t| !dead.contains(t));
+            state
+                .transitions
+                .iter_mut()
+                .for_each(|transition| transition.retain(|t| !dead.contains(t)));
+        }
+        let to_remove = dead
+            .into_iter()
+            .filter(|&idx| idx != self.initial_state)
+            .collect();
+        self.remove_states(to_remove);
+    }
+
+    /// Finds the dead states, that is, all states from which no accepting state can be reached (by transitions and
+    /// ε-moves), and returns them as indices
+    pub fn dead_state_idx(&self) -> HashSet<usize> {
+        let mut predecessors = vec![vec![]; self.states.len()];
+        for (idx, state) in self.states.iter().enumerate() {
+            for &target in state
+                .epsilon_transitions
+                .iter()
+                .chain(state.transitions.iter().flatten())
+            {
+                predecessors[target].push(idx);
+            }
+        }
+        let mut live = self
+            .states
+            .iter()
+            .map(|state| state.accepting)
+            .collect::<Vec<_>>();
+        let mut queue = (0..self.states.len())
+            .filter(|&idx| live[idx])
+            .collect::<Vec<_>>();
+        while let Some(state) = queue.pop() {
+            for &pred in &predecessors[state] {
+                if !live[pred] {
+                    live[pred] = true;
+                    queue.push(pred);
+                }
+            }
+        }
+        (0..self.states.len()).filter(|&idx| !live[idx]).collect()
+    }
+
     /// Finds the unreachable states, that is, all states that cannot be reached by any input to the automata, and
     /// returns them as indices
     pub fn unreachable_state_idx(&self) -> HashSet<usize> {
@@ -973,20 +1419,60 @@ impl Nfa {
     /// Checks if this NFA has an accepting state that is reachable from the initial state, that is, if it has some
     /// input which it accepts
     pub fn has_reachable_accepting_state(&self) -> bool {
-        // Use _idx to not allocate Vec
-        self.reachable_state_idx()
-            .iter()
-            .any(|idx| self.states[*idx].accepting)
+        self.reachable_states_iter()
+            .any(|idx| self.states[idx].accepting)
     }
 
-    /// Finds the reachable states, that is, all states that can be reached by some input to the automata
+    /// Finds the reachable states, that is, all states that can be reached by some input to the automata, in the order
+    /// of [Nfa::reachable_states_iter]
     pub fn reachable_states(&self) -> Vec<&NfaState> {
-        self.reachable_state_idx()
-            .into_iter()
+        self.reachable_states_iter()
             .map(|idx| &self.states[idx])
             .collect()
     }
 
+    /// Iterates over the indices of the reachable states (following both transitions and ε-moves), in breadth-first
+    /// order from the initial state. The initial state comes first, and the successors of each state are visited with
+    /// ε-moves first and then in the order of the alphabet, so the order is deterministic. The search is lazy, so
+    /// stopping the iteration early skips the rest of it.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///          ε    a    b
+    ///    s₀    {}   {s₀} {}
+    /// -> s₁    {s₃} {s₂} {}
+    ///    s₂    {}   {}   {s₂}
+    ///  * s₃    {}   {}   {s₁ s₂}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.reachable_states_iter().collect::<Vec<_>>(), vec![1, 3, 2]);
+    /// ```
+    pub fn reachable_states_iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut seen = vec![false; self.states.len()];
+        seen[self.initial_state] = true;
+        let mut queue = VecDeque::from([self.initial_state]);
+        iter::from_fn(move || {
+            let state = queue.pop_front()?;
+            let NfaState {
+                epsilon_transitions,
+                transitions,
+                ..
+            } = &self.states[state];
+            for &next in epsilon_transitions
+                .iter()
+                .chain(transitions.iter().flatten())
+            {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+            Some(state)
+        })
+    }
+
     /// Finds the reachable states, that is, all states that can be reached by some input to the automata, and
     /// returns them as indices
     pub fn reachable_state_idx(&self) -> HashSet<usize> {
@@ -1002,7 +1488,7 @@ impl Nfa {
                         .iter()
                         .flatten()
                         .copied()
-                        .chain(self.closure(state).unwrap().into_iter())
+                        .chain(self.closure(state).unwrap())
                 })
                 .filter(|&state| reachables.insert(state))
                 .collect();
@@ -1019,7 +1505,7 @@ impl Nfa {
     ///
     /// *NOTE:* Current implementation only works for NFAs without epsilon moves.
     /// See [Nfa::remove_epsilon_moves]
-    pub fn words(&self) -> Words {
+    pub fn words(&self) -> Words<'_> {
         Words::new(self)
     }
 
@@ -1032,7 +1518,7 @@ impl Nfa {
     ///
     /// *NOTE:* Current implementation only works for NFAs without epsilon moves.
     /// See [Nfa::remove_epsilon_moves]
-    pub fn word_components(&self) -> WordComponents {
+    pub fn word_components(&self) -> WordComponents<'_> {
         WordComponents::new(self)
     }
 
@@ -1045,7 +1531,7 @@ impl Nfa {
     ///
     /// *NOTE:* Current implementation only works for NFAs without epsilon moves.
     /// See [Nfa::remove_epsilon_moves]
-    pub fn word_component_indices(&self) -> WordComponentIndices {
+    pub fn word_component_indices(&self) -> WordComponentIndices<'_> {
         WordComponentIndices::new(self)
     }
 
@@ -1061,30 +1547,38 @@ impl Nfa {
         let mut map = HashMap::new();
         // Set of sequential numbers which are accepting states
         let mut accepting = HashSet::new();
-        // Evaluators to explore
-        let mut to_explore = vec![self.evaluator()];
+        // The epsilon closure of every state, so that the closure of a set is the union of the closures of its states
+        let closures = self.epsilon_closures();
+        // Sets of states to explore, which always include their epsilon closure
+        let mut to_explore = vec![closures[self.initial_state].clone()];
         // Transition tables for new states, indexed by sets
         let mut transitions = HashMap::new();
+        let is_accepting = |set: &FixedBitSet| set.ones().any(|s| self.states[s].accepting);
 
         {
             // Pre-work, add init to tables
-            let key = Self::set_to_vec(to_explore[0].current_states_idx());
+            let key = to_explore[0].clone();
             let n = gen.next().unwrap(); // 0
-            map.insert(key, n);
-            if to_explore[0].is_accepting() {
+            if is_accepting(&key) {
                 accepting.insert(n);
             }
+            map.insert(key, n);
         }
 
         // While we have non-expanded states
-        while let Some(eval) = to_explore.pop() {
+        while let Some(current) = to_explore.pop() {
             // Keep track of transitions from this state
             let mut tr = Vec::with_capacity(self.alphabet.len());
-            for new_evaluator in eval.step_all() {
-                let is_accepting = new_evaluator.is_accepting();
-                let key = Self::set_to_vec(new_evaluator.current_states_idx());
+            for elem_idx in 0..self.alphabet.len() {
+                let mut key = FixedBitSet::with_capacity(self.states.len());
+                for state in current.ones() {
+                    for &target in &self.states[state].transitions[elem_idx] {
+                        key.union_with(&closures[target]);
+                    }
+                }
+                let is_accepting = is_accepting(&key);
                 if !map.contains_key(&key) {
-                    to_explore.push(new_evaluator);
+                    to_explore.push(key.clone());
                 }
                 let x = map.entry(key).or_insert_with(|| gen.next().unwrap());
                 tr.push(*x);
@@ -1093,7 +1587,7 @@ impl Nfa {
                 }
             }
 
-            transitions.insert(Self::set_to_vec(eval.current_states_idx()), tr);
+            transitions.insert(current, tr);
         }
 
         let sorted_keys = {
@@ -1102,8 +1596,7 @@ impl Nfa {
             vec
         };
 
-        // We sort the keys to have a nice table later on. This may be wasteful but
-        // Self::set_to_vec sorts and converts sets to vecs anyways so nevermind
+        // We sort the keys to have a nice table later on
         let states = sorted_keys
             .into_iter()
             .map(|(key, &n)| DfaState {
@@ -1121,45 +1614,223 @@ impl Nfa {
         }
     }
 
-    /// Checks if this automaton accepts the given string. This is equivalent to getting the
-    /// evaluator, stepping it multiple times and checking if it is accepting
-    pub fn accepts(&self, string: &[&str]) -> bool {
-        let mut eval = self.evaluator();
-        eval.step_multiple(string);
-        eval.is_accepting()
-    }
+    /// Converts this NFA to a regular expression matching the same language, using state elimination: the states are
+    /// removed one at a time (those with the fewest incoming and outgoing transitions first), and the transitions
+    /// through each removed state are replaced by transitions labeled with regular expressions. The expressions are
+    /// simplified along the way (for example `∅r = ∅`, `εr = r` and `∅|r = r`), but the result may still be much
+    /// larger than necessary, and in general it can be exponential in the number of states.
+    ///
+    /// Each element of the alphabet becomes one symbol of the expression, so elements which aren't a single grapheme
+    /// cluster won't be parsed back into the same symbol by [parser::regex](crate::parser::regex). Elements which
+    /// aren't used by any path from the initial state to an accepting state don't appear in the expression.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// // Accepts words ending in "ab"
+    /// let nfa: Nfa = parser::nfa("
+    ///          a       b
+    /// -> s₀    {s₀ s₁} {s₀}
+    ///    s₁    {}      {s₂}
+    ///  * s₂    {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// let regex = nfa.to_regex();
+    /// assert!(regex.equivalent_to(&parser::regex("(a|b)*ab").unwrap()));
+    /// ```
+    pub fn to_regex(&self) -> Regex {
+        // The generalized NFA has the states of this NFA, a new initial state and a new accepting state, and at most
+        // one transition between each pair of states, labeled by `edges[from][to]` (where ∅ means no transition)
+        let n = self.states.len();
+        let (start, end) = (n, n + 1);
+        let mut edges = vec![vec![RegexTree::EMPTY; n + 2]; n + 2];
+        let mut add_edge = |from: usize, to: usize, tree: RegexTree| {
+            let old = mem::replace(&mut edges[from][to], RegexTree::EMPTY);
+            edges[from][to] = RegexTree::alt(vec![old, tree]);
+        };
+        for (from, state) in self.states.iter().enumerate() {
+            for &to in &state.epsilon_transitions {
+                add_edge(from, to, RegexTree::EPSILON);
+            }
+            for (tos, elem) in state.transitions.iter().zip(self.alphabet.iter()) {
+                for &to in tos {
+                    add_edge(from, to, RegexTree::Char(RegexChar::Grapheme(elem.clone())));
+                }
+            }
+            if state.accepting {
+                add_edge(from, end, RegexTree::EPSILON);
+            }
+        }
+        add_edge(start, self.initial_state, RegexTree::EPSILON);
+
+        let mut remaining = (0..n).collect::<Vec<_>>();
+        while !remaining.is_empty() {
+            let neighbours = |edges: &[Vec<RegexTree>], k: usize| {
+                let incoming = (0..n + 2)
+                    .filter(|&p| p != k && edges[p][k] != RegexTree::EMPTY)
+                    .collect::<Vec<_>>();
+                let outgoing = (0..n + 2)
+                    .filter(|&q| q != k && edges[k][q] != RegexTree::EMPTY)
+                    .collect::<Vec<_>>();
+                (incoming, outgoing)
+            };
+            let pos = (0..remaining.len())
+                .min_by_key(|&pos| {
+                    let (incoming, outgoing) = neighbours(&edges, remaining[pos]);
+                    incoming.len() * outgoing.len()
+                })
+                .unwrap();
+            let k = remaining.swap_remove(pos);
+
+            // Every path p -> k -> q is replaced by p -> q, labeled by (p -> k)(k -> k)*(k -> q)
+            let (incoming, outgoing) = neighbours(&edges, k);
+            let self_loop = RegexTree::star(mem::replace(&mut edges[k][k], RegexTree::EMPTY));
+            for &p in &incoming {
+                for &q in &outgoing {
+                    let through = RegexTree::seq(vec![
+                        edges[p][k].clone(),
+                        self_loop.clone(),
+                        edges[k][q].clone(),
+                    ]);
+                    let old = mem::replace(&mut edges[p][q], RegexTree::EMPTY);
+                    edges[p][q] = RegexTree::alt(vec![old, through]);
+                }
+            }
+            for p in incoming {
+                edges[p][k] = RegexTree::EMPTY;
+            }
+            for q in outgoing {
+                edges[k][q] = RegexTree::EMPTY;
+            }
+        }
 
-    /// Checks if this automaton accepts the given string of graphemes, if every grapheme by
-    /// itself is considered as an element of the alphabet. Note that if the alphabet contains
-    /// elements with multiple graphemes, those won't be recognized. To check if there are
-    /// elements with multiple graphemes, see [Nfa::graphemes_only]. A grapheme is defined to be
-    /// one extended unicode grapheme cluster (which may consist of one or many code points).
-    pub fn accepts_graphemes(&self, string: &str) -> bool {
-        let graphemes = string.graphemes(true).collect::<Vec<_>>();
-        let mut eval = self.evaluator();
-        eval.step_multiple(&graphemes);
-        eval.is_accepting()
+        Regex::from(mem::replace(&mut edges[start][end], RegexTree::EMPTY))
     }
 
-    /// Checks if the alphabet of this automaton consists of only single graphemes. If it does, one may use
-    /// [Nfa::accepts_graphemes] instead of [Nfa::accepts] for improved ergonomics. A grapheme is defined to be
-    /// one extended unicode grapheme cluster (which may consist of one or many code points).
-    pub fn graphemes_only(&self) -> bool {
-        self.alphabet
-            .iter()
-            .all(|str| str.graphemes(true).count() == 1)
-    }
+    /// Computes the canonical residual finite-state automaton (RFSA) of the language of this NFA, as defined by Denis,
+    /// Lemay and Terlutte. Its states are the *prime* residual languages `u⁻¹L = { w | uw ∈ L }`, that is, the
+    /// non-empty residuals which aren't the union of the residuals strictly contained in them. There is a transition
+    /// from `R` to `R'` upon seeing `a` whenever `R' ⊆ a⁻¹R`, and the states whose residuals contain the empty word are
+    /// accepting.
+    ///
+    /// Since the states are a subset of the states of the [minimal DFA](Dfa::minimize), the result is never larger
+    /// than that DFA (except for one extra initial state, needed when the language itself isn't prime), and it's often
+    /// much smaller. The result has no ε-moves, and its states are named after the states of the minimal DFA. Note that
+    /// this is computed from the minimal DFA, so it has the same exponential worst case as [Nfa::to_dfa].
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// // Accepts all words where the third last element is "a"
+    /// let input = "
+    ///           a       b
+    /// -> s₀ {s₀ s₁} {s₀}
+    ///    s₁ {s₂}    {s₂}
+    ///    s₂ {s₃}    {s₃}
+    ///  * s₃ {}      {}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(input).unwrap().try_into().unwrap();
+    /// let mut dfa = nfa.to_dfa();
+    /// dfa.minimize();
+    /// let residual = nfa.to_residual_automaton();
+    /// assert_eq!(dfa.states().len(), 8);
+    /// assert_eq!(residual.states().len(), 4);
+    /// assert!(residual.equivalent_to(&nfa));
+    /// ```
+    pub fn to_residual_automaton(&self) -> Nfa {
+        let mut dfa = self.to_dfa();
+        dfa.minimize();
+        let n = dfa.states.len();
+        let transition = |state: usize, elem: usize| dfa.states[state].transitions[elem];
+
+        // included[p][q] is whether the language of state p is a subset of the language of state q, computed as
+        // a greatest fixpoint
+        let mut included = (0..n)
+            .map(|p| {
+                (0..n)
+                    .map(|q| !dfa.states[p].accepting || dfa.states[q].accepting)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for p in 0..n {
+                for q in 0..n {
+                    if included[p][q]
+                        && (0..dfa.alphabet.len())
+                            .any(|elem| !included[transition(p, elem)][transition(q, elem)])
+                    {
+                        included[p][q] = false;
+                        changed = true;
+                    }
+                }
+            }
+        }
 
-    /// Checks if this automaton has any epsilon moves
-    pub fn has_epsilon_moves(&self) -> bool {
-        self.states
-            .iter()
-            .any(|state| !state.epsilon_transitions.is_empty())
-    }
+        // Checks if the language of the state is included in the union of the languages of the given states,
+        // by following the state and the set of states in parallel
+        let covered_by = |state: usize, parts: BTreeSet<usize>| {
+            let mut seen = HashSet::from([(state, parts.clone())]);
+            let mut queue = vec![(state, parts)];
+            while let Some((state, parts)) = queue.pop() {
+                if dfa.states[state].accepting && parts.iter().all(|&p| !dfa.states[p].accepting) {
+                    return false;
+                }
+                for elem in 0..dfa.alphabet.len() {
+                    let next = (
+                        transition(state, elem),
+                        parts.iter().map(|&p| transition(p, elem)).collect(),
+                    );
+                    if seen.insert(next.clone()) {
+                        queue.push(next);
+                    }
+                }
+            }
+            true
+        };
 
-    /// Gets an evaluator, which is a struct that is used to evaluate strings with the automaton
-    pub fn evaluator(&self) -> NfaEvaluator<'_> {
-        self.into()
+        // States are distinguishable in a minimal DFA, so strict inclusion is inclusion between different states.
+        // The empty language is the empty union, so it is never prime.
+        let primes = (0..n)
+            .filter(|&q| !covered_by(q, (0..n).filter(|&p| p != q && included[p][q]).collect()))
+            .collect::<Vec<_>>();
+        let state_for = |state: usize, name: Rc<str>| NfaState {
+            name,
+            initial: false,
+            accepting: dfa.states[state].accepting,
+            epsilon_transitions: vec![],
+            transitions: dfa.states[state]
+                .transitions
+                .iter()
+                .map(|&target| {
+                    (0..primes.len())
+                        .filter(|&idx| included[primes[idx]][target])
+                        .collect()
+                })
+                .collect(),
+        };
+
+        let mut nfa = Nfa {
+            alphabet: dfa.alphabet.clone(),
+            states: primes
+                .iter()
+                .map(|&q| state_for(q, dfa.states[q].name.clone()))
+                .collect(),
+            initial_state: 0,
+        };
+        nfa.initial_state = match primes.iter().position(|&q| q == dfa.initial_state) {
+            Some(idx) => idx,
+            None => {
+                // The language isn't prime, so the initial state is the union of the primes included in it
+                let name = nfa.fresh_name("init");
+                nfa.states.push(state_for(dfa.initial_state, name));
+                nfa.states.len() - 1
+            }
+        };
+        nfa.states[nfa.initial_state].initial = true;
+        nfa
     }
 
     /// Gives the epsilon closure of a state, given the state index
@@ -1183,18 +1854,180 @@ impl Nfa {
         Some(all)
     }
 
-    /// Generates a table of this NFA suitable for printing, which may be parsed again to this automaton
+    /// Computes the epsilon closure of every state once, indexed by state, so that the closure of a set of states is
+    /// the union of the closures of its states
+    fn epsilon_closures(&self) -> Vec<FixedBitSet> {
+        let mut closures = Vec::with_capacity(self.states.len());
+        let mut to_visit = vec![];
+        for start in 0..self.states.len() {
+            let mut closure = FixedBitSet::with_capacity(self.states.len());
+            closure.insert(start);
+            to_visit.push(start);
+            while let Some(state) = to_visit.pop() {
+                for &eps_target in &self.states[state].epsilon_transitions {
+                    if !closure.put(eps_target) {
+                        to_visit.push(eps_target);
+                    }
+                }
+            }
+            closures.push(closure);
+        }
+        closures
+    }
+
+    /// Generates a table of this NFA suitable for printing, which may be parsed again to this automaton. The columns
+    /// are padded so that they line up, like for [Dfa::to_table], and the states in each set of transitions are ordered
+    /// like the states of the automaton.
     pub fn to_table(&self) -> String {
-        self.gen_table("ε", "→")
+        self.gen_table("ε", "→", |table| table.to_string(" "))
+    }
+
+    /// Generates a table of this NFA for reading, which may be parsed again to this automaton. The columns line up
+    /// like for [Dfa::to_table_aligned], with the widths counted in graphemes and no trailing whitespace.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///       ε   a
+    /// -> q̄ {q} {q̄ q}
+    ///  * q {}  {}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.to_table_aligned().lines().collect::<Vec<_>>(), vec![
+    ///     "      ε   a",
+    ///     "→   q̄ {q} {q̄ q}",
+    ///     "  * q {}  {}",
+    /// ]);
+    /// ```
+    pub fn to_table_aligned(&self) -> String {
+        self.gen_table("ε", "→", |table| table.to_aligned_string(" "))
     }
 
     /// Generates a table of this NFA suitable for printing, which may be parsed again to this automaton. The epsilon
     /// character is represented "eps" and the arrow for the initial state is "->"
     pub fn ascii_table(&self) -> String {
-        self.gen_table("eps", "->")
+        self.gen_table("eps", "->", |table| table.to_string(" "))
     }
 
-    fn gen_table(&self, eps: &str, arrow: &str) -> String {
+    /// Generates a [Mermaid](https://mermaid.js.org/) state diagram of this NFA, in the same way as
+    /// [Dfa::to_mermaid]. ε-transitions are labeled with `ε`.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///             ε     a       b
+    /// ->  s₀      {}    {s₀ s₁} {s₀}
+    ///   * s₁      {s₀}  {}      {s₀}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.to_mermaid(), "\
+    /// stateDiagram-v2
+    ///     state \"s₀\" as state0
+    ///     state \"s₁\" as state1
+    ///     [*] --> state0
+    ///     state0 --> state0 : a, b
+    ///     state0 --> state1 : a
+    ///     state1 --> state0 : b, ε
+    ///     classDef accepting stroke-width:4px
+    ///     class state1 accepting
+    /// ");
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let (states, transitions) = self.diagram_parts();
+        mermaid::state_diagram(&states, &transitions)
+    }
+
+    /// Generates a [Graphviz](https://graphviz.org/) DOT graph of this NFA, in the same way as [Dfa::to_dot].
+    /// ε-transitions are labeled with `ε`.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///             ε     a       b
+    /// ->  s₀      {}    {s₀ s₁} {s₀}
+    ///   * s₁      {s₀}  {}      {s₀}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.to_dot(), "\
+    /// digraph {
+    ///     rankdir=LR;
+    ///     node [shape=circle];
+    ///     start [shape=none, label=\"\"];
+    ///     q0 [label=\"s₀\"];
+    ///     q1 [label=\"s₁\", shape=doublecircle];
+    ///     start -> q0;
+    ///     q0 -> q0 [label=\"a, b\"];
+    ///     q0 -> q1 [label=\"a\"];
+    ///     q1 -> q0 [label=\"b, ε\"];
+    /// }
+    /// ");
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let (states, transitions) = self.diagram_parts();
+        dot::digraph(&states, &transitions)
+    }
+
+    /// Generates a `tikzpicture` of this NFA for LaTeX documents, in the same way as [Dfa::to_tikz]. ε-transitions
+    /// are labeled with `$\varepsilon$`.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///          ε     a       b
+    /// -> s_0   {}    {s_0 s_1} {s_0}
+    ///    s_1   {s_2} {}        {}
+    ///  * s_2   {}    {}        {}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.to_tikz(), r"\begin{tikzpicture}[->, >=stealth, auto]
+    ///     \node[state, initial] (q0) at (0, 0) {s\_0};
+    ///     \node[state] (q1) at (2, 0) {s\_1};
+    ///     \node[state, accepting] (q2) at (4, 0) {s\_2};
+    ///     \path (q0) edge[loop above] node {a, b} (q0);
+    ///     \path (q0) edge node {a} (q1);
+    ///     \path (q1) edge node {$\varepsilon$} (q2);
+    /// \end{tikzpicture}
+    /// ");
+    /// ```
+    pub fn to_tikz(&self) -> String {
+        let (states, transitions) = self.diagram_parts();
+        tikz::picture(&states, &transitions)
+    }
+
+    /// Gets the states and the transitions (as `(from, to, label)`) of this NFA, as given to the diagram exporters
+    fn diagram_parts(&self) -> (Vec<DiagramState<'_>>, Vec<(usize, usize, &str)>) {
+        let states = self
+            .states
+            .iter()
+            .map(|state| DiagramState {
+                name: &state.name,
+                initial: state.initial,
+                accepting: state.accepting,
+            })
+            .collect::<Vec<_>>();
+        let transitions = self
+            .states
+            .iter()
+            .enumerate()
+            .flat_map(|(from, state)| {
+                let epsilon = state
+                    .epsilon_transitions
+                    .iter()
+                    .map(move |&to| (from, to, "ε"));
+                let symbols = state.transitions.iter().zip(self.alphabet.iter()).flat_map(
+                    move |(tos, label)| tos.iter().map(move |&to| (from, to, label as &str)),
+                );
+                epsilon.chain(symbols)
+            })
+            .collect::<Vec<_>>();
+        (states, transitions)
+    }
+
+    fn gen_table(&self, eps: &str, arrow: &str, render: impl FnOnce(&Table) -> String) -> String {
         let mut table = Table::default();
 
         let mut alph = vec!["", "", "", eps];
@@ -1208,6 +2041,9 @@ impl Nfa {
                 iter::once(&state.epsilon_transitions)
                     .chain(&state.transitions)
                     .map(|trans| {
+                        // Sorted by index so that the output doesn't depend on the order of the transitions
+                        let mut trans = trans.clone();
+                        trans.sort_unstable();
                         let s = trans
                             .iter()
                             .map(|c| self.states[*c].name.clone())
@@ -1228,7 +2064,7 @@ impl Nfa {
             state.extend(trans_strings[idx].iter().map(|s| s as &str));
             table.push_row(state);
         }
-        table.to_string(" ")
+        render(&table)
     }
 
     /// Checks if this NFA is equivalent to another NFA, that is, if they accept the same language.
@@ -1238,15 +2074,303 @@ impl Nfa {
         self.separable_from(other).is_none()
     }
 
+    /// Checks if the language of this NFA is a subset of the language of another NFA, that is, if every word accepted
+    /// by this NFA is accepted by the other NFA as well. The alphabets must be equal (not considering ordering),
+    /// otherwise [AlphabetMismatch] is returned.
+    ///
+    /// This uses the antichain algorithm, which doesn't determinize the other NFA. Instead, it explores pairs of a
+    /// state of this NFA and the set of states the other NFA is in on the same word, searching for a pair where the
+    /// state is accepting while none of the states in the set is. A pair is skipped if a pair with the same state and a
+    /// subset of its set has already been explored, since any word rejected by the other NFA from the larger set is
+    /// rejected from the smaller set as well.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let ends_with_aab = "
+    ///             a       b
+    ///     ->  s1 {s1 s2} {s1}
+    ///         s2 {s3}    {}
+    ///         s3 {}      {s4}
+    ///       * s4 {}      {}
+    /// ";
+    /// let ends_with_b = "
+    ///            a    b
+    ///     -> s1 {s1} {s1 s2}
+    ///      * s2 {}   {}
+    /// ";
+    /// let ends_with_aab: Nfa = parser::nfa(ends_with_aab).unwrap().try_into().unwrap();
+    /// let ends_with_b: Nfa = parser::nfa(ends_with_b).unwrap().try_into().unwrap();
+    /// assert_eq!(ends_with_aab.is_subset_of(&ends_with_b), Ok(true));
+    /// assert_eq!(ends_with_b.is_subset_of(&ends_with_aab), Ok(false));
+    /// ```
+    pub fn is_subset_of(&self, other: &Nfa) -> Result<bool, AlphabetMismatch> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return Err(AlphabetMismatch);
+        }
+
+        // other_idx[i] is the index of the i'th element of our alphabet in the alphabet of 'other'
+        let other_idx = self
+            .alphabet
+            .iter()
+            .map(|elem| other.alphabet.iter().position(|e| e == elem).unwrap())
+            .collect::<Vec<_>>();
+        let closures = (0..other.states.len())
+            .map(|state| other.closure(state).unwrap())
+            .collect::<Vec<_>>();
+        let close = |states: &mut dyn Iterator<Item = &usize>| {
+            states
+                .flat_map(|&state| closures[state].iter().copied())
+                .collect::<BTreeSet<_>>()
+        };
+
+        // For each state of this NFA, the minimal sets of states of the other NFA it has been explored together with
+        let mut antichains = vec![Vec::<BTreeSet<usize>>::new(); self.states.len()];
+        let initial = (
+            self.initial_state,
+            close(&mut iter::once(&other.initial_state)),
+        );
+        antichains[initial.0].push(initial.1.clone());
+        let mut queue = vec![initial];
+        while let Some((state, set)) = queue.pop() {
+            if self.states[state].accepting && set.iter().all(|&s| !other.states[s].accepting) {
+                return Ok(false);
+            }
+
+            let mut next = self.states[state]
+                .epsilon_transitions
+                .iter()
+                .map(|&eps| (eps, set.clone()))
+                .collect::<Vec<_>>();
+            for (elem, transitions) in self.states[state].transitions.iter().enumerate() {
+                if transitions.is_empty() {
+                    continue;
+                }
+                let next_set = close(
+                    &mut set
+                        .iter()
+                        .flat_map(|&s| &other.states[s].transitions[other_idx[elem]]),
+                );
+                next.extend(transitions.iter().map(|&t| (t, next_set.clone())));
+            }
+
+            for (state, set) in next {
+                let antichain = &mut antichains[state];
+                if antichain.iter().any(|explored| explored.is_subset(&set)) {
+                    continue;
+                }
+                antichain.retain(|explored| !set.is_subset(explored));
+                antichain.push(set.clone());
+                queue.push((state, set));
+            }
+        }
+        Ok(true)
+    }
+
+    /// Checks if this NFA is unambiguous, that is, if every accepted word has exactly one accepting run. A run is a
+    /// sequence of transitions (including ε-moves), so for example a state with two ε-moves which both lead to the
+    /// same accepting state makes the NFA ambiguous. A state listed multiple times in one transition counts as one
+    /// transition.
+    ///
+    /// This is checked by following pairs of runs on the same word from the initial state (in the product of the NFA
+    /// with itself), where the NFA is ambiguous exactly if two runs which have taken different transitions can both
+    /// be accepting at the same time.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let ambiguous = "
+    ///           a       b
+    /// -> s₀ {s₁ s₂} {}
+    ///  * s₁ {}      {}
+    ///  * s₂ {}      {}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(ambiguous).unwrap().try_into().unwrap();
+    /// assert!(!nfa.is_unambiguous()); // "a" has two accepting runs
+    ///
+    /// let unambiguous = "
+    ///           a    b
+    /// -> s₀ {s₁} {s₂}
+    ///  * s₁ {}   {}
+    ///  * s₂ {}   {}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(unambiguous).unwrap().try_into().unwrap();
+    /// assert!(nfa.is_unambiguous());
+    /// ```
+    pub fn is_unambiguous(&self) -> bool {
+        /// Where two runs on the same word are
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        enum Runs {
+            /// Both runs have taken the same transitions, ending up in this state
+            Same(usize),
+            /// The first run took an ε-move where the second run read the element with the given index instead,
+            /// so the first run has to read that element before it has caught up with the second run
+            Lagging(usize, usize, usize),
+            /// The runs have taken different transitions, and are in these states
+            Diverged(usize, usize),
+        }
+
+        let distinct_pairs = |first: &[usize], second: &[usize]| {
+            first
+                .iter()
+                .flat_map(|&p| second.iter().map(move |&q| (p, q)))
+                .filter(|(p, q)| p != q)
+                .collect::<Vec<_>>()
+        };
+
+        let initial = Runs::Same(self.initial_state);
+        let mut seen = HashSet::from([initial]);
+        let mut queue = vec![initial];
+        while let Some(runs) = queue.pop() {
+            let mut next = vec![];
+            match runs {
+                Runs::Same(s) => {
+                    let state = &self.states[s];
+                    let eps = &state.epsilon_transitions;
+                    // One run ending here while the other continues by ε-moves
+                    if state.accepting
+                        && eps.iter().any(|&e| {
+                            self.closure(e)
+                                .unwrap()
+                                .iter()
+                                .any(|&c| self.states[c].accepting)
+                        })
+                    {
+                        return false;
+                    }
+                    next.extend(eps.iter().map(|&e| Runs::Same(e)));
+                    next.extend(
+                        distinct_pairs(eps, eps)
+                            .into_iter()
+                            .map(|(p, q)| Runs::Diverged(p, q)),
+                    );
+                    for (elem, trans) in state.transitions.iter().enumerate() {
+                        next.extend(trans.iter().map(|&t| Runs::Same(t)));
+                        next.extend(
+                            distinct_pairs(trans, trans)
+                                .into_iter()
+                                .map(|(p, q)| Runs::Diverged(p, q)),
+                        );
+                        // By symmetry, the run taking the ε-move may always be the first one
+                        next.extend(
+                            eps.iter().flat_map(|&e| {
+                                trans.iter().map(move |&t| Runs::Lagging(e, t, elem))
+                            }),
+                        );
+                    }
+                }
+                Runs::Lagging(p, q, elem) => {
+                    let state = &self.states[p];
+                    next.extend(
+                        state
+                            .epsilon_transitions
+                            .iter()
+                            .map(|&e| Runs::Lagging(e, q, elem)),
+                    );
+                    next.extend(
+                        state.transitions[elem]
+                            .iter()
+                            .map(|&t| Runs::Diverged(t, q)),
+                    );
+                }
+                Runs::Diverged(p, q) => {
+                    let (p_state, q_state) = (&self.states[p], &self.states[q]);
+                    if p_state.accepting && q_state.accepting {
+                        return false;
+                    }
+                    next.extend(
+                        p_state
+                            .epsilon_transitions
+                            .iter()
+                            .map(|&e| Runs::Diverged(e, q)),
+                    );
+                    next.extend(
+                        q_state
+                            .epsilon_transitions
+                            .iter()
+                            .map(|&e| Runs::Diverged(p, e)),
+                    );
+                    for (p_trans, q_trans) in p_state.transitions.iter().zip(&q_state.transitions) {
+                        next.extend(
+                            p_trans
+                                .iter()
+                                .flat_map(|&p| q_trans.iter().map(move |&q| Runs::Diverged(p, q))),
+                        );
+                    }
+                }
+            }
+            for runs in next {
+                if seen.insert(runs) {
+                    queue.push(runs);
+                }
+            }
+        }
+        true
+    }
+
+    /// Checks if this NFA is equivalent to another NFA, giving the same result as [Nfa::equivalent_to]. Instead of
+    /// determinizing the NFAs, this checks that each language is a subset of the other with [Nfa::is_subset_of],
+    /// which often scales to much larger NFAs.
+    pub fn equivalent_to_antichain(&self, other: &Nfa) -> bool {
+        self.is_subset_of(other) == Ok(true) && other.is_subset_of(self) == Ok(true)
+    }
+
     pub fn separable_from(&self, other: &Nfa) -> Option<Option<String>> {
         self.to_dfa().separable_from(&other.to_dfa())
     }
+}
+
+// The evaluation path, which only depends on `alloc` and is available without the `std` feature
+impl Nfa {
+    /// Checks if this automaton accepts the given string. This is equivalent to getting the
+    /// evaluator, stepping it multiple times and checking if it is accepting
+    pub fn accepts(&self, string: &[&str]) -> bool {
+        self.accepts_iter(string)
+    }
+
+    /// Checks if this automaton accepts the string given as an iterator of elements of the alphabet. The elements are
+    /// consumed one at a time, so the string never has to be collected. If an element not in the alphabet is seen,
+    /// the string is rejected without consuming the rest of the iterator.
+    pub fn accepts_iter<S: AsRef<str>>(&self, string: impl IntoIterator<Item = S>) -> bool {
+        let mut eval = self.evaluator();
+        for elem in string {
+            if eval.step(elem.as_ref()).is_none() {
+                return false;
+            }
+        }
+        eval.is_accepting()
+    }
 
-    /// Converts a HashSet (which is not hashable) to a Vec (which is hashable) in a determenistic way
-    fn set_to_vec<T: Clone + Ord>(set: &HashSet<T>) -> Vec<T> {
-        let mut vec = set.iter().cloned().collect::<Vec<_>>();
-        vec.sort();
-        vec
+    /// Checks if this automaton accepts the given string of graphemes, if every grapheme by
+    /// itself is considered as an element of the alphabet. Note that if the alphabet contains
+    /// elements with multiple graphemes, those won't be recognized. To check if there are
+    /// elements with multiple graphemes, see [Nfa::graphemes_only]. A grapheme is defined to be
+    /// one extended unicode grapheme cluster (which may consist of one or many code points).
+    pub fn accepts_graphemes(&self, string: &str) -> bool {
+        self.accepts_iter(string.graphemes(true))
+    }
+
+    /// Checks if the alphabet of this automaton consists of only single graphemes. If it does, one may use
+    /// [Nfa::accepts_graphemes] instead of [Nfa::accepts] for improved ergonomics. A grapheme is defined to be
+    /// one extended unicode grapheme cluster (which may consist of one or many code points).
+    pub fn graphemes_only(&self) -> bool {
+        self.alphabet
+            .iter()
+            .all(|str| str.graphemes(true).count() == 1)
+    }
+
+    /// Checks if this automaton has any epsilon moves
+    pub fn has_epsilon_moves(&self) -> bool {
+        self.states
+            .iter()
+            .any(|state| !state.epsilon_transitions.is_empty())
+    }
+
+    /// Gets an evaluator, which is a struct that is used to evaluate strings with the automaton
+    pub fn evaluator(&self) -> NfaEvaluator<'_> {
+        self.into()
     }
 
     /// Gets the alphabet of this NFA
@@ -1268,4 +2392,78 @@ impl Nfa {
     pub fn initial_state_index(&self) -> usize {
         self.initial_state
     }
+
+    /// Deconstructs this NFA into its alphabet, its states and the index of its initial state. The NFA can be
+    /// constructed again with [Nfa::from_parts].
+    pub fn into_parts(self) -> (Vec<Rc<str>>, Vec<NfaState>, usize) {
+        (self.alphabet.to_vec(), self.states, self.initial_state)
+    }
+
+    /// Constructs a NFA from its alphabet, its states and the index of its initial state, such as those given by
+    /// [Nfa::into_parts]. The parts are checked in the same way as when converting a [parsed](crate::parser::nfa) NFA:
+    /// the alphabet and the state names must not contain duplicates, every state must have one set of transitions for
+    /// each element of the alphabet, all transitions (including epsilon transitions) must go to existing states, and
+    /// exactly one state (the one at `initial_state`) must be marked as initial.
+    ///
+    /// ```
+    /// use dandy::dfa::ConstructionError;
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///        ε    a       b
+    /// -> s₀  {}   {s₀ s₁} {s₀}
+    ///  * s₁  {s₀} {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// let (alphabet, states, initial_state) = nfa.clone().into_parts();
+    /// assert_eq!(Nfa::from_parts(alphabet.clone(), states.clone(), initial_state), Ok(nfa));
+    /// assert_eq!(
+    ///     Nfa::from_parts(alphabet, states, 2),
+    ///     Err(ConstructionError::InitialStateDoesNotExist(2))
+    /// );
+    /// ```
+    pub fn from_parts(
+        alphabet: Vec<Rc<str>>,
+        states: Vec<NfaState>,
+        initial_state: usize,
+    ) -> Result<Nfa, ConstructionError> {
+        ConstructionError::check_common(
+            &alphabet,
+            states.iter().map(|state| (&state.name, state.initial)),
+            initial_state,
+        )?;
+        for state in &states {
+            if state.transitions.len() != alphabet.len() {
+                return Err(ConstructionError::WrongNumberOfTransitions(
+                    state.name.clone(),
+                    state.transitions.len(),
+                    alphabet.len(),
+                ));
+            }
+            if let Some(&target) = state
+                .epsilon_transitions
+                .iter()
+                .chain(state.transitions.iter().flatten())
+                .find(|&&t| t >= states.len())
+            {
+                return Err(ConstructionError::TransitionDoesNotExist(
+                    state.name.clone(),
+                    target,
+                ));
+            }
+        }
+        Ok(Nfa {
+            alphabet: alphabet.into(),
+            states,
+            initial_state,
+        })
+    }
+}
+
+/// Writes the same table as [Nfa::to_table]
+#[cfg(feature = "std")]
+impl Display for Nfa {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_table())
+    }
 }
@@ -1,7 +1,6 @@
 use crate::nfa::{Nfa, NfaState};
 use crate::parser::{NfaAlphabetEntry, ParsedNfa, ParsedNfaState};
 use std::collections::{HashMap, HashSet};
-use std::ops::Not;
 use std::rc::Rc;
 use thiserror::Error;
 
@@ -25,26 +24,56 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
     type Error = NfaParseError<'a>;
 
     fn try_from(value: ParsedNfa<'a>) -> Result<Self, Self::Error> {
+        Nfa::validate_all(value).map_err(|mut errors| errors.swap_remove(0))
+    }
+}
+
+impl Nfa {
+    /// Converts a [ParsedNfa] to a [Nfa] like [TryFrom::try_from], but instead of stopping at the first error, this
+    /// collects all errors in the parsed NFA. The first error is the one [TryFrom::try_from] would give.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::nfa::parse::NfaParseError;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    ///        ε   a
+    /// -> s   {t} {}
+    /// -> t   {u} {s v}
+    ///    s   {}  {}
+    /// ";
+    /// let errors = Nfa::validate_all(parser::nfa(input).unwrap()).unwrap_err();
+    /// assert_eq!(errors, vec![
+    ///     NfaParseError::DuplicateStateDefinition("s"),
+    ///     NfaParseError::TransitionDoesNotExist("t", "u"),
+    ///     NfaParseError::TransitionDoesNotExist("t", "v"),
+    ///     NfaParseError::MultipleInitialStates,
+    /// ]);
+    /// ```
+    pub fn validate_all(parsed: ParsedNfa<'_>) -> Result<Nfa, Vec<NfaParseError<'_>>> {
         use NfaParseError::*;
-        let ParsedNfa { head, states } = value;
+        let ParsedNfa { head, states } = parsed;
+        let mut errors = vec![];
 
         let mut eps_idx = None;
         {
             let mut alphabet = HashSet::new();
-            head.iter()
-                .enumerate()
-                .try_for_each(|(idx, e)| match e {
-                    NfaAlphabetEntry::Element(c) => alphabet.insert(c).then_some(()).ok_or(c),
-                    NfaAlphabetEntry::Eps => {
-                        if eps_idx.is_some() {
-                            Err(&"ε")
-                        } else {
-                            eps_idx = Some(idx);
-                            Ok(())
-                        }
+            let mut reported = HashSet::new();
+            head.iter().enumerate().for_each(|(idx, e)| match e {
+                NfaAlphabetEntry::Element(c) => {
+                    if !alphabet.insert(*c) && reported.insert(*c) {
+                        errors.push(DuplicateAlphabetSymbol(c))
                     }
-                })
-                .map_err(|e| DuplicateAlphabetSymbol(e))?
+                }
+                NfaAlphabetEntry::Eps => {
+                    if eps_idx.is_none() {
+                        eps_idx = Some(idx);
+                    } else if reported.insert("ε") {
+                        errors.push(DuplicateAlphabetSymbol("ε"))
+                    }
+                }
+            });
         }
 
         let state_name_map: HashMap<_, _> = states
@@ -54,13 +83,13 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
             .collect();
 
         if state_name_map.len() != states.len() {
-            // We have a duplicate name, let's find it!
+            // We have duplicate names, let's find them!
             let mut seen = HashSet::new();
-            let duplicate = states
+            let mut reported = HashSet::new();
+            states
                 .iter()
-                .find_map(|s| seen.insert(s.name).not().then_some(s.name))
-                .unwrap_or("<unknown>");
-            return Err(DuplicateStateDefinition(duplicate));
+                .filter(|s| !seen.insert(s.name) && reported.insert(s.name))
+                .for_each(|s| errors.push(DuplicateStateDefinition(s.name)));
         }
 
         let mut initial_state = None;
@@ -75,7 +104,7 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
             } = state;
 
             if transitions.len() != head.len() {
-                return Err(WrongNumberOfTransitions(
+                errors.push(WrongNumberOfTransitions(
                     name,
                     transitions.len(),
                     head.len(),
@@ -86,23 +115,16 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
             let mut new_transitions = Vec::with_capacity(head.len());
             for (idx, transition) in transitions.iter().enumerate() {
                 let mut tr_idx = Vec::with_capacity(transition.len());
-                if Some(idx) == eps_idx {
-                    for target in transition {
-                        if let Some(idx) = state_name_map.get(target) {
-                            tr_idx.push(*idx);
-                        } else {
-                            return Err(TransitionDoesNotExist(name, target)); // Target of transition does not exist
-                        }
+                for target in transition {
+                    if let Some(idx) = state_name_map.get(target) {
+                        tr_idx.push(*idx);
+                    } else {
+                        errors.push(TransitionDoesNotExist(name, target)); // Target of transition does not exist
                     }
+                }
+                if Some(idx) == eps_idx {
                     epsilon_transitions = Some(tr_idx);
                 } else {
-                    for target in transition {
-                        if let Some(idx) = state_name_map.get(target) {
-                            tr_idx.push(*idx);
-                        } else {
-                            return Err(TransitionDoesNotExist(name, target)); // Target of transition does not exist
-                        }
-                    }
                     new_transitions.push(tr_idx);
                 }
             }
@@ -110,8 +132,8 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
             if initial {
                 if initial_state.is_none() {
                     initial_state = Some(idx);
-                } else {
-                    return Err(MultipleInitialStates);
+                } else if !errors.contains(&MultipleInitialStates) {
+                    errors.push(MultipleInitialStates);
                 }
             }
 
@@ -124,8 +146,12 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
             });
         }
 
-        if let Some(initial_state) = initial_state {
-            let dfa = Nfa {
+        if initial_state.is_none() {
+            errors.push(MissingInitialState);
+        }
+
+        match initial_state {
+            Some(initial_state) if errors.is_empty() => Ok(Nfa {
                 alphabet: head
                     .into_iter()
                     .filter_map(|s| match s {
@@ -135,10 +161,8 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
                     .collect::<Rc<[_]>>(),
                 states: new_states,
                 initial_state,
-            };
-            Ok(dfa)
-        } else {
-            Err(MissingInitialState)
+            }),
+            _ => Err(errors),
         }
     }
 }
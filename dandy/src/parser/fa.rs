@@ -2,12 +2,12 @@ use crate::parser::{NfaAlphabetEntry, ParsedDfa, ParsedDfaState, ParsedNfa, Pars
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_till1};
 use nom::character::complete::{line_ending, not_line_ending, space0, space1};
-use nom::combinator::{eof, map, opt, recognize, value, verify};
+use nom::combinator::{all_consuming, eof, map, opt, recognize, value, verify};
 use nom::multi::{many0, many1, separated_list0, separated_list1};
 use nom::sequence::{delimited, pair, preceded, terminated, tuple};
 use nom::IResult;
 
-pub(crate) fn full_nfa(input: &str) -> IResult<&str, ParsedNfa> {
+pub(crate) fn full_nfa(input: &str) -> IResult<&str, ParsedNfa<'_>> {
     map(
         delimited(
             many0(space_comment_line),
@@ -24,7 +24,7 @@ pub(crate) fn full_nfa(input: &str) -> IResult<&str, ParsedNfa> {
     )(input)
 }
 
-fn nfa_head(input: &str) -> IResult<&str, Vec<NfaAlphabetEntry>> {
+fn nfa_head(input: &str) -> IResult<&str, Vec<NfaAlphabetEntry<'_>>> {
     delimited(
         space0,
         separated_list1(
@@ -38,7 +38,7 @@ fn nfa_head(input: &str) -> IResult<&str, Vec<NfaAlphabetEntry>> {
     )(input)
 }
 
-fn nfa_line(input: &str) -> IResult<&str, ParsedNfaState> {
+fn nfa_line(input: &str) -> IResult<&str, ParsedNfaState<'_>> {
     map(
         delimited(
             space0,
@@ -61,7 +61,7 @@ fn nfa_line(input: &str) -> IResult<&str, ParsedNfaState> {
     )(input)
 }
 
-pub(crate) fn full_dfa(input: &str) -> IResult<&str, ParsedDfa> {
+pub(crate) fn full_dfa(input: &str) -> IResult<&str, ParsedDfa<'_>> {
     map(
         delimited(
             many0(space_comment_line),
@@ -78,6 +78,52 @@ pub(crate) fn full_dfa(input: &str) -> IResult<&str, ParsedDfa> {
     )(input)
 }
 
+/// Parses the line with the alphabet of a DFA on its own, including its line ending if there is one
+pub(crate) fn dfa_head_line(input: &str) -> IResult<&str, Vec<&str>> {
+    all_consuming(terminated(dfa_head, alt((line_ending, eof))))(input)
+}
+
+/// Parses the line of one state of a DFA on its own, including its line ending if there is one
+pub(crate) fn dfa_state_line(input: &str) -> IResult<&str, ParsedDfaState<'_>> {
+    all_consuming(terminated(dfa_line, alt((line_ending, eof))))(input)
+}
+
+/// Parses any number of automata with `automaton`, separated by lines containing only `separator`. Separators and
+/// lines with only whitespace and comments before, between and after the automata are skipped. Everything else must be
+/// part of an automaton, so this fails where an automaton fails to parse or isn't followed by a separator.
+pub(crate) fn many_automata<'a, O>(
+    mut input: &'a str,
+    separator: &str,
+    mut automaton: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> IResult<&'a str, Vec<O>> {
+    let mut automata = vec![];
+    loop {
+        (input, _) = many0(alt((|i| separator_line(i, separator), space_comment_line)))(input)?;
+        if input.is_empty() {
+            return Ok((input, automata));
+        }
+        let (rest, parsed) = automaton(input)?;
+        automata.push(parsed);
+        input = rest;
+        if !input.is_empty() {
+            (input, _) = separator_line(input, separator)?;
+        }
+    }
+}
+
+/// Parses a line containing only `separator` (and possibly whitespace), including its line ending if there is one
+fn separator_line<'a>(input: &'a str, separator: &str) -> IResult<&'a str, ()> {
+    value(
+        (),
+        tuple((space0, tag(separator), space0, alt((line_ending, eof)))),
+    )(input)
+}
+
+/// Checks if a line contains only whitespace and comments
+pub(crate) fn is_blank_line(input: &str) -> bool {
+    input.is_empty() || all_consuming(space_comment_line)(input).is_ok()
+}
+
 fn dfa_head(input: &str) -> IResult<&str, Vec<&str>> {
     delimited(
         space0,
@@ -86,7 +132,7 @@ fn dfa_head(input: &str) -> IResult<&str, Vec<&str>> {
     )(input)
 }
 
-fn dfa_line(input: &str) -> IResult<&str, ParsedDfaState> {
+fn dfa_line(input: &str) -> IResult<&str, ParsedDfaState<'_>> {
     map(
         delimited(
             space0,
@@ -2,12 +2,12 @@ use crate::parser::{ParsedProduction, ParsedGrammar};
 use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case, take_till1};
 use nom::character::complete::{line_ending, not_line_ending, space0, space1};
-use nom::combinator::{eof, map, opt, recognize, value, verify};
+use nom::combinator::{cut, eof, map, opt, recognize, value, verify};
 use nom::multi::{many0, many1, separated_list0, separated_list1};
 use nom::sequence::{delimited, pair, preceded, terminated, tuple};
 use nom::IResult;
 
-pub(crate) fn full_grammar(input: &str) -> IResult<&str, ParsedGrammar> {
+pub(crate) fn full_grammar(input: &str) -> IResult<&str, ParsedGrammar<'_>> {
     map(
         delimited(
             many0(space_comment_line),
@@ -49,12 +49,13 @@ fn symbol(prompt: &str) -> impl Fn(&str) -> IResult<&str, &str> + use<'_> {
     }
 }
 
-fn production(input: &str) -> IResult<&str, ParsedProduction> {
+fn production(input: &str) -> IResult<&str, ParsedProduction<'_>> {
     map(
         preceded(
             space0,
             pair(
-                terminated(symbol_name, delimited(space1, arrow, space1)),
+                // Once the name has been read, this must be a production, so a missing arrow is reported here
+                terminated(symbol_name, cut(delimited(space1, arrow, space1))),
                 separated_list1(delimited(space0, pipe, space0), separated_list0(space1, symbol_name)),
             ),
         ),
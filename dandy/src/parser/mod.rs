@@ -6,7 +6,7 @@
 //! The file format for DFAs and NFAs are more or less a text representation of the transition table.
 //! The file describing a DFA and NFA should be an UTF-8-encoded file consisting of:
 //! - One line containing the alphabet, with whitespace-separated unique elements (the elements may be multiple
-//!     characters long). For denoting an NFA with epsilon moves, `ε` or `eps` may be used.
+//!   characters long). For denoting an NFA with epsilon moves, `ε` or `eps` may be used.
 //! - One line for each state, consisting of these whitespace-separated elements, in order:
 //!   - Optionally `->` or `→` for denoting that the state is the initial state (there must be exactly one)
 //!   - Optionally `*` for denoting that the state is accepting
@@ -43,25 +43,49 @@
 //! Any lines containing only whitespace are ignored, and if `#` appears on any line, that character and all subsequent
 //! characters on that line will be ignored (as a comment).
 //!
+//! Large DFA files can be parsed without reading the whole file into memory first with [dfa_from_reader], which
+//! parses one line at a time.
+//!
+//! Several DFAs or NFAs may be written in one file, separated by lines containing only `---`. Such files are parsed
+//! with [dfas] and [nfas], or with the [nom] parsers [dfa_many] and [nfa_many] as part of a larger format.
+//!
 //! ## Format for Regular Expressions
-//! There are eight reserved characters: `∅`, `ε`, `|`, `*`, `+`, `\`, `(` and `)`. Symbols distinct from them
-//! may be written as-is. To denote one of the reserved characters, you may escape it with a backslash `\`. Multiple
-//! characters in sequence are sequenced (implicit sequence operator). The alternation operator is `|`, Kleene plus
-//! and Kleene star are written as `+` and `*`, the empty language is written as `∅`, and the empty string is written
-//! as `ε`. Parenthesis is used for grouping `(`/`)`. This is very similar to regex in programming.
+//! There are twelve reserved characters: `∅`, `ε`, `|`, `*`, `+`, `\`, `(`, `)`, `[`, `]`, `{` and `}`. Symbols
+//! distinct from them may be written as-is. To denote one of the reserved characters, you may escape it with a backslash `\`.
+//! Multiple characters in sequence are sequenced (implicit sequence operator). The alternation operator is `|`, Kleene
+//! plus and Kleene star are written as `+` and `*`, the empty language is written as `∅`, and the empty string is
+//! written as `ε`. Parenthesis is used for grouping `(`/`)`. Character classes such as `[abc]` or `[a-z]` match one
+//! of the characters in them, and are desugared to alternations while parsing. Bounded repetition is written as `r{n}`,
+//! `r{n,m}` or `r{n,}` (exactly `n`, between `n` and `m`, or at least `n` repetitions), and is desugared to
+//! sequences, so the bounds may be at most [MAX_REPETITION]. This is very similar to regex in
+//! programming.
 //!
 //! - `(ab)+c` is a regular expression accepting strings starting with "ab" repeated 1 or many times, followed by "c"
 //! - `c(a|b)*c` accepts all strings starting with a `c`, then any amount of `a`s and `b`s, and then a `c`
+//! - `a{2,3}` accepts `aa` and `aaa`, and is the same as `aa(a|ε)`
+//! - `[a-c]*d` accepts all strings of `a`s, `b`s and `c`s, followed by a `d`
 //!
 //! Leading and trailing whitespace is ignored, but not whitespace within the expression itself.
 //!
+//! Extended regular expressions, parsed with [extended_regex], additionally reserve `&` and `~`. `r&s` matches the
+//! strings matched by both `r` and `s`, and `~r` matches the strings not matched by `r`. The complement is taken with
+//! respect to the symbols appearing in the whole expression. Intersection binds tighter than alternation and looser
+//! than sequencing, and complement applies to the element following it (including its quantifier), so `~ab*|c&d` is
+//! `((~a)(b*))|(c&d)` and `~a*` is `~(a*)`.
+//!
+//! - `~((a|b)*aa(a|b)*)` accepts all strings of `a`s and `b`s not containing `aa`
+//! - `(a|b)*a(a|b)*&(a|b)*b(a|b)*` accepts all strings of `a`s and `b`s containing both an `a` and a `b`
+//!
 
 mod fa;
 mod grammar;
 mod regex;
 
 use crate::regex::Regex;
-use nom::{combinator::all_consuming, error::Error, Finish};
+use nom::{combinator::all_consuming, error::Error, Finish, IResult};
+use std::io;
+use std::io::BufRead;
+use thiserror::Error;
 
 #[derive(Debug)]
 pub struct ParsedNfa<'a> {
@@ -97,6 +121,55 @@ pub struct ParsedDfaState<'a> {
     pub(crate) transitions: Vec<&'a str>,
 }
 
+/// A parsed DFA which owns the names of its alphabet and states, unlike [ParsedDfa] which borrows them from the
+/// parsed string. It is returned by [dfa_from_reader], since the lines read from a reader are dropped once they are
+/// parsed. Use [ParsedDfaOwned::as_parsed] to borrow it as a [ParsedDfa], for example to convert it to a
+/// [crate::dfa::Dfa].
+#[derive(Debug)]
+pub struct ParsedDfaOwned {
+    pub(crate) head: Vec<String>,
+    pub(crate) states: Vec<ParsedDfaStateOwned>,
+}
+
+#[derive(Debug)]
+pub struct ParsedDfaStateOwned {
+    pub(crate) name: String,
+    pub(crate) initial: bool,
+    pub(crate) accepting: bool,
+    pub(crate) transitions: Vec<String>,
+}
+
+impl ParsedDfaOwned {
+    /// Borrows this DFA as a [ParsedDfa]. Errors from converting the result to a [crate::dfa::Dfa] borrow names from
+    /// this DFA, so it must outlive them.
+    pub fn as_parsed(&self) -> ParsedDfa<'_> {
+        ParsedDfa {
+            head: self.head.iter().map(String::as_str).collect(),
+            states: self
+                .states
+                .iter()
+                .map(|state| ParsedDfaState {
+                    name: &state.name,
+                    initial: state.initial,
+                    accepting: state.accepting,
+                    transitions: state.transitions.iter().map(String::as_str).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<ParsedDfaState<'_>> for ParsedDfaStateOwned {
+    fn from(state: ParsedDfaState<'_>) -> Self {
+        ParsedDfaStateOwned {
+            name: state.name.to_string(),
+            initial: state.initial,
+            accepting: state.accepting,
+            transitions: state.transitions.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct ParsedGrammar<'a> {
     pub(crate) nonterminals: Vec<&'a str>,
@@ -111,34 +184,337 @@ pub struct ParsedProduction<'a> {
     pub(crate) alternatives: Vec<Vec<&'a str>>,
 }
 
-/// Parses a DFA according to the format above. The whole string must be parsable, otherwise this function errors.
+/// An error from parsing a DFA, NFA or grammar, with the position in the source where parsing failed. Lines and columns start
+/// at 1, and columns are counted in characters. The raw error from [nom] is also available.
+///
+/// ```
+/// use dandy::parser;
+///
+/// let input = "
+///        a  b
+/// -> s₀  s₁ s₀
+///  * s₁  s₁ {s₀}
+/// ";
+/// let err = parser::dfa(input).unwrap_err();
+/// assert_eq!((err.line, err.column), (4, 11));
+/// assert_eq!(err.to_string(), "error at line 4, column 11: unexpected '{s₀}'");
+/// ```
+#[derive(Debug, Error, PartialEq)]
+#[error("error at line {line}, column {column}: {message}")]
+pub struct ParseError<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub nom_error: Error<&'a str>,
+}
+
+impl<'a> ParseError<'a> {
+    /// Creates a parse error from a [nom] error, where the remaining input of the error is a suffix of `source`
+    pub(crate) fn from_nom(source: &'a str, nom_error: Error<&'a str>) -> Self {
+        // Point at the first non-blank character rather than at the blanks before it
+        let rest = nom_error.input.trim_start_matches([' ', '\t']);
+        let offset = source.len().saturating_sub(rest.len());
+        let before = source.get(..offset).unwrap_or_default();
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+        let message = if rest.is_empty() {
+            "unexpected end of input".to_string()
+        } else if rest.starts_with(['\n', '\r']) {
+            "unexpected end of line".to_string()
+        } else {
+            let token = rest.split(char::is_whitespace).next().unwrap_or(rest);
+            format!("unexpected '{token}'")
+        };
+
+        ParseError {
+            line,
+            column,
+            message,
+            nom_error,
+        }
+    }
+}
+
+/// Parses a DFA according to the format above. The whole string must be parsable, otherwise this function errors,
+/// giving the position where parsing failed.
 /// Note that the result is a [ParsedDfa], which is not guaranteed to be a valid [crate::dfa::Dfa]. Use
 /// [TryInto::try_into] to convert a [ParsedDfa] to a [crate::dfa::Dfa].
-pub fn dfa(input: &str) -> Result<ParsedDfa, Error<&str>> {
+pub fn dfa(input: &str) -> Result<ParsedDfa<'_>, ParseError<'_>> {
     all_consuming(fa::full_dfa)(input)
         .finish()
         .map(|(_, dfa)| dfa)
+        .map_err(|e| ParseError::from_nom(input, e))
 }
 
-/// Parses a NFA according to the format above. The whole string must be parsable, otherwise this function errors.
+/// Parses a NFA according to the format above. The whole string must be parsable, otherwise this function errors,
+/// giving the position where parsing failed.
 /// Note that the result is a [ParsedNfa], which is not guaranteed to be a valid [crate::nfa::Nfa]. Use
 /// [TryInto::try_into] to convert a [ParsedNfa] to a [crate::nfa::Nfa].
-pub fn nfa(input: &str) -> Result<ParsedNfa, Error<&str>> {
+pub fn nfa(input: &str) -> Result<ParsedNfa<'_>, ParseError<'_>> {
     all_consuming(fa::full_nfa)(input)
         .finish()
         .map(|(_, nfa)| nfa)
+        .map_err(|e| ParseError::from_nom(input, e))
+}
+
+/// An error from [dfa_from_reader], which owns its message since the line it occurred on has been dropped
+#[derive(Debug, Error)]
+pub enum ReadError {
+    #[error("error reading input: {0}")]
+    Io(#[from] io::Error),
+    #[error("error at line {line}, column {column}: {message}")]
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl ReadError {
+    fn parse(line: usize, source: &str, nom_error: Error<&str>) -> Self {
+        let error = ParseError::from_nom(source, nom_error);
+        ReadError::Parse {
+            line,
+            column: error.column,
+            message: error.message,
+        }
+    }
+}
+
+/// Parses a DFA according to the format above from a reader, one line at a time, so that the whole input never has
+/// to be in memory at once. Since the lines are dropped after being parsed, the result is a [ParsedDfaOwned] which
+/// owns the names of the alphabet and the states, rather than a [ParsedDfa] borrowing from the input as returned by
+/// [dfa].
+///
+/// ```
+/// use dandy::dfa::Dfa;
+/// use dandy::parser;
+///
+/// let input = "
+///        a  b
+/// -> s₀  s₁ s₀
+///  * s₁  s₁ s₀ # comment
+/// ";
+/// let parsed = parser::dfa_from_reader(input.as_bytes()).unwrap();
+/// let dfa: Dfa = parsed.as_parsed().try_into().unwrap();
+/// assert!(dfa.accepts_graphemes("ba"));
+///
+/// let err = parser::dfa_from_reader("  a b\n-> s s {s}".as_bytes()).unwrap_err();
+/// assert_eq!(err.to_string(), "error at line 2, column 8: unexpected '{s}'");
+/// ```
+pub fn dfa_from_reader(mut reader: impl BufRead) -> Result<ParsedDfaOwned, ReadError> {
+    let mut head: Option<Vec<String>> = None;
+    let mut states = vec![];
+    let mut line = String::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+        if fa::is_blank_line(&line) {
+            continue;
+        }
+        if head.is_some() {
+            let (_, state) = fa::dfa_state_line(&line)
+                .finish()
+                .map_err(|e| ReadError::parse(line_number, &line, e))?;
+            states.push(state.into());
+        } else {
+            let (_, elems) = fa::dfa_head_line(&line)
+                .finish()
+                .map_err(|e| ReadError::parse(line_number, &line, e))?;
+            head = Some(elems.into_iter().map(String::from).collect());
+        }
+    }
+
+    match head {
+        Some(head) if !states.is_empty() => Ok(ParsedDfaOwned { head, states }),
+        _ => Err(ReadError::Parse {
+            line: line_number + 1,
+            column: 1,
+            message: "unexpected end of input".to_string(),
+        }),
+    }
+}
+
+/// The default separator between automata for [dfas] and [nfas]
+pub const SEPARATOR: &str = "---";
+
+/// A [nom] parser for several DFAs, separated by lines containing only `---` (and possibly whitespace), which can be
+/// used as part of a larger parser. Separators and lines containing only whitespace and comments before, between and
+/// after the DFAs are skipped. Each DFA is parsed in the same way as with [dfa]. The parser stops at the end of the
+/// input, and fails where a DFA fails to parse or isn't followed by a separator.
+///
+/// ```
+/// use dandy::parser;
+///
+/// let (rest, dfas) = parser::dfa_many("a\n-> s s\n---\n---\nb\n-> t t\n").unwrap();
+/// assert_eq!(rest, "");
+/// assert_eq!(dfas.len(), 2);
+/// assert!(parser::dfa_many("a\n-> s s\nb\n-> t t\n").is_err());
+/// ```
+pub fn dfa_many(input: &str) -> IResult<&str, Vec<ParsedDfa<'_>>> {
+    dfa_many_with_separator(input, SEPARATOR)
+}
+
+/// Like [dfa_many], but the DFAs are separated by lines containing only `separator` instead of `---`
+pub fn dfa_many_with_separator<'a>(
+    input: &'a str,
+    separator: &str,
+) -> IResult<&'a str, Vec<ParsedDfa<'a>>> {
+    fa::many_automata(input, separator, fa::full_dfa)
+}
+
+/// A [nom] parser for several NFAs, in the same way as [dfa_many]
+pub fn nfa_many(input: &str) -> IResult<&str, Vec<ParsedNfa<'_>>> {
+    nfa_many_with_separator(input, SEPARATOR)
+}
+
+/// Like [nfa_many], but the NFAs are separated by lines containing only `separator` instead of `---`
+pub fn nfa_many_with_separator<'a>(
+    input: &'a str,
+    separator: &str,
+) -> IResult<&'a str, Vec<ParsedNfa<'a>>> {
+    fa::many_automata(input, separator, fa::full_nfa)
+}
+
+/// Parses several DFAs from one string, where the DFAs are separated by lines containing only `---` (and possibly
+/// whitespace), as with [dfa_many]. The whole string must be parsable, otherwise this function errors, giving the
+/// position where parsing failed.
+///
+/// ```
+/// use dandy::dfa::Dfa;
+/// use dandy::parser;
+///
+/// let input = "
+///      a
+/// -> s s
+/// ---
+///        a b
+/// -> * s t s
+///      t t t
+/// ";
+/// let dfas = parser::dfas(input).unwrap();
+/// assert_eq!(dfas.len(), 2);
+/// let dfa: Dfa = dfas.into_iter().nth(1).unwrap().try_into().unwrap();
+/// assert!(dfa.accepts_graphemes("bb"));
+/// ```
+pub fn dfas(input: &str) -> Result<Vec<ParsedDfa<'_>>, ParseError<'_>> {
+    dfas_with_separator(input, SEPARATOR)
+}
+
+/// Like [dfas], but the DFAs are separated by lines containing only `separator` instead of `---`
+pub fn dfas_with_separator<'a>(
+    input: &'a str,
+    separator: &str,
+) -> Result<Vec<ParsedDfa<'a>>, ParseError<'a>> {
+    all_consuming(|i| dfa_many_with_separator(i, separator))(input)
+        .finish()
+        .map(|(_, dfas)| dfas)
+        .map_err(|e| ParseError::from_nom(input, e))
+}
+
+/// Parses several NFAs from one string, in the same way as [dfas]
+pub fn nfas(input: &str) -> Result<Vec<ParsedNfa<'_>>, ParseError<'_>> {
+    nfas_with_separator(input, SEPARATOR)
+}
+
+/// Like [nfas], but the NFAs are separated by lines containing only `separator` instead of `---`
+pub fn nfas_with_separator<'a>(
+    input: &'a str,
+    separator: &str,
+) -> Result<Vec<ParsedNfa<'a>>, ParseError<'a>> {
+    all_consuming(|i| nfa_many_with_separator(i, separator))(input)
+        .finish()
+        .map(|(_, nfas)| nfas)
+        .map_err(|e| ParseError::from_nom(input, e))
+}
+
+/// The largest bound allowed in a bounded repetition such as `r{n,m}` in a regular expression. Since the repetition
+/// is desugared to a sequence of copies of `r`, larger bounds would make the regex huge.
+pub const MAX_REPETITION: usize = 1000;
+
+/// The error given when parsing a regular expression with [regex] or [extended_regex]
+///
+/// ```
+/// use dandy::parser;
+/// use dandy::parser::RegexParseError;
+///
+/// assert_eq!(parser::regex("a{3,1}"), Err(RegexParseError::InvalidRepetition(3, 1)));
+/// assert_eq!(parser::regex("a{1000000000}"), Err(RegexParseError::RepetitionTooLarge(1000000000)));
+/// assert!(matches!(parser::regex("a{3"), Err(RegexParseError::Syntax(_))));
+/// ```
+#[derive(Debug, Error, PartialEq)]
+pub enum RegexParseError<'a> {
+    #[error("The repetition {{{0},{1}}} has a greater minimum than maximum")]
+    InvalidRepetition(usize, usize),
+    #[error("The repetition bound {0} is greater than the maximum of {MAX_REPETITION}")]
+    RepetitionTooLarge(usize),
+    #[error("{0}")]
+    Syntax(Error<&'a str>),
+}
+
+impl<'a> RegexParseError<'a> {
+    fn from_nom(error: Error<&'a str>) -> Self {
+        regex::invalid_repetition(&error).unwrap_or(RegexParseError::Syntax(error))
+    }
 }
 
 /// Parses a regular expression according to the format above. The whole string must be parsable, otherwise this
 /// function errors. All regexes that are successfully parsed by this function is guaranteed to be valid regexes.
-pub fn regex(input: &str) -> Result<Regex, Error<&str>> {
-    all_consuming(regex::full_regex)(input)
+pub fn regex(input: &str) -> Result<Regex, RegexParseError<'_>> {
+    all_consuming(regex::full_regex::<false>)(input)
+        .finish()
+        .map(|(_, regex)| regex)
+        .map_err(RegexParseError::from_nom)
+}
+
+/// Parses an extended regular expression, which may also use intersection `&` and complement `~` (see the format
+/// above). Every regular expression without `&` and `~` is parsed in the same way as by [regex].
+///
+/// ```
+/// use dandy::parser;
+///
+/// // All strings of a's and b's which contain no "aa"
+/// let regex = parser::extended_regex("~((a|b)*aa(a|b)*)").unwrap();
+/// let dfa = regex.to_dfa();
+/// assert!(dfa.accepts_graphemes("abab"));
+/// assert!(!dfa.accepts_graphemes("baab"));
+///
+/// // `&` is just a character in regular expressions which aren't extended
+/// assert!(parser::regex("a&b").unwrap().to_dfa().accepts_graphemes("a&b"));
+/// ```
+pub fn extended_regex(input: &str) -> Result<Regex, RegexParseError<'_>> {
+    all_consuming(regex::full_regex::<true>)(input)
         .finish()
         .map(|(_, regex)| regex)
+        .map_err(RegexParseError::from_nom)
 }
 
-pub fn grammar(input: &str) -> Result<ParsedGrammar, Error<&str>> {
+/// Parses a grammar, written as a `Nonterminals:` line, a `Terminals:` line and a `Start:` line followed by one line
+/// for each production. The whole string must be parsable, otherwise this function errors, giving the position where
+/// parsing failed, for example at a production missing its arrow.
+/// Note that the result is a [ParsedGrammar], which is not guaranteed to be a valid [crate::grammar::Grammar]. Use
+/// [TryInto::try_into] to convert a [ParsedGrammar] to a [crate::grammar::Grammar].
+///
+/// ```
+/// use dandy::parser;
+///
+/// let input = "
+/// Nonterminals: S
+/// Terminals: a b
+/// Start: S
+/// S a S b |
+/// ";
+/// let err = parser::grammar(input).unwrap_err();
+/// assert_eq!(err.to_string(), "error at line 5, column 3: unexpected 'a'");
+/// ```
+pub fn grammar(input: &str) -> Result<ParsedGrammar<'_>, ParseError<'_>> {
     all_consuming(grammar::full_grammar)(input)
         .finish()
         .map(|(_, grammar)| grammar)
+        .map_err(|e| ParseError::from_nom(input, e))
 }
@@ -1,37 +1,78 @@
+use crate::parser::{RegexParseError, MAX_REPETITION};
 use crate::regex::{Regex, RegexChar, RegexTree};
 use nom::branch::alt;
 use nom::character::complete;
-use nom::character::complete::one_of;
-use nom::combinator::{fail, map, opt, value, verify};
+use nom::character::complete::digit1;
+use nom::combinator::{fail, map, map_opt, map_res, opt, value, verify};
+use nom::error::{Error, ErrorKind};
 use nom::multi::{many1, separated_list1};
-use nom::sequence::{delimited, preceded};
+use nom::sequence::{delimited, pair, preceded, separated_pair};
 use nom::{IResult, Parser};
 use std::hint::unreachable_unchecked;
+use std::iter;
 use std::rc::Rc;
 use unicode_segmentation::UnicodeSegmentation;
 
-pub(crate) fn full_regex(input: &str) -> IResult<&str, Regex> {
-    map(expression, |tree| Regex { tree })(input.trim()) //trim instead of delimited since otherwise trailing w.s. can be counted as tokens
+/// Parses a full regular expression. With `EXTENDED`, `&` (intersection) and `~` (complement) are also reserved
+/// characters and operators.
+pub(crate) fn full_regex<const EXTENDED: bool>(input: &str) -> IResult<&str, Regex> {
+    map(expression::<EXTENDED>, |tree| Regex { tree })(input.trim()) //trim instead of delimited since otherwise trailing w.s. can be counted as tokens
 }
 
-fn expression(input: &str) -> IResult<&str, RegexTree> {
-    alternation(input)
+fn expression<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexTree> {
+    alternation::<EXTENDED>(input)
 }
 
-fn alternation(input: &str) -> IResult<&str, RegexTree> {
+fn alternation<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexTree> {
     map(
-        separated_list1(complete::char('|'), sequence),
+        separated_list1(complete::char('|'), intersection::<EXTENDED>),
         wrap_multiple(RegexTree::Alt),
     )(input)
 }
 
-fn sequence(input: &str) -> IResult<&str, RegexTree> {
+/// Intersection binds tighter than alternation but looser than sequencing, so `ab&c|d` is `((ab)&c)|d`
+fn intersection<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexTree> {
+    if !EXTENDED {
+        return sequence::<EXTENDED>(input);
+    }
     map(
-        many1(alt((par_expr, combinated_char))),
+        separated_list1(complete::char('&'), sequence::<EXTENDED>),
+        wrap_multiple(RegexTree::Intersection),
+    )(input)
+}
+
+fn sequence<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexTree> {
+    map(
+        many1(factor::<EXTENDED>),
         wrap_multiple(RegexTree::Sequence),
     )(input)
 }
 
+/// One element of a sequence
+fn factor<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexTree> {
+    if EXTENDED {
+        alt((
+            complement::<EXTENDED>,
+            par_expr::<EXTENDED>,
+            combinated_class,
+            combinated_char::<EXTENDED>,
+        ))(input)
+    } else {
+        alt((
+            par_expr::<EXTENDED>,
+            combinated_class,
+            combinated_char::<EXTENDED>,
+        ))(input)
+    }
+}
+
+/// Complement applies to the following element including its quantifier, so `~a*` is `~(a*)`
+fn complement<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexTree> {
+    map(preceded(complete::char('~'), factor::<EXTENDED>), |tree| {
+        RegexTree::Complement(Box::new(tree))
+    })(input)
+}
+
 fn wrap_multiple<T>(f: impl Fn(Vec<T>) -> T) -> impl Fn(Vec<T>) -> T {
     move |mut items| {
         if items.len() > 1 {
@@ -42,39 +83,174 @@ fn wrap_multiple<T>(f: impl Fn(Vec<T>) -> T) -> impl Fn(Vec<T>) -> T {
     }
 }
 
-fn par_expr(input: &str) -> IResult<&str, RegexTree> {
+fn par_expr<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexTree> {
+    map(
+        delimited(
+            complete::char('('),
+            expression::<EXTENDED>,
+            complete::char(')'),
+        )
+        .and(opt(quantifier)),
+        apply_quantifier,
+    )(input)
+}
+
+fn combinated_char<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexTree> {
     map(
-        delimited(complete::char('('), expression, complete::char(')')).and(opt(one_of("+*"))),
-        apply_kleene,
+        map(regex_char::<EXTENDED>, RegexTree::Char).and(opt(quantifier)),
+        apply_quantifier,
     )(input)
 }
 
-fn combinated_char(input: &str) -> IResult<&str, RegexTree> {
+fn combinated_class(input: &str) -> IResult<&str, RegexTree> {
+    map(char_class.and(opt(quantifier)), apply_quantifier)(input)
+}
+
+/// A character class such as `[abc]` or `[a-z0-9]`, which is desugared to an alternation of its characters
+fn char_class(input: &str) -> IResult<&str, RegexTree> {
     map(
-        map(regex_char, RegexTree::Char).and(opt(one_of("+*"))),
-        apply_kleene,
+        delimited(complete::char('['), many1(class_item), complete::char(']')),
+        |items| wrap_multiple(RegexTree::Alt)(items.concat()),
     )(input)
 }
 
-fn apply_kleene((to_combine, kleene): (RegexTree, Option<char>)) -> RegexTree {
-    match kleene {
-        Some('+') => RegexTree::Sequence(vec![
+fn class_item(input: &str) -> IResult<&str, Vec<RegexTree>> {
+    alt((
+        class_range,
+        map(class_char, |c| {
+            vec![RegexTree::Char(RegexChar::Grapheme(Rc::from(c)))]
+        }),
+    ))(input)
+}
+
+/// A range `a-z` in a character class, including both ends. Both ends must be single characters, and the range must
+/// not be empty (so `z-a` is rejected)
+fn class_range(input: &str) -> IResult<&str, Vec<RegexTree>> {
+    map_opt(
+        separated_pair(class_char, complete::char('-'), class_char),
+        |(from, to)| {
+            let (from, to) = (single_char(from)?, single_char(to)?);
+            (from <= to).then(|| {
+                (from..=to)
+                    .map(|c| RegexTree::Char(RegexChar::Grapheme(Rc::from(c.to_string()))))
+                    .collect()
+            })
+        },
+    )(input)
+}
+
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    chars.next().filter(|_| chars.next().is_none())
+}
+
+fn class_char(input: &str) -> IResult<&str, &str> {
+    alt((
+        preceded(complete::char('\\'), grapheme),
+        verify(grapheme, |g: &str| !["[", "]", "\\", "-"].contains(&g)),
+    ))(input)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quantifier {
+    Plus,
+    Star,
+    /// Between `min` and `max` (inclusive) repetitions, or at least `min` repetitions if there is no `max`
+    Bounded(usize, Option<usize>),
+}
+
+fn quantifier(input: &str) -> IResult<&str, Quantifier> {
+    alt((
+        value(Quantifier::Plus, complete::char('+')),
+        value(Quantifier::Star, complete::char('*')),
+        bounded_repetition,
+    ))(input)
+}
+
+/// Bounded repetition `{n}`, `{n,m}` or `{n,}`, where `n` may not be greater than `m`, and neither may be greater than
+/// [MAX_REPETITION]. Otherwise, this fails (without backtracking) with [ErrorKind::TooLarge] at the `{`, which
+/// [invalid_repetition] recognizes.
+fn bounded_repetition(input: &str) -> IResult<&str, Quantifier> {
+    let (rest, (min, max)) = repetition_bounds(input)?;
+    if repetition_error(min, max).is_some() {
+        return Err(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge)));
+    }
+    Ok((rest, Quantifier::Bounded(min, max)))
+}
+
+/// Parses the bounds `(min, max)` of a bounded repetition, where `max` is `None` if there is no upper bound
+fn repetition_bounds(input: &str) -> IResult<&str, (usize, Option<usize>)> {
+    map(
+        delimited(
+            complete::char('{'),
+            pair(number, opt(preceded(complete::char(','), opt(number)))),
+            complete::char('}'),
+        ),
+        |(min, max)| (min, max.unwrap_or(Some(min))),
+    )(input)
+}
+
+fn repetition_error(min: usize, max: Option<usize>) -> Option<RegexParseError<'static>> {
+    match max {
+        _ if min > MAX_REPETITION => Some(RegexParseError::RepetitionTooLarge(min)),
+        Some(max) if max > MAX_REPETITION => Some(RegexParseError::RepetitionTooLarge(max)),
+        Some(max) if min > max => Some(RegexParseError::InvalidRepetition(min, max)),
+        _ => None,
+    }
+}
+
+/// Gets the error for the repetition that `error` was given for, if it was given by [bounded_repetition] because of
+/// invalid bounds
+pub(crate) fn invalid_repetition(error: &Error<&str>) -> Option<RegexParseError<'static>> {
+    if error.code != ErrorKind::TooLarge {
+        return None;
+    }
+    let (_, (min, max)) = repetition_bounds(error.input).ok()?;
+    repetition_error(min, max)
+}
+
+fn number(input: &str) -> IResult<&str, usize> {
+    map_res(digit1, str::parse)(input)
+}
+
+fn apply_quantifier((to_combine, quantifier): (RegexTree, Option<Quantifier>)) -> RegexTree {
+    match quantifier {
+        Some(Quantifier::Plus) => RegexTree::Sequence(vec![
             to_combine.clone(),
             RegexTree::Repeat(Box::new(to_combine)),
         ]),
-        Some('*') => RegexTree::Repeat(Box::new(to_combine)),
+        Some(Quantifier::Star) => RegexTree::Repeat(Box::new(to_combine)),
+        Some(Quantifier::Bounded(min, max)) => {
+            // r{2,4} is desugared to rr(r|ε)(r|ε), and r{2,} to rrr*
+            let mut seq = vec![to_combine.clone(); min];
+            match max {
+                Some(max) => {
+                    let optional =
+                        RegexTree::Alt(vec![to_combine, RegexTree::Char(RegexChar::Epsilon)]);
+                    seq.extend(iter::repeat_n(optional, max - min));
+                }
+                None => seq.push(RegexTree::Repeat(Box::new(to_combine))),
+            }
+            match seq.len() {
+                0 => RegexTree::Char(RegexChar::Epsilon),
+                1 => seq.remove(0),
+                _ => RegexTree::Sequence(seq),
+            }
+        }
         None => to_combine,
-        _ => unreachable!("Should only be +, * or none"),
     }
 }
 
-fn regex_char(input: &str) -> IResult<&str, RegexChar> {
-    alt((empty_lang, empty_str, escaped_char, normal_char))(input)
+fn regex_char<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexChar> {
+    alt((empty_lang, empty_str, escaped_char, normal_char::<EXTENDED>))(input)
 }
 
-fn normal_char(input: &str) -> IResult<&str, RegexChar> {
+fn normal_char<const EXTENDED: bool>(input: &str) -> IResult<&str, RegexChar> {
     verify(one_cluster, |rxc| match rxc {
-        RegexChar::Grapheme(c) => !is_reserved_char(c.chars().next().unwrap_or_default()),
+        RegexChar::Grapheme(c) => {
+            let c = c.chars().next().unwrap_or_default();
+            !(is_reserved_char(c) || (EXTENDED && is_extended_reserved_char(c)))
+        }
         // Safety: mapped under one_char, it can only yield RegexChar::Char
         _ => unsafe { unreachable_unchecked() },
     })(input)
@@ -86,12 +262,16 @@ fn escaped_char(input: &str) -> IResult<&str, RegexChar> {
 
 /// A parser taking one grapheme cluster from the input stream and returning it as a regex char.
 fn one_cluster(input: &str) -> IResult<&str, RegexChar> {
+    map(grapheme, |g| RegexChar::Grapheme(Rc::from(g)))(input)
+}
+
+/// A parser taking one grapheme cluster from the input stream.
+fn grapheme(input: &str) -> IResult<&str, &str> {
     let mut indices = input.graphemes(true);
     let Some(grapheme) = indices.next() else {
         return fail(input);
     };
-    let regex = RegexChar::Grapheme(Rc::from(grapheme));
-    Ok((&input[grapheme.len()..], regex))
+    Ok((&input[grapheme.len()..], grapheme))
 }
 
 fn empty_str(input: &str) -> IResult<&str, RegexChar> {
@@ -102,5 +282,9 @@ fn empty_lang(input: &str) -> IResult<&str, RegexChar> {
 }
 
 fn is_reserved_char(char: char) -> bool {
-    ['(', ')', '∅', 'ε', '|', '*', '+', '\\'].contains(&char)
+    ['(', ')', '[', ']', '{', '}', '∅', 'ε', '|', '*', '+', '\\'].contains(&char)
+}
+
+fn is_extended_reserved_char(char: char) -> bool {
+    ['&', '~'].contains(&char)
 }
@@ -0,0 +1,238 @@
+//! # Pushdown automata
+//! The PDA module includes the [Pda] struct which represents a nondeterministic
+//! [pushdown automaton](https://en.wikipedia.org/wiki/Pushdown_automaton) accepting by empty stack. Currently, the
+//! only way to create such an instance is by [converting a context-free grammar](crate::grammar::Grammar::to_pda),
+//! which shows that every context-free language is accepted by some PDA.
+//!
+//! ## Example
+//! ```
+//! use dandy::grammar::Grammar;
+//! use dandy::parser;
+//!
+//! // Palindromes of a's and b's
+//! let input = "
+//! Nonterminals: S
+//! Terminals: a b
+//! Start: S
+//! S → a S a | b S b | a | b |
+//! ";
+//! let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+//! let pda = grammar.to_pda();
+//! assert!(pda.accepts(&["a", "b", "b", "a"]));
+//! assert!(pda.accepts(&["b", "a", "b"]));
+//! assert!(!pda.accepts(&["a", "b"]));
+//! ```
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// The maximum depth of the stack symbols searched by [Pda::accepts], unless changed with [Pda::set_max_depth]
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// A nondeterministic pushdown automaton, which accepts a word if some computation reading the whole word empties the
+/// stack. Stack symbols, states and elements of the alphabet are referred to by their indices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pda {
+    pub(crate) alphabet: Rc<[Rc<str>]>,
+    pub(crate) stack_alphabet: Vec<Rc<str>>,
+    pub(crate) states: Vec<Rc<str>>,
+    pub(crate) initial_state: usize,
+    /// The initial contents of the stack, with the top first
+    pub(crate) initial_stack: Vec<usize>,
+    pub(crate) transitions: Vec<PdaTransition>,
+    pub(crate) max_depth: usize,
+}
+
+/// A transition of a [Pda], which may be taken in the state `from` when the top of the stack is `pop`, and the next
+/// element of the input is `input` (or always, if `input` is `None`). Taking it reads the input element, replaces the
+/// top of the stack with `push` (where the first symbol ends up on top) and enters the state `to`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PdaTransition {
+    pub(crate) from: usize,
+    pub(crate) input: Option<usize>,
+    pub(crate) pop: usize,
+    pub(crate) to: usize,
+    pub(crate) push: Vec<usize>,
+}
+
+impl PdaTransition {
+    /// Gets the index of the state this transition is taken from
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    /// Gets the index of the element of the alphabet read by this transition, or `None` for an ε-transition
+    pub fn input(&self) -> Option<usize> {
+        self.input
+    }
+
+    /// Gets the index of the stack symbol popped by this transition
+    pub fn pop(&self) -> usize {
+        self.pop
+    }
+
+    /// Gets the index of the state this transition enters
+    pub fn to(&self) -> usize {
+        self.to
+    }
+
+    /// Gets the indices of the stack symbols pushed by this transition, with the new top of the stack first
+    pub fn push(&self) -> &[usize] {
+        &self.push
+    }
+}
+
+/// A way of popping a stack symbol, as `(symbol, from, to)` where `from` and `to` are the states before and after
+type Pop = (usize, usize, usize);
+
+impl Pda {
+    /// Checks if this PDA accepts the given word, that is, if some computation reads the whole word and ends with an
+    /// empty stack. A PDA may grow its stack forever without reading any input, so only computations where the stack
+    /// symbols are nested at most [Pda::max_depth] deep are searched. The symbols of the initial stack have depth 1,
+    /// and the symbols pushed by a transition are one deeper than the symbol it pops (for a PDA from a grammar, this is
+    /// the height of the derivation tree). A word is therefore rejected if every computation accepting it goes deeper,
+    /// see [Pda::set_max_depth].
+    ///
+    /// For each depth, subword, stack symbol and state `p`, this computes the states `q` such that the PDA can go from
+    /// `p` to `q` reading exactly the subword while popping the stack symbol (and not touching the stack below it).
+    /// This takes time polynomial in the length of the word and the depth bound, and stops early if going deeper
+    /// doesn't give anything new.
+    ///
+    /// ```
+    /// use dandy::grammar::Grammar;
+    /// use dandy::parser;
+    ///
+    /// let input = "
+    /// Nonterminals: S
+    /// Terminals: ( )
+    /// Start: S
+    /// S → ( S ) S |
+    /// ";
+    /// let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    /// let mut pda = grammar.to_pda();
+    /// assert!(pda.accepts(&["(", "(", ")", ")", "(", ")"]));
+    /// assert!(pda.accepts(&[]));
+    /// assert!(!pda.accepts(&["(", ")", ")"]));
+    ///
+    /// // The innermost S of "(())" has depth 3
+    /// pda.set_max_depth(2);
+    /// assert!(pda.accepts(&["(", ")"]));
+    /// assert!(!pda.accepts(&["(", "(", ")", ")"]));
+    /// pda.set_max_depth(3);
+    /// assert!(pda.accepts(&["(", "(", ")", ")"]));
+    /// ```
+    pub fn accepts(&self, word: &[&str]) -> bool {
+        let Some(word) = word
+            .iter()
+            .map(|s| self.alphabet.iter().position(|elem| elem as &str == *s))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+
+        let n = word.len();
+        // popped[i][j] has (symbol, p, q) if the PDA can go from p to q reading word[i..j] while popping symbol, without
+        // going deeper than the number of rounds done so far
+        let mut popped: Vec<Vec<HashSet<Pop>>> = vec![vec![HashSet::new(); n + 1]; n + 1];
+        for _ in 0..self.max_depth {
+            let mut deeper = popped.clone();
+            for (start, row) in deeper.iter_mut().enumerate() {
+                for (end, reached) in row.iter_mut().enumerate().skip(start) {
+                    for transition in &self.transitions {
+                        let after_input = match transition.input {
+                            None => start,
+                            Some(input) if start < end && word[start] == input => start + 1,
+                            Some(_) => continue,
+                        };
+                        let ends = Self::pop_all(
+                            &transition.push,
+                            transition.to,
+                            after_input,
+                            end,
+                            &popped,
+                        );
+                        reached.extend(
+                            ends.into_iter()
+                                .map(|to| (transition.pop, transition.from, to)),
+                        );
+                    }
+                }
+            }
+            if deeper == popped {
+                break;
+            }
+            popped = deeper;
+        }
+
+        !Self::pop_all(&self.initial_stack, self.initial_state, 0, n, &popped).is_empty()
+    }
+
+    /// Computes the states the PDA can be in after popping all the given stack symbols (in order) starting in
+    /// `state`, while reading exactly `word[start..end]`, using the results in `popped` found so far
+    fn pop_all(
+        symbols: &[usize],
+        state: usize,
+        start: usize,
+        end: usize,
+        popped: &[Vec<HashSet<Pop>>],
+    ) -> HashSet<usize> {
+        // reach[pos - start] is the set of states the PDA can be in after reading word[start..pos]
+        let mut reach = vec![HashSet::new(); end + 1 - start];
+        reach[0].insert(state);
+        for &symbol in symbols {
+            let mut next = vec![HashSet::new(); end + 1 - start];
+            for (from_pos, states) in reach.iter().enumerate() {
+                for to_pos in from_pos..=end - start {
+                    next[to_pos].extend(
+                        popped[start + from_pos][start + to_pos]
+                            .iter()
+                            .filter(|&&(s, p, _)| s == symbol && states.contains(&p))
+                            .map(|&(_, _, q)| q),
+                    );
+                }
+            }
+            reach = next;
+        }
+        reach.pop().unwrap()
+    }
+
+    /// Gets the maximum depth of the stack symbols in the computations searched by [Pda::accepts]
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Sets the maximum depth of the stack symbols in the computations searched by [Pda::accepts]. A larger bound lets
+    /// more words be accepted, but makes the search slower. It is [DEFAULT_MAX_DEPTH] for a new PDA.
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Gets the input alphabet of this PDA
+    pub fn alphabet(&self) -> &[Rc<str>] {
+        &self.alphabet
+    }
+
+    /// Gets the stack alphabet of this PDA
+    pub fn stack_alphabet(&self) -> &[Rc<str>] {
+        &self.stack_alphabet
+    }
+
+    /// Gets the names of the states of this PDA
+    pub fn states(&self) -> &[Rc<str>] {
+        &self.states
+    }
+
+    /// Gets the index of the initial state of this PDA
+    pub fn initial_state_index(&self) -> usize {
+        self.initial_state
+    }
+
+    /// Gets the initial contents of the stack of this PDA, with the top first
+    pub fn initial_stack(&self) -> &[usize] {
+        &self.initial_stack
+    }
+
+    /// Gets the transitions of this PDA
+    pub fn transitions(&self) -> &[PdaTransition] {
+        &self.transitions
+    }
+}
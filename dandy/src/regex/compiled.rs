@@ -0,0 +1,80 @@
+use crate::dfa::Dfa;
+use std::collections::HashMap;
+use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A regular expression compiled to a minimized DFA, for matching many strings against the same regular expression
+/// without converting it again for each string. See [Regex::compile](crate::regex::Regex::compile).
+///
+/// The alphabet is the set of symbols appearing in the regular expression, and strings containing any other symbol
+/// are never matched.
+#[derive(Clone, Debug)]
+pub struct CompiledRegex {
+    pub(crate) dfa: Dfa,
+    /// The index of each element of the alphabet
+    pub(crate) symbols: HashMap<Rc<str>, usize>,
+    /// The transitions of all states, where the transition from state `s` upon seeing the element with index `e` is
+    /// found at `s * alphabet_len + e`
+    pub(crate) table: Vec<usize>,
+    pub(crate) accepting: Vec<bool>,
+    /// A state from which no string is accepted, if there is one, so that matching can stop early
+    pub(crate) dead: Option<usize>,
+}
+
+impl CompiledRegex {
+    pub(crate) fn new(dfa: Dfa) -> Self {
+        let symbols = dfa
+            .alphabet
+            .iter()
+            .enumerate()
+            .map(|(idx, elem)| (elem.clone(), idx))
+            .collect();
+        let table = dfa
+            .states
+            .iter()
+            .flat_map(|state| state.transitions.iter().copied())
+            .collect();
+        let accepting = dfa.states.iter().map(|state| state.accepting).collect();
+        // The DFA is minimized, so all dead states have been merged into (at most) one
+        let dead = dfa.dead_state_idx().into_iter().next();
+        Self {
+            dfa,
+            symbols,
+            table,
+            accepting,
+            dead,
+        }
+    }
+
+    /// Checks if the whole input is matched by the regular expression, where the input is split into grapheme
+    /// clusters in the same way as the regular expression is when parsed
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// let regex = parser::regex("(ab|c)*").unwrap().compile();
+    /// assert!(regex.is_match("abcab"));
+    /// assert!(regex.is_match(""));
+    /// assert!(!regex.is_match("aba"));
+    /// assert!(!regex.is_match("abd")); // 'd' isn't in the regular expression
+    /// ```
+    pub fn is_match(&self, input: &str) -> bool {
+        let alphabet_len = self.dfa.alphabet.len();
+        let mut state = self.dfa.initial_state;
+        for grapheme in input.graphemes(true) {
+            let Some(&elem) = self.symbols.get(grapheme) else {
+                return false;
+            };
+            state = self.table[state * alphabet_len + elem];
+            if Some(state) == self.dead {
+                return false;
+            }
+        }
+        self.accepting[state]
+    }
+
+    /// Gets the minimized DFA which this regular expression was compiled to
+    pub fn dfa(&self) -> &Dfa {
+        &self.dfa
+    }
+}
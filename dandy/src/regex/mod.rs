@@ -5,20 +5,45 @@
 //! ## Syntax
 //! Regular expressions are written in a UTF-8 encoded file. Each unicode extended grapheme clusters is considered
 //! one character (but no normalization is used). Sequencing is done by concatenating characters. There are
-//! eight reserved characters: `(`, `)`, `∅`, `ε`, `|`, `*`, `+` and `\`. These needs to be escaped with a backslash
-//! (`\`), while all other characters are supported. Parenthesis `(`,`)` is used for grouping, `∅` denotes the empty
-//! language, `ε` denotes the empty string, `|` denotes alternation, and `*`/`+` is Kleene star/plus (zero or more/one
-//! or more). Initial and trailing whitespace is ignored, but all whitespace within the expression is significant.
+//! twelve reserved characters: `(`, `)`, `[`, `]`, `{`, `}`, `∅`, `ε`, `|`, `*`, `+` and `\`. These needs to be
+//! escaped with a backslash (`\`), while all other characters are supported. Parenthesis `(`,`)` is used for
+//! grouping, `∅` denotes the empty language, `ε` denotes the empty string, `|` denotes alternation, and `*`/`+` is
+//! Kleene star/plus (zero or more/one or more). Initial and trailing whitespace is ignored, but all whitespace within
+//! the expression is significant.
+//!
+//! Bounded repetition is written with braces: `r{n}` matches exactly `n` repetitions of `r`, `r{n,m}` matches between
+//! `n` and `m` repetitions (where `n` may not be greater than `m`) and `r{n,}` matches at least `n` repetitions. These
+//! are desugared while parsing, so `a{2,3}` is the same as `aa(a|ε)` and `a{2,}` is the same as `aaa*`.
+//!
+//! Brackets `[`,`]` denote a character class, which matches any one of the characters in it. A character class may
+//! contain single characters such as `[abc]` and ranges of characters such as `[a-z]` (including both ends), or a
+//! mix of them such as `[a-cxy]`. A character class is simply shorthand for an alternation, so `[a-cx]` is the same
+//! as `(a|b|c|x)`. Within a character class, `[`, `]`, `-` and `\` needs to be escaped with a backslash.
+//!
+//! Extended regular expressions, parsed with [extended_regex](crate::parser::extended_regex), also support
+//! intersection `r&s` and complement `~r`, where `&` and `~` are reserved characters. The complement is taken with
+//! respect to the symbols appearing in the whole expression, so `~(ab)` matches all strings of `a`s and `b`s except
+//! `ab`. In regular expressions which aren't extended, `&` and `~` are ordinary characters.
 //!
 //! Here are some examples:
 //! * `(ab)+` matches `ab`, `abab`, `ababab`, ...
 //! * `(ab)*` matches `(empty string)`, `ab`, `abab`, `ababab`, ...
+//! * `(ab){2,}` matches `abab`, `ababab`, ...
+//! * `[a-c]+` matches `a`, `b`, `c`, `aa`, `ab`, ...
 //! * `0*1(0+ε)` matches `1`, `10`, `0001` and all other strings containing the character `1` once
 //!
 //! ## Operations
-//! The only operation currently implemented is converting a Regular Expression to a NFA. From there, you can do lots
-//! of stuff, like optimizing it, encoding it to a table, enumerate all words in it, convert it to a DFA to take the
-//! symmetric difference to another regex or automata etc.
+//! The main operation is converting a Regular Expression to a NFA. From there, you can do lots of stuff, like
+//! optimizing it, encoding it to a table, enumerate all words in it, convert it to a DFA to take the symmetric
+//! difference to another regex or automata etc.
+//!
+//! Regular expressions also support [Brzozowski derivatives](Regex::derivative), and checking if they are
+//! [nullable](Regex::nullable) (match the empty string). Together, these can be used to match a string directly
+//! against a regular expression, by taking the derivative for each symbol of the string and checking if the final
+//! expression is nullable. To match many strings against the same regular expression, [compile](Regex::compile) it
+//! once instead.
+//!
+//! The [star height](Regex::star_height), the maximum nesting depth of Kleene stars, can be computed as well.
 //!
 //! Here are some example usages of the regexes above:
 //! ```
@@ -51,31 +76,77 @@
 //! assert_eq!(words.next(), Some("10".to_string()));
 //! ```
 
+use crate::dfa::{Dfa, DfaState};
 use crate::nfa::{Nfa, NfaState};
+pub use compiled::CompiledRegex;
 use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::iter;
 use std::rc::Rc;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+pub mod compiled;
+
+/// A regular expression, represented by its syntax tree. The tree is public, so one may inspect or transform it
+/// directly (see [Regex::as_tree] and [RegexTree]), and any tree is a valid regular expression.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Regex {
     pub tree: RegexTree,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The syntax tree of a regular expression. Note that `r+` is represented as the sequence of `r` and `r*`, and that
+/// character classes are represented as alternations, since that is how they are desugared by the
+/// [parser](crate::parser::regex).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum RegexTree {
+    /// Concatenation of the subtrees, in order. The empty sequence matches the empty string
     Sequence(Vec<RegexTree>),
+    /// Alternation (union) between the subtrees. The empty alternation matches nothing
     Alt(Vec<RegexTree>),
+    /// Kleene star, zero or more repetitions of the subtree
     Repeat(Box<RegexTree>),
+    /// Intersection between the subtrees, as parsed by the [extended parser](crate::parser::extended_regex). The empty
+    /// intersection matches every string over the alphabet
+    Intersection(Vec<RegexTree>),
+    /// Complement of the subtree with respect to the symbols appearing in the whole regular expression, as parsed by
+    /// the [extended parser](crate::parser::extended_regex)
+    Complement(Box<RegexTree>),
+    /// A single symbol, the empty string or the empty language
     Char(RegexChar),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The leaves of a [RegexTree]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum RegexChar {
+    /// A literal symbol, which is one grapheme cluster when parsed
     Grapheme(Rc<str>),
+    /// The empty string, `ε`
     Epsilon,
+    /// The empty language, `∅`
     Empty,
 }
 
+/// How tightly the context of a subtree binds, used to only add the parentheses that are needed when writing a regex
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    /// The top level, or an element of an alternation
+    Alt,
+    /// An element of an intersection
+    Intersection,
+    /// An element of a sequence
+    Sequence,
+    /// The subtree of a complement
+    Complement,
+    /// The subtree of a Kleene star
+    Repeat,
+}
+
+impl From<RegexTree> for Regex {
+    fn from(tree: RegexTree) -> Self {
+        Regex { tree }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct StateCounter {
     state: usize,
@@ -98,9 +169,191 @@ impl StateCounter {
 }
 
 impl Regex {
-    /// Converts this regular expression to a NFA. This is the only operation available to regular expressions.
-    /// To check if a string is accepted by this regular expression, one should convert it to a NFA and then check
-    /// using that NFA. Note that the resulting NFA may be quite large, so converting it to a DFA may optimize it.
+    /// Gets the syntax tree of this regular expression
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::regex::{RegexChar, RegexTree};
+    ///
+    /// let regex = parser::regex("a*").unwrap();
+    /// let a = RegexTree::Char(RegexChar::Grapheme("a".into()));
+    /// assert_eq!(regex.as_tree(), &RegexTree::Repeat(Box::new(a)));
+    /// ```
+    pub fn as_tree(&self) -> &RegexTree {
+        &self.tree
+    }
+
+    /// Converts this regular expression into its syntax tree
+    pub fn into_tree(self) -> RegexTree {
+        self.tree
+    }
+
+    /// Computes the [Brzozowski derivative](https://en.wikipedia.org/wiki/Brzozowski_derivative) of this regular
+    /// expression with respect to `symbol`, which is a regular expression matching exactly those strings `w` where
+    /// this regular expression matches `symbol` followed by `w`. The resulting expression is simplified (for example
+    /// `∅r = ∅`, `εr = r`, `∅|r = r` and `r|r = r`) so that repeatedly taking derivatives doesn't make the expression
+    /// grow unboundedly.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// let regex = parser::regex("a(b|c)*").unwrap();
+    /// let derivative = regex.derivative("a");
+    /// assert_eq!(derivative, parser::regex("(b|c)*").unwrap());
+    /// assert!(derivative.nullable());
+    /// // Nothing is accepted after seeing a 'b' first
+    /// assert_eq!(regex.derivative("b"), parser::regex("∅").unwrap());
+    /// ```
+    pub fn derivative(&self, symbol: &str) -> Regex {
+        Regex {
+            tree: self.tree.derivative(symbol),
+        }
+    }
+
+    /// Checks if this regular expression is nullable, that is, if it matches the empty string. This is computed
+    /// directly from the syntax tree: `ε` and Kleene stars are nullable, sequences and intersections are nullable if
+    /// all of their elements are, alternations are nullable if any of their elements are, and complements are nullable
+    /// if their subtree isn't.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// assert!(parser::regex("a*").unwrap().nullable());
+    /// assert!(!parser::regex("a+").unwrap().nullable());
+    /// assert!(parser::regex("(a|ε)b*").unwrap().nullable());
+    /// assert!(!parser::regex("∅*a").unwrap().nullable());
+    /// ```
+    pub fn nullable(&self) -> bool {
+        self.tree.nullable()
+    }
+
+    /// Computes the star height of this regular expression, which is the maximum nesting depth of Kleene stars in it.
+    /// Note that `r+` is desugared to `rr*` and thus has the same star height as `r*`.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// assert_eq!(parser::regex("ab|c").unwrap().star_height(), 0);
+    /// assert_eq!(parser::regex("a*b*").unwrap().star_height(), 1);
+    /// assert_eq!(parser::regex("(a*b)*").unwrap().star_height(), 2);
+    /// ```
+    pub fn star_height(&self) -> usize {
+        self.tree.star_height()
+    }
+
+    /// Converts this regular expression to a DFA using the derivative automaton: each state corresponds to a
+    /// (simplified) [derivative](Regex::derivative) of this regular expression, and the transition from a state upon
+    /// seeing a symbol goes to the derivative with respect to that symbol. A state is accepting if its derivative is
+    /// [nullable](Regex::nullable). The resulting DFA is usually close to minimal, but isn't guaranteed to be minimal.
+    ///
+    /// The alphabet of the DFA is exactly the symbols appearing in this regular expression, in order of their first
+    /// appearance (which means that the alphabet is empty for regexes such as `∅` or `ε`). The states are named
+    /// sequentially from 0, where 0 is the initial state. Note that the table format needs at least one element in
+    /// the alphabet, so the [table](Dfa::to_table) of a DFA with an empty alphabet can't be parsed again.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// let regex = parser::regex("(ab)*").unwrap();
+    /// let dfa = regex.to_dfa();
+    /// assert_eq!(dfa.states().len(), 3); // (ab)*, b(ab)* and ∅
+    /// assert!(dfa.accepts_graphemes("abab"));
+    /// assert!(!dfa.accepts_graphemes("aba"));
+    /// ```
+    pub fn to_dfa(&self) -> Dfa {
+        let alphabet = {
+            let mut alphabet: Vec<Rc<str>> = vec![];
+            self.tree.collect_graphemes(&mut alphabet);
+            alphabet
+        };
+        Self::derivative_dfa(&self.tree, alphabet)
+    }
+
+    /// Constructs the derivative automaton of the tree over the given alphabet, see [Regex::to_dfa]
+    fn derivative_dfa(tree: &RegexTree, alphabet: Vec<Rc<str>>) -> Dfa {
+        let mut state_idx = HashMap::from([(tree.clone(), 0)]);
+        let mut trees = vec![tree.clone()];
+        let mut states = vec![];
+
+        // States are explored in order of their indices, so the next state to explore is always states.len()
+        while states.len() < trees.len() {
+            let tree = trees[states.len()].clone();
+            let transitions = alphabet
+                .iter()
+                .map(|elem| {
+                    let derivative = tree.derivative(elem);
+                    let next_idx = state_idx.len();
+                    *state_idx
+                        .entry(derivative)
+                        .or_insert_with_key(|derivative| {
+                            trees.push(derivative.clone());
+                            next_idx
+                        })
+                })
+                .collect::<Vec<_>>();
+            let idx = states.len();
+            states.push(DfaState {
+                name: Rc::from(idx.to_string()),
+                initial: idx == 0,
+                accepting: trees[idx].nullable(),
+                transitions,
+            });
+        }
+
+        Dfa {
+            alphabet: alphabet.into(),
+            states,
+            initial_state: 0,
+        }
+    }
+
+    /// Compiles this regular expression for matching strings against it, by converting it to a [DFA](Regex::to_dfa)
+    /// and minimizing it once, so that many strings can be matched efficiently with [CompiledRegex::is_match]. The
+    /// alphabet is the symbols appearing in this regular expression.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// let regex = parser::regex("[0-9]+(.[0-9]+|ε)").unwrap().compile();
+    /// let matches = ["3.14", "42", "1.", ".5", "x"]
+    ///     .into_iter()
+    ///     .filter(|s| regex.is_match(s))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(matches, vec!["3.14", "42"]);
+    /// ```
+    pub fn compile(&self) -> CompiledRegex {
+        let mut dfa = self.to_dfa();
+        dfa.minimize();
+        CompiledRegex::new(dfa)
+    }
+
+    /// Checks if this regular expression is equivalent to another regular expression, that is, if they match the same
+    /// language. The two regular expressions don't need to contain the same symbols: the comparison is done over the
+    /// union of the symbols of both regular expressions. This is done by converting both to DFAs (see
+    /// [Regex::to_dfa]) and checking if those are equivalent.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// let regex1 = parser::regex("(a|b)*").unwrap();
+    /// let regex2 = parser::regex("(a*b*)*").unwrap();
+    /// assert!(regex1.equivalent_to(&regex2));
+    ///
+    /// let regex3 = parser::regex("(a|c)*").unwrap();
+    /// assert!(!regex1.equivalent_to(&regex3));
+    /// ```
+    pub fn equivalent_to(&self, other: &Regex) -> bool {
+        let mut dfa1 = self.to_dfa();
+        let mut dfa2 = other.to_dfa();
+        let alphabet1 = dfa1.alphabet().to_vec();
+        dfa1.extend_alphabet(dfa2.alphabet());
+        dfa2.extend_alphabet(&alphabet1);
+        dfa1.equivalent_to(&dfa2)
+    }
+
+    /// Converts this regular expression to a NFA. To check if a string is accepted by this regular expression, one
+    /// could convert it to a NFA and then check using that NFA. Note that the resulting NFA may be quite large, so
+    /// converting it to a DFA may optimize it. See also [Regex::to_dfa], which constructs a DFA directly.
     pub fn to_nfa(self) -> Nfa {
         // Final accepting state is 0
         // Initial state is 1
@@ -128,8 +381,13 @@ impl Regex {
             transitions: vec![],
         };
 
+        // Intersections and complements are constructed over all symbols in the regular expression
+        let mut alphabet = vec![];
+        self.tree.collect_graphemes(&mut alphabet);
+
         let states = {
-            let mut tree_states = Self::tree_to_nfa(self.tree, &mut counter, &mut grapheme_idx, 0);
+            let mut tree_states =
+                Self::tree_to_nfa(self.tree, &alphabet, &mut counter, &mut grapheme_idx, 0);
             let mut all_states = Vec::with_capacity(tree_states.len() + 2);
             all_states.push(accepting_state); // state 0
             all_states.push(initial_state); // state 1
@@ -154,37 +412,62 @@ impl Regex {
         }
     }
 
-    /// *This is subject to change*
-    pub fn to_string(&self) -> String {
-        let mut acc = String::new();
-        Self::build_string(&self.tree, &mut acc);
-        acc
-    }
-
-    fn build_string(tree: &RegexTree, acc: &mut String) {
+    /// Writes the tree to `acc`, adding parentheses only where they are needed to keep the structure of the tree in
+    /// the given context. Sequences, alternations and intersections of one element are written as their element, an
+    /// empty sequence as `ε`, an empty alternation as `∅` and an empty intersection as `~∅`.
+    fn build_string(tree: &RegexTree, acc: &mut String, prec: Precedence) {
         match tree {
+            RegexTree::Sequence(seq) if seq.is_empty() => acc.push('ε'),
+            RegexTree::Alt(alt) if alt.is_empty() => acc.push('∅'),
+            RegexTree::Intersection(inter) if inter.is_empty() => acc.push_str("~∅"),
+            RegexTree::Sequence(items) | RegexTree::Alt(items) | RegexTree::Intersection(items)
+                if items.len() == 1 =>
+            {
+                Self::build_string(&items[0], acc, prec)
+            }
             RegexTree::Sequence(seq) => {
-                for item in seq {
-                    Self::build_string(item, acc);
-                }
+                Self::parenthesized(acc, prec > Precedence::Sequence, |acc| {
+                    for item in seq {
+                        Self::build_string(item, acc, Precedence::Sequence);
+                    }
+                });
             }
-            RegexTree::Alt(seq) => {
-                acc.push('(');
-                let mut iter = seq.iter();
-                if let Some(first) = iter.next() {
-                    Self::build_string(first, acc);
-                    for item in iter {
-                        acc.push('|');
-                        Self::build_string(item, acc);
+            RegexTree::Alt(alt) => {
+                Self::parenthesized(acc, prec > Precedence::Alt, |acc| {
+                    let mut iter = alt.iter();
+                    if let Some(first) = iter.next() {
+                        Self::build_string(first, acc, Precedence::Alt);
+                        for item in iter {
+                            acc.push('|');
+                            Self::build_string(item, acc, Precedence::Alt);
+                        }
                     }
-                }
-                acc.push(')');
+                });
+            }
+            RegexTree::Intersection(inter) => {
+                Self::parenthesized(acc, prec > Precedence::Intersection, |acc| {
+                    let mut iter = inter.iter();
+                    if let Some(first) = iter.next() {
+                        Self::build_string(first, acc, Precedence::Intersection);
+                        for item in iter {
+                            acc.push('&');
+                            Self::build_string(item, acc, Precedence::Intersection);
+                        }
+                    }
+                });
             }
-            RegexTree::Repeat(seq) => {
-                acc.push('(');
-                Self::build_string(seq, acc);
-                acc.push(')');
-                acc.push('*');
+            // A quantifier can't directly follow another one, so (r*)* keeps its parentheses
+            RegexTree::Repeat(inner) => {
+                Self::parenthesized(acc, prec > Precedence::Complement, |acc| {
+                    Self::build_string(inner, acc, Precedence::Repeat);
+                    acc.push('*');
+                });
+            }
+            RegexTree::Complement(inner) => {
+                Self::parenthesized(acc, prec > Precedence::Complement, |acc| {
+                    acc.push('~');
+                    Self::build_string(inner, acc, Precedence::Complement);
+                });
             }
             RegexTree::Char(c) => match c {
                 RegexChar::Epsilon => {
@@ -195,8 +478,10 @@ impl Regex {
                 }
                 RegexChar::Grapheme(g) => {
                     if g.len() == 1
-                        && ['(', ')', '∅', 'ε', '|', '*', '+', '\\']
-                            .contains(&g.chars().next().unwrap())
+                        && [
+                            '(', ')', '[', ']', '{', '}', '∅', 'ε', '|', '*', '+', '\\', '&', '~',
+                        ]
+                        .contains(&g.chars().next().unwrap())
                     {
                         acc.push('\\');
                         acc.push_str(g);
@@ -208,12 +493,23 @@ impl Regex {
         }
     }
 
+    fn parenthesized(acc: &mut String, parenthesize: bool, write: impl FnOnce(&mut String)) {
+        if parenthesize {
+            acc.push('(');
+        }
+        write(acc);
+        if parenthesize {
+            acc.push(')');
+        }
+    }
+
     /// We turn a tree to a NFA recursively. `counter` is used to get the number of the next state.
     /// `char_idx` gives the index of a given character in the alphabet (and inserts the character
     /// if it didn't exist already). `send_to` is the state that the subtree should transition to
-    /// if successful.
+    /// if successful. `alphabet` is all symbols in the whole regular expression.
     fn tree_to_nfa(
         tree: RegexTree,
+        alphabet: &[Rc<str>],
         counter: &mut StateCounter,
         grapheme_idx: &mut impl FnMut(Rc<str>) -> usize,
         send_to: usize,
@@ -247,8 +543,13 @@ impl Regex {
                                 epsilon_transitions: vec![],
                                 transitions: vec![],
                             };
-                            let new_states =
-                                Self::tree_to_nfa(subtree, counter, grapheme_idx, after_state_idx);
+                            let new_states = Self::tree_to_nfa(
+                                subtree,
+                                alphabet,
+                                counter,
+                                grapheme_idx,
+                                after_state_idx,
+                            );
                             if idx + 1 == seq_len {
                                 after_state.epsilon_transitions.push(send_to);
                             } else {
@@ -267,7 +568,7 @@ impl Regex {
                     .into_iter()
                     .flat_map(|tree| {
                         incoming_state.epsilon_transitions.push(counter.peek());
-                        Self::tree_to_nfa(tree, counter, grapheme_idx, send_to)
+                        Self::tree_to_nfa(tree, alphabet, counter, grapheme_idx, send_to)
                     })
                     .collect::<Vec<_>>();
                 let mut ret = Vec::with_capacity(1 + additional.len());
@@ -278,7 +579,7 @@ impl Regex {
             RegexTree::Repeat(r) => {
                 incoming_state.epsilon_transitions = vec![counter.peek(), send_to];
                 let mut additional =
-                    Self::tree_to_nfa(*r, counter, grapheme_idx, incoming_state_idx);
+                    Self::tree_to_nfa(*r, alphabet, counter, grapheme_idx, incoming_state_idx);
                 let mut ret = Vec::with_capacity(additional.len() + 1);
                 ret.push(incoming_state);
                 ret.append(&mut additional);
@@ -305,6 +606,201 @@ impl Regex {
                     vec![incoming_state]
                 }
             },
+            tree @ (RegexTree::Intersection(_) | RegexTree::Complement(_)) => {
+                // These have no direct NFA construction, so the subtree is converted to a DFA whose states are added
+                let dfa = Self::derivative_dfa(&tree, alphabet.to_vec());
+                incoming_state
+                    .epsilon_transitions
+                    .push(counter.peek() + dfa.initial_state);
+                let offset = counter.peek();
+                let mut ret = Vec::with_capacity(dfa.states.len() + 1);
+                ret.push(incoming_state);
+                for state in dfa.states {
+                    let mut transitions = vec![];
+                    for (elem, target) in dfa.alphabet.iter().zip(state.transitions) {
+                        let cidx = grapheme_idx(elem.clone());
+                        if transitions.len() <= cidx {
+                            transitions.resize(cidx + 1, vec![]);
+                        }
+                        transitions[cidx].push(offset + target);
+                    }
+                    ret.push(NfaState {
+                        name: Rc::from(counter.next().to_string()),
+                        initial: false,
+                        accepting: false,
+                        epsilon_transitions: if state.accepting {
+                            vec![send_to]
+                        } else {
+                            vec![]
+                        },
+                        transitions,
+                    });
+                }
+                ret
+            }
+        }
+    }
+}
+
+impl RegexTree {
+    pub(crate) const EMPTY: RegexTree = RegexTree::Char(RegexChar::Empty);
+    pub(crate) const EPSILON: RegexTree = RegexTree::Char(RegexChar::Epsilon);
+
+    fn derivative(&self, symbol: &str) -> RegexTree {
+        match self {
+            RegexTree::Char(RegexChar::Grapheme(g)) if g.as_ref() == symbol => Self::EPSILON,
+            RegexTree::Char(_) => Self::EMPTY,
+            RegexTree::Alt(alts) => {
+                Self::alt(alts.iter().map(|tree| tree.derivative(symbol)).collect())
+            }
+            RegexTree::Repeat(tree) => Self::seq(vec![tree.derivative(symbol), self.clone()]),
+            RegexTree::Intersection(trees) => {
+                Self::inter(trees.iter().map(|tree| tree.derivative(symbol)).collect())
+            }
+            RegexTree::Complement(tree) => Self::complement(tree.derivative(symbol)),
+            RegexTree::Sequence(seq) => {
+                // d(r1 r2 ... rn) = d(r1) r2 ... rn | d(r2 ... rn) if r1 is nullable, and so on
+                let mut alts = vec![];
+                for (idx, tree) in seq.iter().enumerate() {
+                    let mut derived = vec![tree.derivative(symbol)];
+                    derived.extend(seq[idx + 1..].iter().cloned());
+                    alts.push(Self::seq(derived));
+                    if !tree.nullable() {
+                        break;
+                    }
+                }
+                Self::alt(alts)
+            }
+        }
+    }
+
+    /// Pushes all graphemes in this tree to `acc` which aren't already in it, in order of their first appearance
+    fn collect_graphemes(&self, acc: &mut Vec<Rc<str>>) {
+        match self {
+            RegexTree::Sequence(trees) | RegexTree::Alt(trees) | RegexTree::Intersection(trees) => {
+                trees.iter().for_each(|tree| tree.collect_graphemes(acc))
+            }
+            RegexTree::Repeat(tree) | RegexTree::Complement(tree) => tree.collect_graphemes(acc),
+            RegexTree::Char(RegexChar::Grapheme(g)) => {
+                if !acc.contains(g) {
+                    acc.push(g.clone());
+                }
+            }
+            RegexTree::Char(_) => {}
+        }
+    }
+
+    fn nullable(&self) -> bool {
+        match self {
+            RegexTree::Sequence(seq) => seq.iter().all(RegexTree::nullable),
+            RegexTree::Alt(alts) => alts.iter().any(RegexTree::nullable),
+            RegexTree::Repeat(_) => true,
+            RegexTree::Intersection(trees) => trees.iter().all(RegexTree::nullable),
+            RegexTree::Complement(tree) => !tree.nullable(),
+            RegexTree::Char(c) => *c == RegexChar::Epsilon,
+        }
+    }
+
+    fn star_height(&self) -> usize {
+        match self {
+            RegexTree::Sequence(trees) | RegexTree::Alt(trees) | RegexTree::Intersection(trees) => {
+                trees.iter().map(RegexTree::star_height).max().unwrap_or(0)
+            }
+            RegexTree::Repeat(tree) => tree.star_height() + 1,
+            RegexTree::Complement(tree) => tree.star_height(),
+            RegexTree::Char(_) => 0,
         }
     }
+
+    /// Creates a simplified sequence of the trees: nested sequences are flattened, ε is removed and if any of the
+    /// trees is ∅, the sequence is ∅
+    pub(crate) fn seq(trees: Vec<RegexTree>) -> RegexTree {
+        let mut flattened = Vec::with_capacity(trees.len());
+        for tree in trees {
+            match tree {
+                RegexTree::Sequence(seq) => flattened.extend(seq),
+                RegexTree::Char(RegexChar::Epsilon) => {}
+                RegexTree::Char(RegexChar::Empty) => return Self::EMPTY,
+                tree => flattened.push(tree),
+            }
+        }
+        match flattened.len() {
+            0 => Self::EPSILON,
+            1 => flattened.remove(0),
+            _ => RegexTree::Sequence(flattened),
+        }
+    }
+
+    /// Creates a simplified alternation of the trees: nested alternations are flattened, ∅ is removed and the
+    /// alternatives are sorted and deduplicated
+    pub(crate) fn alt(trees: Vec<RegexTree>) -> RegexTree {
+        let mut flattened = Vec::with_capacity(trees.len());
+        for tree in trees {
+            match tree {
+                RegexTree::Alt(alts) => flattened.extend(alts),
+                RegexTree::Char(RegexChar::Empty) => {}
+                tree => flattened.push(tree),
+            }
+        }
+        flattened.sort();
+        flattened.dedup();
+        match flattened.len() {
+            0 => Self::EMPTY,
+            1 => flattened.remove(0),
+            _ => RegexTree::Alt(flattened),
+        }
+    }
+
+    /// Creates a simplified intersection of the trees: nested intersections are flattened, `~∅` (matching everything)
+    /// is removed, if any of the trees is ∅ the intersection is ∅, and the trees are sorted and deduplicated
+    pub(crate) fn inter(trees: Vec<RegexTree>) -> RegexTree {
+        let universal = Self::complement(Self::EMPTY);
+        let mut flattened = Vec::with_capacity(trees.len());
+        for tree in trees {
+            match tree {
+                RegexTree::Intersection(trees) => flattened.extend(trees),
+                RegexTree::Char(RegexChar::Empty) => return Self::EMPTY,
+                tree if tree == universal => {}
+                tree => flattened.push(tree),
+            }
+        }
+        flattened.sort();
+        flattened.dedup();
+        match flattened.len() {
+            0 => universal,
+            1 => flattened.remove(0),
+            _ => RegexTree::Intersection(flattened),
+        }
+    }
+
+    /// Creates a simplified complement of the tree, where `~~r` is `r`
+    pub(crate) fn complement(tree: RegexTree) -> RegexTree {
+        match tree {
+            RegexTree::Complement(tree) => *tree,
+            tree => RegexTree::Complement(Box::new(tree)),
+        }
+    }
+
+    /// Creates a simplified Kleene star of the tree: `∅*` and `ε*` are `ε`, and `(r*)*` is `r*`
+    pub(crate) fn star(tree: RegexTree) -> RegexTree {
+        match tree {
+            RegexTree::Char(RegexChar::Empty | RegexChar::Epsilon) => Self::EPSILON,
+            tree @ RegexTree::Repeat(_) => tree,
+            tree => RegexTree::Repeat(Box::new(tree)),
+        }
+    }
+}
+
+/// Writes the regex in the syntax accepted by the [parser](crate::parser::regex), with parentheses only where they
+/// are needed, so that it can be parsed back to an equivalent regex. Regexes containing intersections or complements
+/// are written in the syntax of the [extended parser](crate::parser::extended_regex). `&` and `~` are always escaped,
+/// so that the result means the same for both parsers.
+///
+/// *This is subject to change*
+impl Display for Regex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut acc = String::new();
+        Self::build_string(&self.tree, &mut acc, Precedence::Alt);
+        f.write_str(&acc)
+    }
 }
@@ -1,5 +1,6 @@
 use std::cmp::max;
 use std::iter;
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Default, Debug, Clone)]
 pub struct Table<'a> {
@@ -48,4 +49,32 @@ impl<'a> Table<'a> {
             .collect::<Vec<_>>()
             .join("\n")
     }
+
+    /// Like [Table::to_string], but measures the widths of the cells in graphemes rather than characters (so that
+    /// cells with combining characters line up as well), and trims trailing whitespace from every line
+    pub fn to_aligned_string(&self, sep: &str) -> String {
+        let graphemes = |s: &str| s.graphemes(true).count();
+        let mut widths = vec![0; self.row_len.len()];
+        for row in &self.rows {
+            widths
+                .iter_mut()
+                .zip(row)
+                .for_each(|(width, s)| *width = max(*width, graphemes(s)));
+        }
+        self.rows
+            .iter()
+            .map(|row| {
+                let line = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(s, &width)| {
+                        let padding = width.saturating_sub(graphemes(s));
+                        format!("{s}{}{sep}", " ".repeat(padding))
+                    })
+                    .collect::<String>();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 }
@@ -1,5 +1,7 @@
-use crate::dfa::{Dfa, DfaState};
+use crate::dfa::{AlphabetMismatch, Dfa, DfaParseError, DfaState, RetainError};
+use crate::grammar::{Grammar, GrammarOwned, ParseNode, Production, ProductionOwned};
 use crate::nfa::{Nfa, NfaState};
+use crate::regex::{Regex, RegexChar, RegexTree};
 use crate::*;
 use ::regex::Regex as LibRegex;
 use proptest::prelude::*;
@@ -7,6 +9,7 @@ use rand::prelude::*;
 use std::collections::HashSet;
 use std::ops::RangeInclusive;
 use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
 
 struct MultipleCounterIter {
     state: Vec<usize>,
@@ -84,6 +87,71 @@ proptest! {
         assert_eq!(dfa, parsed_dfa);
     }
 
+    /// Tests that randomly generated automata are valid, by reparsing their tables, and that
+    /// the same seed gives the same automaton
+    #[test]
+    fn random_automata(seed: u64, num_states in 1usize..20, density in 0.0..=1.0) {
+        let alphabet = ["a", "b", "c", "a"];
+        let dfa = Dfa::random(&mut StdRng::seed_from_u64(seed), num_states, &alphabet);
+        assert_eq!(dfa.states().len(), num_states);
+        assert_eq!(dfa.alphabet().len(), 3);
+        let parsed_dfa: Dfa = parser::dfa(&dfa.to_table()).unwrap().try_into().unwrap();
+        assert_eq!(dfa, parsed_dfa);
+        assert_eq!(dfa, Dfa::random(&mut StdRng::seed_from_u64(seed), num_states, &alphabet));
+
+        let nfa = Nfa::random(&mut StdRng::seed_from_u64(seed), num_states, &alphabet, density, true);
+        let parsed_nfa: Nfa = parser::nfa(&nfa.to_table()).unwrap().try_into().unwrap();
+        assert_eq!(nfa, parsed_nfa);
+        let nfa = Nfa::random(&mut StdRng::seed_from_u64(seed), num_states, &alphabet, density, false);
+        assert!(!nfa.has_epsilon_moves());
+    }
+
+    /// Tests that determinizing a random NFA and minimizing a random DFA keeps the language
+    #[test]
+    fn random_automata_convert(dfa in seeded_dfa(20, 5), nfa in seeded_nfa(10, 5)) {
+        let mut minimized = dfa.clone();
+        minimized.minimize();
+        assert!(minimized.equivalent_to(&dfa));
+        assert!(minimized.states().len() <= dfa.states().len());
+
+        assert!(nfa.to_dfa().to_nfa().equivalent_to(&nfa));
+    }
+
+    /// Tests that displaying an automaton gives the same output as its table
+    #[test]
+    fn display_is_table(dfa in dfa(10, 10), nfa in nfa(10, 10)) {
+        assert_eq!(dfa.to_string(), dfa.to_table());
+        assert_eq!(nfa.to_string(), nfa.to_table());
+    }
+
+    /// Tests that the aligned tables of automata can be parsed to the *very same* automata again
+    #[test]
+    fn table_aligned_reparse(dfa in dfa(20, 20), nfa in nfa(20, 20)) {
+        let parsed_dfa: Dfa = parser::dfa(&dfa.to_table_aligned()).unwrap().try_into().unwrap();
+        assert_eq!(dfa, parsed_dfa);
+        let parsed_nfa: Nfa = parser::nfa(&nfa.to_table_aligned()).unwrap().try_into().unwrap();
+        assert_eq!(nfa.to_table(), parsed_nfa.to_table());
+    }
+
+    /// Tests that the columns of dfa.to_table() line up, with each transition starting
+    /// in the same column as its alphabet element
+    #[test]
+    fn dfa_table_aligned(dfa in dfa(20, 20)) {
+        let table = dfa.to_table();
+        let column_starts = |line: &str| {
+            let chars = line.chars().collect::<Vec<_>>();
+            (0..chars.len())
+                .filter(|&i| !chars[i].is_whitespace() && (i == 0 || chars[i - 1].is_whitespace()))
+                .collect::<Vec<_>>()
+        };
+        let mut lines = table.lines();
+        let header = column_starts(lines.next().unwrap());
+        for line in lines {
+            let starts = column_starts(line);
+            assert_eq!(&starts[starts.len() - header.len()..], &header[..]);
+        }
+    }
+
     /// Tests that a DFA can be minimized and is then still equivalent to the original DFA
     #[test]
     fn dfa_minimize_eq(dfa in dfa(25, 25)) { // This size is adequate, larger size takes too long time
@@ -104,10 +172,15 @@ proptest! {
 
 
     /// Tests that a NFA can be turned into a table with dfa.to_table() and then be
-    /// parsed to the *very same* DFA again (not just equivalent)
+    /// parsed to the *very same* DFA again (not just equivalent, but with the targets
+    /// of each transition sorted)
     #[test]
-    fn nfa_table_reparse(nfa in nfa(50, 50)) {
+    fn nfa_table_reparse(mut nfa in nfa(50, 50)) {
         let parsed_nfa: Nfa = parser::nfa(&nfa.to_table()).unwrap().try_into().unwrap();
+        for state in nfa.states.iter_mut() {
+            state.epsilon_transitions.sort_unstable();
+            state.transitions.iter_mut().for_each(|trans| trans.sort_unstable());
+        }
         assert_eq!(nfa, parsed_nfa);
     }
 
@@ -120,6 +193,393 @@ proptest! {
         assert!(converted.equivalent_to(&nfa), "NFA->DFA->NFA should be equivalent to NFA");
     }
 
+    /// Tests that accepting from an iterator agrees with stepping an evaluator, also when the
+    /// string contains elements outside the alphabet (g)
+    #[test]
+    fn accepts_iter(
+        dfa in fixed_alphabet_dfa(20, 'a'..='f', ('a'..='f').count()),
+        nfa in fixed_alphabet_nfa(10, 'a'..='f', ('a'..='f').count()),
+        tests in prop::collection::vec("[a-g]*", 50)
+    ) {
+        for test in tests.iter() {
+            let elems = test.chars().map(String::from).collect::<Vec<_>>();
+            let slice = elems.iter().map(String::as_str).collect::<Vec<_>>();
+            let mut dfa_eval = dfa.evaluator();
+            dfa_eval.step_multiple(&slice);
+            assert_eq!(dfa.accepts_iter(test.chars().map(String::from)), dfa_eval.is_accepting());
+            let mut nfa_eval = nfa.evaluator();
+            nfa_eval.step_multiple(&slice);
+            assert_eq!(nfa.accepts_iter(&elems), nfa_eval.is_accepting());
+        }
+    }
+
+    /// Tests that accepting with a reused scratch agrees with accepting without one, also
+    /// when the scratch was last used with a larger NFA
+    #[test]
+    fn nfa_accepts_with(
+        nfa in fixed_alphabet_nfa(10, 'a'..='f', ('a'..='f').count()),
+        larger in fixed_alphabet_nfa(30, 'a'..='f', ('a'..='f').count()),
+        tests in prop::collection::vec("[a-g]*", 50)
+    ) {
+        let mut scratch = nfa::NfaScratch::new();
+        for test in tests.iter() {
+            let elems = test.chars().map(String::from).collect::<Vec<_>>();
+            assert_eq!(larger.accepts_with(&elems, &mut scratch), larger.accepts_iter(&elems));
+            assert_eq!(nfa.accepts_with(&elems, &mut scratch), nfa.accepts_iter(&elems));
+        }
+    }
+
+    /// Tests that the canonical residual automaton of a NFA is equivalent to the NFA, and
+    /// not larger than the minimal DFA (except for the initial state)
+    #[test]
+    fn nfa_residual_automaton(nfa in nfa(10, 5)) {
+        let residual = nfa.to_residual_automaton();
+        assert!(residual.equivalent_to(&nfa));
+        assert!(!residual.has_epsilon_moves());
+        let mut dfa = nfa.to_dfa();
+        dfa.minimize();
+        assert!(residual.states().len() <= dfa.states().len() + 1);
+    }
+
+    /// Tests that the partition into non-distinguishable states is consistent, is a
+    /// congruence, and consists of singletons only for a minimized DFA
+    #[test]
+    fn dfa_state_partition(dfa in dfa(20, 5)) {
+        let partition = dfa.state_equivalence_classes_idx();
+        let mut seen = partition.classes().concat();
+        seen.sort();
+        assert_eq!(seen, (0..dfa.states().len()).collect::<Vec<_>>());
+        for (idx, class) in partition.classes().iter().enumerate() {
+            assert_eq!(partition.representative(idx), class[0]);
+            for &state in class {
+                assert_eq!(partition.class_of(state), idx);
+                let (first, other) = (&dfa.states()[class[0]], &dfa.states()[state]);
+                assert_eq!(first.is_accepting(), other.is_accepting());
+                for (&t1, &t2) in first.transitions().iter().zip(other.transitions()) {
+                    assert_eq!(partition.class_of(t1), partition.class_of(t2));
+                }
+            }
+        }
+
+        let mut minimized = dfa.clone();
+        minimized.minimize();
+        let partition = minimized.state_equivalence_classes_idx();
+        assert_eq!(partition.len(), minimized.states().len());
+    }
+
+    /// Tests that two states have a distinguishing word exactly when they are in different
+    /// classes, and that the word distinguishes them
+    #[test]
+    fn dfa_distinguishing_word(dfa in dfa(15, 5)) {
+        let partition = dfa.state_equivalence_classes_idx();
+        let run = |state: usize, word: &[Rc<str>]| {
+            word.iter().fold(state, |state, elem| {
+                let idx = dfa.alphabet().iter().position(|e| e == elem).unwrap();
+                dfa.states()[state].transitions()[idx]
+            })
+        };
+        for s1 in 0..dfa.states().len() {
+            for s2 in 0..dfa.states().len() {
+                match dfa.distinguishing_word(s1, s2) {
+                    None => assert_eq!(partition.class_of(s1), partition.class_of(s2)),
+                    Some(word) => {
+                        assert_ne!(partition.class_of(s1), partition.class_of(s2));
+                        let (end1, end2) = (run(s1, &word), run(s2, &word));
+                        assert_ne!(dfa.states()[end1].is_accepting(), dfa.states()[end2].is_accepting());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Tests that the reachable states are iterated in breadth-first order, without
+    /// repetitions, and that they are exactly the reachable states
+    #[test]
+    fn reachable_states_iter(dfa in dfa(20, 5), nfa in nfa(20, 5)) {
+        let order = dfa.reachable_states_iter().collect::<Vec<_>>();
+        assert_eq!(order.iter().copied().collect::<HashSet<_>>(), dfa.reachable_state_idx());
+        assert_eq!(order.len(), dfa.reachable_state_idx().len());
+        assert_eq!(order[0], dfa.initial_state_index());
+        // In breadth-first order, the distances from the initial state never decrease
+        let mut distance = vec![usize::MAX; dfa.states().len()];
+        distance[order[0]] = 0;
+        for &state in &order {
+            for &next in dfa.states()[state].transitions() {
+                distance[next] = distance[next].min(distance[state] + 1);
+            }
+        }
+        assert!(order.windows(2).all(|w| distance[w[0]] <= distance[w[1]]));
+
+        let order = nfa.reachable_states_iter().collect::<Vec<_>>();
+        assert_eq!(order.iter().copied().collect::<HashSet<_>>(), nfa.reachable_state_idx());
+        assert_eq!(order.len(), nfa.reachable_state_idx().len());
+        assert_eq!(order[0], nfa.initial_state_index());
+    }
+
+    /// Tests that removing dead states keeps the language, and leaves at most one dead
+    /// state in a DFA and none but the initial state in a NFA
+    #[test]
+    fn remove_dead_states(dfa in dfa(20, 5), nfa in nfa(20, 5)) {
+        let mut trimmed = dfa.clone();
+        trimmed.remove_dead_states();
+        assert!(trimmed.equivalent_to(&dfa));
+        assert!(trimmed.dead_state_idx().len() <= 1);
+
+        let mut trimmed = nfa.clone();
+        trimmed.remove_dead_states();
+        assert!(trimmed.equivalent_to(&nfa));
+        let dead = trimmed.dead_state_idx();
+        assert!(dead.iter().all(|&idx| idx == trimmed.initial_state_index()));
+    }
+
+    /// Tests that the parallel product construction is deterministic, and equivalent to
+    /// and as large as the serial one
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_product_construction(
+        dfa1 in fixed_alphabet_dfa(20, 'a'..='c', 3),
+        dfa2 in fixed_alphabet_dfa(20, 'a'..='c', 3)
+    ) {
+        let xor = |s1: bool, s2: bool| s1 != s2;
+        let Ok(serial) = dfa1.product_construction(&dfa2, xor) else {
+            assert!(dfa1.par_product_construction(&dfa2, xor).is_err());
+            return Ok(());
+        };
+        let parallel = dfa1.par_product_construction(&dfa2, xor).unwrap();
+        assert!(parallel.equivalent_to(&serial));
+        assert_eq!(parallel.states().len(), serial.states().len());
+        assert_eq!(parallel, dfa1.par_product_construction(&dfa2, xor).unwrap());
+    }
+
+    /// Tests that a word is accepted by the prefix closure exactly when some extension of
+    /// it is accepted by the original DFA (extensions longer than the number of states
+    /// aren't needed)
+    #[test]
+    fn dfa_prefix_closure(
+        dfa in fixed_alphabet_dfa(6, 'a'..='b', 2),
+        words in prop::collection::vec("[ab]{0,6}", 20)
+    ) {
+        let closure = dfa.prefix_closure();
+        let extensions = (0..=6)
+            .flat_map(|len| (0..1 << len).map(move |bits| {
+                (0..len).map(|i| if bits >> i & 1 == 0 { 'a' } else { 'b' }).collect::<String>()
+            }))
+            .collect::<Vec<_>>();
+        for word in words {
+            let extendable = extensions
+                .iter()
+                .any(|ext| dfa.accepts_graphemes(&format!("{word}{ext}")));
+            assert_eq!(closure.accepts_graphemes(&word), extendable);
+        }
+    }
+
+    /// Tests that uniformly sampled strings have the right length and are accepted, and
+    /// that there are none exactly when no string of the length is accepted
+    #[test]
+    fn dfa_sample_uniform(dfa in fixed_alphabet_dfa(10, 'a'..='c', 3), len in 0usize..6, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let any_accepted = MultipleCounterIter::new(len, 2)
+            .filter(|word| word.len() == len)
+            .any(|word| {
+                let word = word.iter().map(|&c| ["a", "b", "c"][c]).collect::<Vec<_>>();
+                dfa.accepts(&word)
+            });
+        match dfa.sample_uniform(len, &mut rng) {
+            None => assert!(!any_accepted),
+            Some(word) => {
+                assert_eq!(word.len(), len);
+                assert!(dfa.accepts_iter(&word));
+            }
+        }
+    }
+
+    /// Tests that a DFA with n states has a finite language exactly when it accepts no string with a length between
+    /// n and 2n (exclusive)
+    #[test]
+    fn dfa_is_finite(dfa in fixed_alphabet_dfa(6, 'a'..='b', 2), seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let n = dfa.states().len();
+        let infinite = (n..2 * n).any(|len| dfa.sample_uniform(len, &mut rng).is_some());
+        assert_eq!(dfa.is_finite(), !infinite);
+
+        let mut inverted = dfa.clone();
+        inverted.invert();
+        let co_infinite = (n..2 * n).any(|len| inverted.sample_uniform(len, &mut rng).is_some());
+        assert_eq!(dfa.is_cofinite(), !co_infinite);
+    }
+
+    #[test]
+    fn dfa_nerode_index(dfa in dfa(20, 5)) {
+        let mut minimized = dfa.clone();
+        minimized.minimize();
+        assert_eq!(dfa.nerode_index(), minimized.states().len());
+    }
+
+    /// Tests that a DFA is isomorphic to itself with its states shuffled (with the shuffle as the
+    /// isomorphism if all states are reachable), and isomorphic to its minimization exactly when its
+    /// reachable part is already minimal
+    #[test]
+    fn dfa_is_isomorphic_to(dfa in dfa(20, 5), seed: u64) {
+        let mut order = (0..dfa.states().len()).collect::<Vec<_>>();
+        order.shuffle(&mut StdRng::seed_from_u64(seed));
+        // order[new] = old, so position[old] = new
+        let mut position = vec![0; order.len()];
+        order.iter().enumerate().for_each(|(new, &old)| position[old] = new);
+        let shuffled = Dfa {
+            alphabet: dfa.alphabet.clone(),
+            states: order
+                .iter()
+                .map(|&old| {
+                    let mut state = dfa.states[old].clone();
+                    state.transitions.iter_mut().for_each(|t| *t = position[*t]);
+                    state
+                })
+                .collect(),
+            initial_state: position[dfa.initial_state],
+        };
+        assert!(dfa.is_isomorphic_to(&shuffled));
+        assert!(shuffled.is_isomorphic_to(&dfa));
+        let all_reachable = dfa.reachable_state_idx().len() == dfa.states().len();
+        assert_eq!(dfa.isomorphism(&shuffled), all_reachable.then(|| position.clone()));
+        assert_eq!(shuffled.isomorphism(&dfa), all_reachable.then(|| order.clone()));
+
+        let mut minimized = dfa.clone();
+        minimized.minimize();
+        let minimal = dfa.reachable_state_idx().len() == minimized.states().len();
+        assert_eq!(dfa.is_isomorphic_to(&minimized), minimal);
+        assert_eq!(minimized.is_isomorphic_to(&dfa), minimal);
+    }
+
+    /// Tests that canonicalizing keeps the structure, and that equivalent DFAs become
+    /// identical when minimized and canonicalized
+    #[test]
+    fn dfa_canonicalize(dfa in dfa(20, 5)) {
+        let mut canonical = dfa.clone();
+        canonical.canonicalize();
+        assert!(canonical.is_isomorphic_to(&dfa));
+        assert_eq!(canonical.states().len(), dfa.states().len());
+
+        let mut first = dfa.clone();
+        first.minimize();
+        first.canonicalize();
+        let mut second = dfa.to_nfa().to_dfa();
+        second.minimize();
+        second.canonicalize();
+        assert_eq!(first, second);
+    }
+
+    /// Tests that the regular expression given by state elimination accepts the same words as
+    /// the NFA, including words with symbols which don't appear in the expression
+    #[test]
+    fn nfa_to_regex(nfa in nfa(6, 3)) {
+        let regex_nfa = nfa.to_regex().to_nfa();
+        for word in MultipleCounterIter::new(4, nfa.alphabet().len() - 1) {
+            let word = word.iter().map(|&c| &nfa.alphabet()[c] as &str).collect::<Vec<_>>();
+            assert_eq!(regex_nfa.accepts(&word), nfa.accepts(&word), "{word:?}");
+        }
+    }
+
+    /// Tests that the regular expression of a DFA over single characters is equivalent to it
+    #[test]
+    fn dfa_to_regex(dfa in fixed_alphabet_dfa(10, 'a'..='c', 3)) {
+        let regex = dfa.to_regex();
+        let reparsed = parser::regex(&regex.to_string()).unwrap();
+        assert_eq!(reparsed, regex);
+        let regex_dfa = regex.to_dfa();
+        let words = MultipleCounterIter::new(5, 2)
+            .map(|word| word.iter().map(|&c| ["a", "b", "c"][c]).collect::<Vec<_>>());
+        for word in words {
+            assert_eq!(regex_dfa.accepts(&word), dfa.accepts(&word), "{word:?}");
+        }
+    }
+
+    /// Tests that a state is accepting in the right quotient exactly when the language
+    /// from that state intersected with the language of the divisor is nonempty
+    #[test]
+    fn dfa_right_quotient(
+        dfa in fixed_alphabet_dfa(15, 'a'..='c', 3),
+        divisor in fixed_alphabet_dfa(15, 'a'..='c', 3)
+    ) {
+        let quotient = dfa.right_quotient(&divisor).unwrap();
+        for (idx, state) in quotient.states().iter().enumerate() {
+            let mut from_state = dfa.clone();
+            from_state.states[from_state.initial_state].initial = false;
+            from_state.states[idx].initial = true;
+            from_state.initial_state = idx;
+            let nonempty = from_state
+                .intersection(&divisor)
+                .unwrap()
+                .has_reachable_accepting_state();
+            assert_eq!(state.is_accepting(), nonempty);
+        }
+    }
+
+    /// Tests that the petgraph node indices are the state indices, with one edge per
+    /// transition target and ε-move
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn to_petgraph(dfa in dfa(20, 5), nfa in nfa(20, 5)) {
+        use crate::graph::EdgeLabel;
+        use petgraph::graph::NodeIndex;
+        use petgraph::visit::EdgeRef;
+
+        let graph = dfa.to_petgraph();
+        assert_eq!(graph.node_count(), dfa.states().len());
+        assert_eq!(graph.edge_count(), dfa.states().len() * dfa.alphabet().len());
+        for (idx, state) in dfa.states().iter().enumerate() {
+            let label = &graph[NodeIndex::new(idx)];
+            assert_eq!(&*label.name, state.name());
+            assert_eq!(label.initial, state.is_initial());
+            assert_eq!(label.accepting, state.is_accepting());
+        }
+
+        let graph = nfa.to_petgraph();
+        assert_eq!(graph.node_count(), nfa.states().len());
+        for (idx, state) in nfa.states().iter().enumerate() {
+            let mut edges = graph
+                .edges(NodeIndex::new(idx))
+                .map(|edge| (edge.weight().clone(), edge.target().index()))
+                .collect::<Vec<_>>();
+            let mut expected = state
+                .epsilon_transitions()
+                .iter()
+                .map(|&to| (EdgeLabel::Epsilon, to))
+                .chain(state.transitions().iter().zip(nfa.alphabet().iter()).flat_map(
+                    |(targets, elem)| targets.iter().map(|&to| (EdgeLabel::Element(elem.clone()), to)),
+                ))
+                .collect::<Vec<_>>();
+            edges.sort_by_key(|(label, to)| (format!("{label:?}"), *to));
+            expected.sort_by_key(|(label, to)| (format!("{label:?}"), *to));
+            assert_eq!(edges, expected);
+        }
+    }
+
+    /// Tests that every element of the transition monoid is induced by its word, and that
+    /// the multiplication table agrees with concatenating the words
+    #[test]
+    fn dfa_transition_monoid(dfa in dfa(5, 3)) {
+        let Some(monoid) = dfa.transition_monoid(200) else {
+            return Ok(());
+        };
+        let induced = |word: &[Rc<str>]| {
+            (0..dfa.states().len())
+                .map(|state| word.iter().fold(state, |state, elem| {
+                    let idx = dfa.alphabet().iter().position(|e| e == elem).unwrap();
+                    dfa.states()[state].transitions()[idx]
+                }))
+                .collect::<Vec<_>>()
+        };
+        assert!(monoid.elements()[0].word().is_empty());
+        for (i, first) in monoid.elements().iter().enumerate() {
+            assert_eq!(first.function(), induced(first.word()));
+            for (j, second) in monoid.elements().iter().enumerate() {
+                let word = [first.word(), second.word()].concat();
+                let product = &monoid.elements()[monoid.multiply(i, j)];
+                assert_eq!(product.function(), induced(&word));
+            }
+        }
+    }
+
     #[test]
     fn dfa_binary_ops(
         dfa1 in fixed_alphabet_dfa(20, 'a'..='f', ('a'..='f').count()),
@@ -157,6 +617,105 @@ proptest! {
         }
     }
 
+    /// Tests that the Levenshtein automaton of a word accepts exactly the strings within the
+    /// edit distance, computed by dynamic programming
+    #[test]
+    fn nfa_levenshtein(
+        word in "[a-c]{0,5}",
+        max_edits in 0..3usize,
+        tests in prop::collection::vec("[a-d]{0,7}", 30)
+    ) {
+        let word = word.graphemes(true).collect::<Vec<_>>();
+        let nfa = Nfa::levenshtein(&word, max_edits, &["a", "b", "c", "d"]);
+        assert!(nfa.accepts(&word));
+        for test in tests.iter() {
+            let test = test.graphemes(true).collect::<Vec<_>>();
+            let mut distances = (0..=word.len()).collect::<Vec<_>>();
+            for (i, elem) in test.iter().enumerate() {
+                let mut next = vec![i + 1];
+                for (j, expected) in word.iter().enumerate() {
+                    let substitution = distances[j] + usize::from(elem != expected);
+                    next.push(substitution.min(distances[j + 1] + 1).min(next[j] + 1));
+                }
+                distances = next;
+            }
+            assert_eq!(nfa.accepts(&test), distances[word.len()] <= max_edits);
+        }
+    }
+
+    /// Tests that checking equivalence with antichains agrees with checking it by determinizing
+    #[test]
+    fn nfa_equivalent_to_antichain(nfa in nfa(25, 25), flipped in any::<prop::sample::Index>()) {
+        let converted = nfa.to_dfa().to_nfa();
+        assert!(nfa.equivalent_to_antichain(&converted));
+        assert!(converted.equivalent_to_antichain(&nfa));
+
+        let mut modified = nfa.clone();
+        let state = flipped.index(modified.states.len());
+        modified.states[state].accepting = !modified.states[state].accepting;
+        assert_eq!(nfa.equivalent_to_antichain(&modified), nfa.equivalent_to(&modified));
+        assert_eq!(modified.equivalent_to_antichain(&nfa), modified.equivalent_to(&nfa));
+    }
+
+    /// Tests the antichain inclusion check against checking if the difference of the
+    /// determinized NFAs is empty
+    #[test]
+    fn nfa_is_subset_of(
+        nfa1 in fixed_alphabet_nfa(8, 'a'..='c', ('a'..='c').count()),
+        nfa2 in fixed_alphabet_nfa(8, 'a'..='c', ('a'..='c').count()),
+    ) {
+        let difference = nfa1.to_dfa().difference(&nfa2.to_dfa()).unwrap();
+        assert_eq!(nfa1.is_subset_of(&nfa2), Ok(!difference.has_reachable_accepting_state()));
+        assert_eq!(nfa1.is_subset_of(&nfa1), Ok(true));
+        let union = nfa1.clone().union(nfa2.clone()).unwrap();
+        assert_eq!(nfa2.is_subset_of(&union), Ok(true));
+    }
+
+    #[test]
+    fn dfa_binary_ops_extend(
+        dfa1 in fixed_alphabet_dfa(20, 'a'..='c', ('a'..='c').count()),
+        dfa2 in fixed_alphabet_dfa(20, 'b'..='d', ('b'..='d').count()),
+        tests in prop::collection::vec("[a-d]+", 100)
+    ) {
+        let intersection = dfa1.intersection_extend(&dfa2);
+        let union = dfa1.union_extend(&dfa2);
+        assert_eq!(intersection.alphabet().len(), 4);
+        assert_eq!(union.alphabet().len(), 4);
+        for test in tests.iter() {
+            let r1 = dfa1.accepts_graphemes(test);
+            let r2 = dfa2.accepts_graphemes(test);
+            assert_eq!(intersection.accepts_graphemes(test), r1 && r2);
+            assert_eq!(union.accepts_graphemes(test), r1 || r2);
+        }
+    }
+
+    #[test]
+    fn dfa_product_all(
+        dfas in prop::collection::vec(fixed_alphabet_dfa(6, 'a'..='c', ('a'..='c').count()), 1..5),
+        tests in prop::collection::vec("[a-c]*", 100)
+    ) {
+        let intersection = Dfa::intersection_all(&dfas).unwrap();
+        let union = Dfa::union_all(&dfas).unwrap();
+        for test in tests.iter() {
+            let results = dfas.iter().map(|dfa| dfa.accepts_graphemes(test)).collect::<Vec<_>>();
+            assert_eq!(intersection.accepts_graphemes(test), results.iter().all(|&r| r));
+            assert_eq!(union.accepts_graphemes(test), results.iter().any(|&r| r));
+        }
+    }
+
+    #[test]
+    fn dfa_equivalence_witness(
+        dfa1 in fixed_alphabet_dfa(20, 'a'..='c', ('a'..='c').count()),
+        dfa2 in fixed_alphabet_dfa(20, 'a'..='c', ('a'..='c').count()),
+    ) {
+        let witness = dfa1.equivalence_witness(&dfa2).unwrap();
+        assert_eq!(witness.is_none(), dfa1.equivalent_to(&dfa2));
+        if let Some(word) = witness {
+            let word = word.iter().map(|s| s.as_ref()).collect::<Vec<_>>();
+            assert_ne!(dfa1.accepts(&word), dfa2.accepts(&word));
+        }
+    }
+
     #[test]
     fn dfa_self_union(dfa in fixed_alphabet_dfa(20, 'a'..='z', ('a'..='z').count())) {
         let union = dfa.union(&dfa).unwrap();
@@ -212,6 +771,7 @@ proptest! {
     }
 
     #[test]
+    #[allow(clippy::while_let_on_iterator, clippy::unnecessary_map_or)]
     fn nfa_words(
         dfa in fixed_alphabet_dfa(25, 'a'..='f', ('a'..='f').count())
     ) {
@@ -280,92 +840,594 @@ proptest! {
     }
 
     #[test]
-    fn regex_parse(regex_str in random_regex("[a-zε∅]")) {
-        let parse1 = parser::regex(&regex_str).unwrap();
-        let stringified = parse1.to_string();
-        let parse2 = parser::regex(&stringified).unwrap();
-        assert!(parse1.to_nfa().equivalent_to(&parse2.to_nfa()));
+    fn regex_derivative(
+        regex_str in random_regex("[a-dε∅]"),
+        tests in prop::collection::vec("[a-d]*", 20)
+    ) {
+        let regex = parser::regex(&regex_str).unwrap();
+        let nfa = regex.clone().to_nfa();
+        tests.iter().for_each(|test| {
+            let derived = test
+                .chars()
+                .fold(regex.clone(), |regex, c| regex.derivative(&c.to_string()));
+            // Characters which are not in the regex are not in the NFA alphabet, which rejects them as well
+            assert_eq!(derived.nullable(), nfa.accepts_graphemes(test));
+        })
     }
-}
-
-prop_compose! {
-    fn fixed_alphabet_nfa(max_states: usize, alphabet: RangeInclusive<char>, alphabet_size: usize)
-        (num_states in 1..max_states)
-        (
-            states in state_names(num_states),
-            initial_state in 0..num_states,
-            accepting_states in prop::collection::vec(any::<bool>(), num_states..=num_states),
-            transitions in prop::collection::vec(nfa_transitions(num_states, alphabet_size), num_states..=num_states),
-            epsilon_transitions in prop::collection::vec(epsilon_transitions(num_states), num_states..=num_states),
-        )
-    -> Nfa {
-        let states = states.into_iter().zip(
-            accepting_states.into_iter().zip(
-                transitions.into_iter().zip(
-                    epsilon_transitions.into_iter()
-                )
-            )
-        ).enumerate().map(|(idx, (state_name, (accepting, (transitions, epsilon_transitions))))|
-            NfaState {
-                name: Rc::from(state_name.as_str()),
-                initial: idx == initial_state,
-                accepting,
-                transitions,
-                epsilon_transitions
-            }
-        ).collect();
 
-        let mut alphabet: Vec<Rc<str>> = alphabet.clone().map(|c| Rc::from(c.to_string())).collect();
-        alphabet.shuffle(&mut thread_rng());
-        let alphabet = Rc::from(alphabet);
+    #[test]
+    fn regex_to_dfa(regex_str in random_regex("[a-dε∅]")) {
+        let regex = parser::regex(&regex_str).unwrap();
+        let dfa = regex.to_dfa();
+        let mut minimized = regex.clone().to_nfa().to_dfa();
+        minimized.minimize();
+        assert!(dfa.equivalent_to(&minimized));
+        assert!(dfa.states().len() >= minimized.states().len());
+    }
 
-        Nfa {
-            alphabet,
-            states,
-            initial_state
-        }
+    #[test]
+    fn regex_compile(
+        regex_str in random_regex("[a-dε∅]"),
+        tests in prop::collection::vec("[a-e]*", 20)
+    ) {
+        let regex = parser::regex(&regex_str).unwrap();
+        let compiled = regex.compile();
+        let nfa = regex.to_nfa();
+        tests.iter().for_each(|test| {
+            // 'e' never appears in the regex, so strings containing it are never matched
+            assert_eq!(compiled.is_match(test), nfa.accepts_graphemes(test));
+        })
     }
-}
 
-prop_compose! {
-    fn nfa(max_states: usize, max_alphabet_size: usize)
-        (num_states in 1..max_states, alphabet_size in 1..max_alphabet_size)
-        (
-            states in state_names(num_states),
-            alphabet in alphabet_elems(alphabet_size),
-            initial_state in 0..num_states,
-            accepting_states in prop::collection::vec(any::<bool>(), num_states..=num_states),
-            epsilon_transitions in prop::collection::vec(epsilon_transitions(num_states), num_states..=num_states),
-            transitions in prop::collection::vec(nfa_transitions(num_states, alphabet_size), num_states..=num_states)
-        )
-    -> Nfa {
-        let states = states.into_iter().zip(
-            accepting_states.into_iter().zip(
-                transitions.into_iter().zip(
-                    epsilon_transitions.into_iter()
-                )
-            )
-        ).enumerate().map(|(idx, (state_name, (accepting, (transitions, epsilon_transitions))))|
-            NfaState {
-                name: Rc::from(state_name.as_str()),
-                initial: idx == initial_state,
-                accepting,
-                epsilon_transitions,
-                transitions
-            }
-        ).collect();
+    #[test]
+    fn extended_regex_operations(
+        regex1 in random_regex("[a-cε∅]"),
+        regex2 in random_regex("[a-cε∅]"),
+        tests in prop::collection::vec("[a-d]{0,8}", 20)
+    ) {
+        let nfa1 = parser::regex(&regex1).unwrap().to_nfa();
+        let nfa2 = parser::regex(&regex2).unwrap().to_nfa();
+        let intersection = parser::extended_regex(&format!("({regex1})&({regex2})")).unwrap();
+        let complement = parser::extended_regex(&format!("~({regex1})")).unwrap();
+        let reparsed = parser::extended_regex(&intersection.to_string()).unwrap();
+        assert!(intersection.equivalent_to(&reparsed));
 
-        Nfa {
-            alphabet: alphabet.iter().map(|entry| Rc::from(entry.as_str())).collect(),
-            states,
-            initial_state
-        }
+        let intersection_dfa = intersection.to_dfa();
+        let intersection_nfa = intersection.to_nfa();
+        let complement_dfa = complement.to_dfa();
+        let complement_nfa = complement.to_nfa();
+        tests.iter().for_each(|test| {
+            let both = nfa1.accepts_graphemes(test) && nfa2.accepts_graphemes(test);
+            assert_eq!(intersection_dfa.accepts_graphemes(test), both);
+            assert_eq!(intersection_nfa.accepts_graphemes(test), both);
+            // The complement is only taken over the symbols in the regex
+            let in_alphabet = test
+                .graphemes(true)
+                .all(|g| nfa1.alphabet().iter().any(|elem| elem.as_ref() == g));
+            let complemented = in_alphabet && !nfa1.accepts_graphemes(test);
+            assert_eq!(complement_dfa.accepts_graphemes(test), complemented);
+            assert_eq!(complement_nfa.accepts_graphemes(test), complemented);
+        })
     }
-}
 
-prop_compose! {
-    fn fixed_alphabet_dfa(max_states: usize, alphabet: RangeInclusive<char>, alphabet_size: usize)
-        (num_states in 1..max_states)
+    #[test]
+    fn nfa_homomorphism(regex_str in random_regex("[a-cε∅]")) {
+        let nfa = parser::regex(&regex_str).unwrap().to_nfa();
+        let image = nfa.apply_homomorphism(|elem| match elem {
+            "a" => vec!["x", "y"],
+            "b" => vec![],
+            _ => vec!["x"],
+        });
+        // Substituting the images in the regex gives a regex for the image of the language
+        let substituted = regex_str.replace('a', "(xy)").replace('b', "ε").replace('c', "x");
+        let mut expected = parser::regex(&substituted).unwrap().to_dfa();
+        let mut image = image.to_dfa();
+        let alphabet = [Rc::from("x"), Rc::from("y")];
+        expected.extend_alphabet(&alphabet);
+        image.extend_alphabet(&alphabet);
+        assert!(image.equivalent_to(&expected));
+    }
+
+    #[test]
+    fn dfa_inverse_homomorphism(
+        dfa in fixed_alphabet_dfa(10, 'a'..='c', 3),
+        tests in prop::collection::vec("[x-z]{0,6}", 20)
+    ) {
+        fn h(elem: &str) -> Vec<&str> {
+            match elem {
+                "x" => vec!["a", "b"],
+                "y" => vec![],
+                _ => vec!["c"],
+            }
+        }
+        let preimage = dfa.inverse_homomorphism(h, &["x", "y", "z"]);
+        tests.iter().for_each(|test| {
+            let image = test.graphemes(true).flat_map(h).collect::<Vec<_>>();
+            assert_eq!(preimage.accepts_graphemes(test), dfa.accepts(&image));
+        });
+
+        // h(h⁻¹(L)) is a subset of L
+        let nfa = dfa.clone().to_nfa();
+        let image = preimage.to_nfa().apply_homomorphism(h);
+        assert!(image.is_subset_of(&nfa).unwrap());
+        // L is a subset of h⁻¹(h(L)) for an injective h
+        fn g(elem: &str) -> Vec<&str> {
+            match elem {
+                "a" => vec!["x"],
+                "b" => vec!["y", "y"],
+                _ => vec!["x", "z"],
+            }
+        }
+        let roundtrip = nfa.apply_homomorphism(g).to_dfa().inverse_homomorphism(g, &["a", "b", "c"]);
+        assert!(nfa.is_subset_of(&roundtrip.to_nfa()).unwrap());
+    }
+
+    /// Tests that a DFA encoded with to_bytes() is decoded to the same DFA
+    #[test]
+    fn dfa_bytes_roundtrip(dfa in dfa(50, 50)) {
+        prop_assert_eq!(Dfa::from_bytes(&dfa.to_bytes()), Ok(dfa));
+    }
+
+    /// Tests that a byte DFA accepts the same strings as the DFA it was converted from, including strings with bytes
+    /// outside the alphabet
+    #[test]
+    fn dfa_to_byte_dfa(
+        dfa in fixed_alphabet_dfa(20, 'a'..='d', 4),
+        words in prop::collection::vec(prop::collection::vec(b'a'..=b'e', 0..10), 20),
+    ) {
+        let byte_dfa = dfa.to_byte_dfa().unwrap();
+        for word in words {
+            let elems = word.iter().map(|&b| (b as char).to_string()).collect::<Vec<_>>();
+            let elems = elems.iter().map(String::as_str).collect::<Vec<_>>();
+            prop_assert_eq!(byte_dfa.accepts(&word), dfa.accepts(&elems));
+        }
+    }
+
+    /// Tests that the lazy intersection check agrees with building the intersection, also when the alphabets are
+    /// ordered differently
+    #[test]
+    fn dfa_intersects(
+        dfa1 in fixed_alphabet_dfa(15, 'a'..='c', 3),
+        dfa2 in fixed_alphabet_dfa(15, 'a'..='c', 3),
+        other in fixed_alphabet_dfa(5, 'a'..='d', 4),
+    ) {
+        let expected = dfa1.intersection(&dfa2).unwrap().has_reachable_accepting_state();
+        prop_assert_eq!(dfa1.intersects(&dfa2), Ok(expected));
+        prop_assert_eq!(dfa2.intersects(&dfa1), Ok(expected));
+        prop_assert_eq!(dfa1.intersects(&other), Err(AlphabetMismatch));
+    }
+
+    /// Tests that every state of the product corresponds to the pair of states given by the mapping, both in whether
+    /// it is accepting and in its transitions
+    #[test]
+    fn dfa_product_with_mapping(
+        dfa1 in fixed_alphabet_dfa(10, 'a'..='c', 3),
+        dfa2 in fixed_alphabet_dfa(10, 'a'..='c', 3),
+        other in fixed_alphabet_dfa(5, 'a'..='d', 4),
+    ) {
+        let accept = |s1: bool, s2: bool| s1 != s2;
+        let (product, pairs) = dfa1.product_with_mapping(&dfa2, accept).unwrap();
+        prop_assert_eq!(pairs.len(), product.states().len());
+        prop_assert_eq!(pairs[product.initial_state_index()], (dfa1.initial_state_index(), dfa2.initial_state_index()));
+        prop_assert_eq!(&product, &dfa1.symmetric_difference(&dfa2).unwrap());
+        for (state, &(s1, s2)) in product.states().iter().zip(&pairs) {
+            let (state1, state2) = (&dfa1.states()[s1], &dfa2.states()[s2]);
+            prop_assert_eq!(state.is_accepting(), accept(state1.is_accepting(), state2.is_accepting()));
+            for (elem, &target) in product.alphabet().iter().zip(state.transitions()) {
+                let idx1 = dfa1.alphabet().iter().position(|e| e == elem).unwrap();
+                let idx2 = dfa2.alphabet().iter().position(|e| e == elem).unwrap();
+                prop_assert_eq!(pairs[target], (state1.transitions()[idx1], state2.transitions()[idx2]));
+            }
+        }
+        prop_assert_eq!(dfa1.product_with_mapping(&other, accept), Err(AlphabetMismatch));
+    }
+
+    /// Tests that the lazily determinized DFA accepts the same words as the NFA, and that it reaches the same
+    /// states as the full DFA when all transitions are taken
+    #[test]
+    fn nfa_lazy_dfa(
+        nfa in nfa(15, 4),
+        words in prop::collection::vec(prop::collection::vec(any::<prop::sample::Index>(), 0..10), 20),
+    ) {
+        let mut lazy = nfa.lazy_dfa();
+        for word in words {
+            let word = word
+                .iter()
+                .map(|idx| idx.get(nfa.alphabet()) as &str)
+                .collect::<Vec<_>>();
+            prop_assert_eq!(lazy.accepts(&word), nfa.accepts(&word));
+        }
+
+        let mut state = 0;
+        while state < lazy.num_states() {
+            for elem in nfa.alphabet().iter() {
+                lazy.step(state, elem).unwrap();
+            }
+            state += 1;
+        }
+        let dfa = nfa.to_dfa();
+        prop_assert_eq!(lazy.num_states(), dfa.states().len());
+    }
+
+    /// Tests that the accepting state found is reachable, and the first accepting state in breadth-first order
+    #[test]
+    fn dfa_first_reachable_accepting_state(dfa in dfa(15, 4)) {
+        let first = dfa.first_reachable_accepting_state();
+        prop_assert_eq!(first.is_some(), dfa.has_reachable_accepting_state());
+        if let Some(state) = first {
+            prop_assert!(dfa.states()[state].is_accepting());
+            prop_assert!(dfa.reachable_state_idx().contains(&state));
+            let mut before = dfa.reachable_states_iter().take_while(|&idx| idx != state);
+            prop_assert!(before.all(|idx| !dfa.states()[idx].is_accepting()));
+        }
+    }
+
+    /// Tests that the power of a DFA accepts a word exactly when it can be split into the given number of words
+    /// accepted by the DFA
+    #[test]
+    fn dfa_power(
+        dfa in dfa(6, 3),
+        k in 0..4usize,
+        words in prop::collection::vec(prop::collection::vec(any::<prop::sample::Index>(), 0..7), 20),
+    ) {
+        let power = dfa.power(k);
+        let expected_states = if k == 0 { 1 } else { dfa.states().len() * k };
+        prop_assert_eq!(power.states().len(), expected_states);
+        for word in words {
+            let word = word
+                .iter()
+                .map(|idx| idx.get(dfa.alphabet()) as &str)
+                .collect::<Vec<_>>();
+            let n = word.len();
+            // accepted[i][j] is true if word[i..j] is accepted by the DFA
+            let accepted = (0..=n)
+                .map(|i| (0..=n).map(|j| i <= j && dfa.accepts(&word[i..j])).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            // splits[j] is true if word[..j] can be split into the words accepted so far
+            let mut splits = (0..=n).map(|j| j == 0).collect::<Vec<_>>();
+            for _ in 0..k {
+                splits = (0..=n)
+                    .map(|j| (0..=j).any(|i| splits[i] && accepted[i][j]))
+                    .collect();
+            }
+            prop_assert_eq!(power.accepts(&word), splits[n]);
+        }
+    }
+
+    /// Tests that the accepting state reached is given exactly when the word is accepted, and that it is the state the
+    /// evaluator ends in
+    #[test]
+    fn dfa_accepting_state(
+        dfa in dfa(10, 4),
+        words in prop::collection::vec(prop::collection::vec(any::<prop::sample::Index>(), 0..10), 20),
+    ) {
+        for word in words {
+            let mut word = word
+                .iter()
+                .map(|idx| idx.get(dfa.alphabet()) as &str)
+                .collect::<Vec<_>>();
+            let state = dfa.accepting_state(&word);
+            prop_assert_eq!(state.is_some(), dfa.accepts(&word));
+            if let Some(state) = state {
+                let mut eval = dfa.evaluator();
+                eval.step_multiple(&word);
+                prop_assert_eq!(state, eval.current_state_idx());
+            }
+            word.push("not in the alphabet");
+            prop_assert_eq!(dfa.accepting_state(&word), None);
+        }
+    }
+
+    /// Tests that retaining the reachable states is the same as removing the unreachable states, and that removing
+    /// any states from a NFA only errors for the initial state
+    #[test]
+    fn retain_states(
+        dfa in dfa(10, 3),
+        nfa in nfa(10, 3),
+        removed in prop::collection::vec(any::<bool>(), 10),
+    ) {
+        let reachable = dfa.reachable_state_idx();
+        let mut retained = dfa.clone();
+        retained.retain_states(|idx, _| reachable.contains(&idx)).unwrap();
+        let mut expected = dfa.clone();
+        expected.remove_unreachable_states();
+        prop_assert_eq!(retained, expected);
+
+        let reachable = nfa.reachable_state_idx();
+        let mut retained = nfa.clone();
+        retained.retain_states(|idx, _| reachable.contains(&idx)).unwrap();
+        prop_assert!(retained.equivalent_to(&nfa));
+
+        let mut retained = nfa.clone();
+        let result = retained.retain_states(|idx, _| !removed[idx]);
+        if removed[nfa.initial_state_index()] {
+            prop_assert_eq!(result, Err(RetainError::InitialStateRemoved(nfa.initial_state_index())));
+            prop_assert_eq!(retained, nfa);
+        } else {
+            prop_assert_eq!(result, Ok(()));
+            let kept = removed.iter().take(nfa.states().len()).filter(|&&r| !r).count();
+            prop_assert_eq!(retained.states().len(), kept);
+        }
+    }
+
+    /// Tests that parsing a DFA table from a reader gives the same DFA as parsing it from a string
+    #[test]
+    fn dfa_from_reader_roundtrip(dfa in dfa(50, 50)) {
+        let table = dfa.to_table();
+        let parsed = parser::dfa_from_reader(table.as_bytes()).unwrap();
+        let read: Dfa = parsed.as_parsed().try_into().unwrap();
+        prop_assert_eq!(read, dfa);
+    }
+
+    /// Tests that a derivation tree is found exactly for the words accepted by the grammar, and that the tree only
+    /// uses productions of the grammar and derives the word
+    #[test]
+    fn grammar_parse_tree(
+        owned in grammar(6, 4),
+        words in prop::collection::vec(prop::collection::vec(any::<prop::sample::Index>(), 0..6), 10),
+    ) {
+        let grammar = owned.as_grammar().unwrap();
+
+        fn check_node(grammar: &Grammar, node: &ParseNode) {
+            if let ParseNode::Nonterminal(name, children) = node {
+                let alt = children.iter().map(|c| c.symbol()).collect::<Vec<_>>();
+                assert!(grammar.productions.iter().any(|p| p.name == *name && p.alternatives.contains(&alt)));
+                children.iter().for_each(|c| check_node(grammar, c));
+            } else {
+                assert!(grammar.terminals.contains(&node.symbol()));
+            }
+        }
+
+        for word in words {
+            let word = terminal_word(&grammar, &word);
+            let tree = grammar.parse_tree(&word);
+            prop_assert_eq!(tree.is_some(), grammar.accepts(&word), "{:?}", word);
+            if let Some(tree) = tree {
+                prop_assert_eq!(tree.symbol(), grammar.start);
+                prop_assert_eq!(tree.word(), word);
+                check_node(&grammar, &tree);
+            }
+        }
+    }
+
+    /// Tests that the words sampled from a grammar are generated by it, and that sampling with a larger depth
+    /// succeeds whenever sampling with a smaller one does
+    #[test]
+    fn grammar_sample(owned in grammar(6, 4), seed: u64) {
+        let grammar = owned.as_grammar().unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+        prop_assert_eq!(grammar.sample(&mut rng, 0), None);
+        let mut found = false;
+        for depth in 1..8 {
+            match grammar.sample(&mut rng, depth) {
+                Some(word) => {
+                    prop_assert!(grammar.accepts(&word), "{:?}", word);
+                    found = true;
+                }
+                None => prop_assert!(!found),
+            }
+        }
+    }
+
+    /// Tests that eliminating left recursion keeps the language of a grammar, and that the result has no left
+    /// recursion when the grammar has no ε-productions and no unit productions
+    #[test]
+    fn grammar_eliminate_left_recursion(
+        owned in grammar(5, 3),
+        words in prop::collection::vec(prop::collection::vec(any::<prop::sample::Index>(), 0..6), 20),
+    ) {
+        let grammar = owned.as_grammar().unwrap();
+
+        /// Checks if some nonterminal can derive a sentential form starting with itself
+        fn is_left_recursive(grammar: &Grammar) -> bool {
+            let nullable = grammar.nullable_nonterminals();
+            let left_corners = |nt: &str| -> Vec<&str> {
+                grammar.productions.iter().filter(|p| p.name == nt).flat_map(|p| &p.alternatives).flat_map(|alt| {
+                    let prefix = alt.iter().take_while(|s| nullable.contains(*s)).count();
+                    alt.iter().take(prefix + 1).copied()
+                }).collect()
+            };
+            grammar.nonterminals.iter().any(|&nt| {
+                let mut reached = left_corners(nt);
+                let mut idx = 0;
+                while let Some(&symbol) = reached.get(idx) {
+                    for corner in left_corners(symbol) {
+                        if !reached.contains(&corner) {
+                            reached.push(corner);
+                        }
+                    }
+                    idx += 1;
+                }
+                reached.contains(&nt)
+            })
+        }
+
+        let eliminated = grammar.eliminate_left_recursion();
+        let eliminated = eliminated.as_grammar().unwrap();
+        for word in words {
+            let word = terminal_word(&grammar, &word);
+            prop_assert_eq!(eliminated.accepts(&word), grammar.accepts(&word), "{:?}", word);
+        }
+
+        let proper = grammar.remove_epsilon_productions().remove_unit_productions();
+        if proper.nullable_nonterminals().is_empty() {
+            let eliminated = proper.eliminate_left_recursion();
+            prop_assert!(!is_left_recursive(&eliminated.as_grammar().unwrap()));
+        }
+    }
+
+    /// Tests that a predictive parser using the LL(1) table of a grammar accepts exactly the words generated by it
+    #[test]
+    fn grammar_ll1_table(
+        owned in grammar(5, 3),
+        words in prop::collection::vec(prop::collection::vec(any::<prop::sample::Index>(), 0..6), 20),
+    ) {
+        let grammar = owned.as_grammar().unwrap();
+        let Ok(table) = grammar.ll1_table() else {
+            return Ok(());
+        };
+
+        for word in words {
+            let word = terminal_word(&grammar, &word);
+            let mut stack = vec![grammar.start];
+            let mut pos = 0;
+            let mut steps = 0;
+            let accepted = loop {
+                steps += 1;
+                prop_assert!(steps < 10_000, "the predictive parser doesn't terminate on {:?}", word);
+                let lookahead = word.get(pos).copied().unwrap_or(grammar::END_OF_INPUT);
+                match stack.pop() {
+                    None => break pos == word.len(),
+                    Some(symbol) if grammar.nonterminals.contains(&symbol) => match table.get(symbol, lookahead) {
+                        Some(alt) => stack.extend(alt.iter().rev()),
+                        None => break false,
+                    },
+                    Some(symbol) if symbol == lookahead => pos += 1,
+                    Some(_) => break false,
+                }
+            };
+            prop_assert_eq!(accepted, grammar.accepts(&word), "{:?}", word);
+        }
+    }
+
+    /// Tests that the PDA of a grammar accepts exactly the words generated by the grammar
+    #[test]
+    fn grammar_to_pda(
+        owned in grammar(5, 3),
+        words in prop::collection::vec(prop::collection::vec(any::<prop::sample::Index>(), 0..6), 20),
+    ) {
+        let grammar = owned.as_grammar().unwrap();
+        let pda = grammar.to_pda();
+        for word in words {
+            let word = terminal_word(&grammar, &word);
+            prop_assert_eq!(pda.accepts(&word), grammar.accepts(&word), "{:?}", word);
+        }
+    }
+
+    /// Tests that a grammar can be written with to_string() and then be parsed to the
+    /// *very same* grammar again
+    #[test]
+    fn grammar_reparse(owned in grammar(10, 10)) {
+        let grammar = owned.as_grammar().unwrap();
+        let expected = parser::ParsedGrammar {
+            nonterminals: grammar.nonterminals.clone(),
+            terminals: grammar.terminals.clone(),
+            start: grammar.start,
+            productions: grammar
+                .productions
+                .iter()
+                .map(|p| parser::ParsedProduction { name: p.name, alternatives: p.alternatives.clone() })
+                .collect(),
+        };
+        let output = grammar.to_string();
+        assert_eq!(parser::grammar(&output).unwrap(), expected);
+        let reparsed: Grammar = parser::grammar(&output).unwrap().try_into().unwrap();
+        assert_eq!(reparsed, grammar);
+    }
+
+    #[test]
+    fn regex_parse(regex_str in random_regex("[a-zε∅]")) {
+        let parse1 = parser::regex(&regex_str).unwrap();
+        let stringified = parse1.to_string();
+        let parse2 = parser::regex(&stringified).unwrap();
+        assert!(parse1.to_nfa().equivalent_to(&parse2.to_nfa()));
+    }
+}
+
+prop_compose! {
+    fn fixed_alphabet_nfa(max_states: usize, alphabet: RangeInclusive<char>, alphabet_size: usize)
+        (num_states in 1..max_states)
+        (
+            states in state_names(num_states),
+            initial_state in 0..num_states,
+            accepting_states in prop::collection::vec(any::<bool>(), num_states..=num_states),
+            transitions in prop::collection::vec(nfa_transitions(num_states, alphabet_size), num_states..=num_states),
+            epsilon_transitions in prop::collection::vec(epsilon_transitions(num_states), num_states..=num_states),
+        )
+    -> Nfa {
+        let states = states.into_iter().zip(
+            accepting_states.into_iter().zip(
+                transitions.into_iter().zip(
+                    epsilon_transitions.into_iter()
+                )
+            )
+        ).enumerate().map(|(idx, (state_name, (accepting, (transitions, epsilon_transitions))))|
+            NfaState {
+                name: Rc::from(state_name.as_str()),
+                initial: idx == initial_state,
+                accepting,
+                transitions,
+                epsilon_transitions
+            }
+        ).collect();
+
+        let mut alphabet: Vec<Rc<str>> = alphabet.clone().map(|c| Rc::from(c.to_string())).collect();
+        alphabet.shuffle(&mut thread_rng());
+        let alphabet = Rc::from(alphabet);
+
+        Nfa {
+            alphabet,
+            states,
+            initial_state
+        }
+    }
+}
+
+prop_compose! {
+    fn seeded_nfa(max_states: usize, max_alphabet_size: usize)
+        (num_states in 1..max_states, alphabet_size in 1..max_alphabet_size)
+        (
+            num_states in Just(num_states),
+            alphabet in alphabet_elems(alphabet_size),
+            seed in any::<u64>(),
+            density in 0.0..=1.0,
+            epsilon in any::<bool>(),
+        )
+    -> Nfa {
+        let alphabet = alphabet.iter().map(String::as_str).collect::<Vec<_>>();
+        Nfa::random(&mut StdRng::seed_from_u64(seed), num_states, &alphabet, density, epsilon)
+    }
+}
+
+prop_compose! {
+    fn nfa(max_states: usize, max_alphabet_size: usize)
+        (num_states in 1..max_states, alphabet_size in 1..max_alphabet_size)
+        (
+            states in state_names(num_states),
+            alphabet in alphabet_elems(alphabet_size),
+            initial_state in 0..num_states,
+            accepting_states in prop::collection::vec(any::<bool>(), num_states..=num_states),
+            epsilon_transitions in prop::collection::vec(epsilon_transitions(num_states), num_states..=num_states),
+            transitions in prop::collection::vec(nfa_transitions(num_states, alphabet_size), num_states..=num_states)
+        )
+    -> Nfa {
+        let states = states.into_iter().zip(
+            accepting_states.into_iter().zip(
+                transitions.into_iter().zip(
+                    epsilon_transitions.into_iter()
+                )
+            )
+        ).enumerate().map(|(idx, (state_name, (accepting, (transitions, epsilon_transitions))))|
+            NfaState {
+                name: Rc::from(state_name.as_str()),
+                initial: idx == initial_state,
+                accepting,
+                epsilon_transitions,
+                transitions
+            }
+        ).collect();
+
+        Nfa {
+            alphabet: alphabet.iter().map(|entry| Rc::from(entry.as_str())).collect(),
+            states,
+            initial_state
+        }
+    }
+}
+
+prop_compose! {
+    fn fixed_alphabet_dfa(max_states: usize, alphabet: RangeInclusive<char>, alphabet_size: usize)
+        (num_states in 1..max_states)
         (
             states in state_names(num_states),
             initial_state in 0..num_states,
@@ -398,6 +1460,16 @@ prop_compose! {
     }
 }
 
+prop_compose! {
+    fn seeded_dfa(max_states: usize, max_alphabet_size: usize)
+        (num_states in 1..max_states, alphabet_size in 1..max_alphabet_size)
+        (num_states in Just(num_states), alphabet in alphabet_elems(alphabet_size), seed in any::<u64>())
+    -> Dfa {
+        let alphabet = alphabet.iter().map(String::as_str).collect::<Vec<_>>();
+        Dfa::random(&mut StdRng::seed_from_u64(seed), num_states, &alphabet)
+    }
+}
+
 prop_compose! {
     fn dfa(max_states: usize, max_alphabet_size: usize)
         (num_states in 1..max_states, alphabet_size in 1..max_alphabet_size)
@@ -513,6 +1585,66 @@ prop_compose! {
     }
 }
 
+prop_compose! {
+    fn grammar_parts(max_nonterminals: usize, max_terminals: usize)
+        (num_nonterminals in 1..max_nonterminals, num_terminals in 0..max_terminals)
+        (
+            names in filtered_set(num_nonterminals + num_terminals, r"[^\s#{}]+", &["|", "→", "->"]),
+            productions in prop::collection::vec(
+                prop::option::of(prop::collection::vec(
+                    prop::collection::vec(0..num_nonterminals + num_terminals, 0..5),
+                    1..5
+                )),
+                num_nonterminals..=num_nonterminals
+            )
+        )
+    -> (Vec<String>, Vec<String>, Vec<(usize, Vec<Vec<usize>>)>) {
+        let mut names: Vec<_> = names.into_iter().collect();
+        let terminals = names.split_off(productions.len());
+        // The format needs at least one production, so the first nonterminal always has one
+        let productions = productions.into_iter()
+            .enumerate()
+            .filter_map(|(idx, alternatives)| match alternatives {
+                None if idx == 0 => Some((idx, vec![vec![]])),
+                alternatives => alternatives.map(|alternatives| (idx, alternatives)),
+            })
+            .collect();
+        (names, terminals, productions)
+    }
+}
+
+prop_compose! {
+    fn grammar(max_nonterminals: usize, max_terminals: usize)
+        ((nonterminals, terminals, productions) in grammar_parts(max_nonterminals, max_terminals))
+    -> GrammarOwned {
+        let symbol = |idx: usize| nonterminals.iter().chain(&terminals).nth(idx).unwrap().clone();
+        GrammarOwned {
+            start: nonterminals[0].clone(),
+            productions: productions
+                .into_iter()
+                .map(|(name, alternatives)| ProductionOwned {
+                    name: nonterminals[name].clone(),
+                    alternatives: alternatives.iter().map(|alt| alt.iter().map(|&s| symbol(s)).collect()).collect(),
+                })
+                .collect(),
+            nonterminals,
+            terminals,
+        }
+    }
+}
+
+/// Picks the terminals of `grammar` given by the indices in `word`, giving the empty word if the grammar has no
+/// terminals
+fn terminal_word<'a>(grammar: &Grammar<'a>, word: &[prop::sample::Index]) -> Vec<&'a str> {
+    if grammar.terminals.is_empty() {
+        vec![]
+    } else {
+        word.iter()
+            .map(|idx| *idx.get(&grammar.terminals))
+            .collect()
+    }
+}
+
 fn random_regex(base: &'static str) -> impl Strategy<Value = String> {
     base.prop_recursive(20, 1024, 20, |inner| {
         prop_oneof![
@@ -526,6 +1658,300 @@ fn random_regex(base: &'static str) -> impl Strategy<Value = String> {
 }
 
 #[test]
+fn test_parse_error_position() {
+    let input = "
+           ε    a
+    -> s0  {}   {s0 s1}
+     * s1  {s0  {}
+    ";
+    let err = parser::nfa(input).unwrap_err();
+    assert_eq!((err.line, err.column), (4, 6));
+    assert_eq!(err.message, "unexpected '*'");
+
+    let err = parser::dfa("a b\n").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 1));
+    assert_eq!(err.message, "unexpected end of input");
+
+    let input = "
+    Nonterminals: S T
+    Terminals: a
+    Start: S
+    S → a T
+    T a
+    ";
+    let err = parser::grammar(input).unwrap_err();
+    assert_eq!((err.line, err.column), (6, 7));
+    assert_eq!(err.message, "unexpected 'a'");
+
+    let err = parser::grammar("Nonterminals S\nTerminals: a\nStart: S\nS → a\n").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 1));
+    assert_eq!(err.message, "unexpected 'Nonterminals'");
+}
+
+#[test]
+fn test_dfa_from_reader() {
+    use crate::parser::ReadError;
+
+    let input =
+        "# A comment\r\n       a  b\r\n\r\n-> s0  s1 s0\r\n * s1  s1 s0   # another one\r\n";
+    let parsed = parser::dfa_from_reader(input.as_bytes()).unwrap();
+    let read: Dfa = parsed.as_parsed().try_into().unwrap();
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    assert_eq!(read, dfa);
+
+    // Errors are at the same positions as when parsing a string
+    for input in [
+        "\n       a  b\n-> s0  s1 s0\n * s1  s1 {s0}\n",
+        "\n    a b\n -> * s0\n",
+        "  ε a\n-> s s s\n",
+        "a b\n",
+        "# Only a comment\n",
+    ] {
+        let expected = parser::dfa(input).unwrap_err();
+        match parser::dfa_from_reader(input.as_bytes()) {
+            Err(ReadError::Parse {
+                line,
+                column,
+                message,
+            }) => assert_eq!(
+                (line, column, message),
+                (expected.line, expected.column, expected.message)
+            ),
+            result => panic!("expected a parse error for {input:?}, got {result:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_parse_many() {
+    let input = "
+    # Two NFAs
+           a    b
+    -> * s0 {s0} {}
+    ===
+    # Another one, with an error
+         ε   a
+    * s  {}  {}
+    -> t {s} {s
+    ===
+    ";
+    let err = parser::nfas_with_separator(input, "===").unwrap_err();
+    assert_eq!((err.line, err.column), (9, 14));
+
+    let input = input.replace("{s\n", "{s}\n");
+    let nfas = parser::nfas_with_separator(&input, "===").unwrap();
+    let nfas = nfas
+        .into_iter()
+        .map(|nfa| Nfa::try_from(nfa).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(nfas.len(), 2);
+    assert!(nfas[0].accepts_graphemes("aaa"));
+    assert!(nfas[1].accepts_graphemes("a"));
+    assert!(parser::nfas(&input).is_err());
+
+    // The nom parser consumes the whole input
+    let (rest, parsed) = parser::nfa_many_with_separator(&input, "===").unwrap();
+    assert_eq!(rest, "");
+    assert_eq!(parsed.len(), 2);
+}
+
+#[test]
+fn test_parse_partial_dfa() {
+    let input = "
+           a    b
+    -> s0  s1   s0
+     * s1  -
+       trap s0  s1
+    ";
+    // The strict conversion doesn't allow missing transitions
+    assert!(Dfa::try_from(parser::dfa(input).unwrap()).is_err());
+
+    let dfa = Dfa::try_from_partial(parser::dfa(input).unwrap()).unwrap();
+    assert_eq!(dfa.states().len(), 4);
+    let trap = &dfa.states()[3];
+    assert_eq!(trap.name(), "0");
+    assert_eq!(trap.transitions(), &[3, 3]);
+    assert_eq!(dfa.states()[1].transitions(), &[3, 3]);
+    assert!(dfa.accepts_graphemes("ba"));
+    assert!(!dfa.accepts_graphemes("aa"));
+
+    // Nothing is added if no transitions are missing
+    let input = "
+           a
+    -> s0  s0
+    ";
+    let dfa = Dfa::try_from_partial(parser::dfa(input).unwrap()).unwrap();
+    assert_eq!(dfa.states().len(), 1);
+
+    // Too many transitions are still an error
+    let input = "
+           a
+    -> s0  s0 s0
+    ";
+    assert_eq!(
+        Dfa::try_from_partial(parser::dfa(input).unwrap()),
+        Err(DfaParseError::WrongNumberOfTransitions("s0", 2, 1))
+    );
+}
+
+#[test]
+fn test_nfa_table_sorted_targets() {
+    let input = "
+          ε       a
+    -> s0 {s2 s1} {s2 s0 s1}
+     * s1 {}      {s1 s0}
+       s2 {s1}    {}
+    ";
+    let nfa: Nfa = parser::nfa(input).unwrap().try_into().unwrap();
+    let lines = nfa
+        .to_table()
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            "ε a",
+            "→ s0 {s1 s2} {s0 s1 s2}",
+            "* s1 {} {s0 s1}",
+            "s2 {s1} {}",
+        ]
+    );
+}
+
+#[test]
+fn test_nfa_unambiguous() {
+    let cases = [
+        ("a|a", false),
+        ("a|b", true),
+        ("(a|b)*", true),
+        ("a*a*", false),
+        ("a*b*", true),
+        ("(a|ab)(c|bc)", false),
+        ("(a|ab)c", true),
+    ];
+    for (regex, unambiguous) in cases {
+        let nfa = parser::regex(regex).unwrap().to_nfa();
+        assert_eq!(nfa.is_unambiguous(), unambiguous, "{regex}");
+        // Any DFA is unambiguous
+        assert!(nfa.to_dfa().to_nfa().is_unambiguous(), "{regex}");
+    }
+}
+
+#[test]
+fn test_dfa_hamming_ball() {
+    let dfa = Dfa::hamming_ball(&["a", "b", "b", "a"], 2, &["a", "b"]);
+    let accepted = ["abba", "bbba", "abab", "aaaa", "bbbb", "baba"];
+    let rejected = ["", "abb", "abbaa", "baab", "bbbbb"];
+    for word in accepted {
+        assert!(dfa.accepts_graphemes(word), "{word}");
+    }
+    for word in rejected {
+        assert!(!dfa.accepts_graphemes(word), "{word}");
+    }
+    // (position, substitutions) for substitutions <= min(position, 2), and the trap
+    assert_eq!(dfa.states().len(), 1 + 2 + 3 + 3 + 3 + 1);
+
+    let dfa = Dfa::hamming_ball(&[], 3, &["a"]);
+    assert!(dfa.accepts(&[]));
+    assert!(!dfa.accepts(&["a"]));
+}
+
+#[test]
+fn test_dfa_star_free() {
+    let cases = [
+        ("(aa)*", false),
+        ("a*", true),
+        ("(ab)*", true),
+        ("a*ba*", true),
+        ("(a|b)*(aa|bb)(a|b)*", true),
+        ("((a|b)(a|b)(a|b))*", false),
+        ("(ab|ba)*", true),
+    ];
+    for (regex, star_free) in cases {
+        let dfa = parser::regex(regex).unwrap().to_nfa().to_dfa();
+        assert_eq!(dfa.is_star_free(1000), Some(star_free), "{regex}");
+    }
+}
+
+#[test]
+fn test_remove_dead_states() {
+    let input = "
+           a    b
+    -> s0  s1   trap
+     * s1  s0   trap
+       trap trap trap
+    ";
+    let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    let mut trimmed = dfa.clone();
+    trimmed.remove_dead_states();
+    // The DFA still needs the trap state, since it has all transitions
+    assert_eq!(trimmed, dfa);
+
+    let mut nfa = dfa.to_nfa();
+    nfa.remove_dead_states();
+    assert_eq!(nfa.states().len(), 2);
+    assert!(nfa.states().iter().all(|state| state.name() != "trap"));
+    assert_eq!(nfa.states()[0].transitions(), &[vec![1], vec![]]);
+    assert!(nfa.accepts_graphemes("aaa"));
+    assert!(!nfa.accepts_graphemes("ab"));
+}
+
+#[test]
+fn test_dfa_right_quotient_alphabet_mismatch() {
+    let ab: Dfa = parser::dfa("a b \n -> * s s s")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let ac: Dfa = parser::dfa("a c \n -> * s s s")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(ab.right_quotient(&ac), Err(dfa::AlphabetMismatch));
+}
+
+/// Samples many times from a DFA where a random walk would be biased, and checks that
+/// every accepted string is picked roughly equally often (more than 5 standard deviations
+/// off fails, which doesn't happen by chance)
+#[test]
+fn test_dfa_sample_uniform_distribution() {
+    // Accepts "aaa" and all strings of length 3 starting with b
+    let dfa: Dfa = parser::dfa(
+        "
+           a    b
+    -> s   a    b
+       a   aa   trap
+       aa  aaa  trap
+     * aaa trap trap
+       b   b1   b1
+       b1  b2   b2
+     * b2  trap trap
+       trap trap trap
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let mut rng = StdRng::seed_from_u64(830);
+    let mut counts = std::collections::HashMap::new();
+    let samples = 5000;
+    for _ in 0..samples {
+        let word = dfa.sample_uniform(3, &mut rng).unwrap().concat();
+        *counts.entry(word).or_insert(0usize) += 1;
+    }
+    assert_eq!(counts.len(), 5);
+    let expected = samples as f64 / 5.0;
+    let deviation = (samples as f64 * 0.2 * 0.8).sqrt();
+    for (word, count) in counts {
+        assert!(
+            (count as f64 - expected).abs() < 5.0 * deviation,
+            "{word} was sampled {count} times"
+        );
+    }
+}
+
+#[test]
+#[allow(clippy::useless_conversion)]
 fn test_parse_grammar() {
     let grammar_source = include_str!("../tests/test_files/grammar1.cfg");
     let parsed_grammar = parser::grammar(grammar_source).unwrap();
@@ -553,3 +1979,874 @@ fn test_parse_grammar() {
 
     assert!(grammar == expected_grammar);
 }
+
+#[test]
+fn test_grammar_cyk() {
+    let grammar_source = include_str!("../tests/test_files/grammar1.cfg");
+    let grammar: Grammar = parser::grammar(grammar_source).unwrap().try_into().unwrap();
+
+    let accepted: [&[&str]; 5] = [
+        &["1"],
+        &["1", "1", "1"],
+        &["1", "+", "1"],
+        &["1", "1", "-", "1", "+", "1", "1"],
+        &["1", "1", "1", "-", "1"],
+    ];
+    for word in accepted {
+        assert!(grammar.accepts(word), "{word:?} should be accepted");
+    }
+
+    let rejected: [&[&str]; 6] = [
+        &[],
+        &["+"],
+        &["1", "+"],
+        &["-", "1"],
+        &["1", "+", "+", "1"],
+        &["1", "*", "1"],
+    ];
+    for word in rejected {
+        assert!(!grammar.accepts(word), "{word:?} should be rejected");
+    }
+}
+
+#[test]
+fn test_grammar_parse_tree() {
+    let grammar_source = include_str!("../tests/test_files/grammar1.cfg");
+    let grammar: Grammar = parser::grammar(grammar_source).unwrap().try_into().unwrap();
+
+    use ParseNode::{Nonterminal as N, Terminal as T};
+    let one = || N("N", vec![T("1")]);
+    assert_eq!(
+        grammar.parse_tree(&["1", "+", "1"]),
+        Some(N(
+            "E",
+            vec![
+                N("E", vec![one()]),
+                N("O", vec![T("+")]),
+                N("E", vec![one()])
+            ]
+        ))
+    );
+    // Every node of the tree gets its own DOT node, even with the same symbol
+    let dot = grammar.parse_tree(&["1", "+", "1"]).unwrap().to_dot();
+    assert_eq!(dot.matches("[label=").count(), 9);
+    assert_eq!(dot.matches(" -> ").count(), 8);
+    assert!(dot.contains("n8 [label=\"1\", shape=box];"));
+
+    // O may be empty, so "1 1" is either N → 1 N or E → E O E with an empty O
+    let tree = grammar.parse_tree(&["1", "1"]).unwrap();
+    assert_eq!(tree.word(), ["1", "1"]);
+
+    assert_eq!(grammar.parse_tree(&["1", "+"]), None);
+    assert_eq!(grammar.parse_tree(&["1", "*", "1"]), None);
+    assert_eq!(grammar.parse_tree(&[]), None);
+}
+
+#[test]
+fn test_grammar_nullable_nonterminals() {
+    let input = "
+    Nonterminals: S A B
+    Terminals: a b
+    Start: S
+    S → B A
+    A → | a A
+    B → b B
+    ";
+    let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    assert_eq!(grammar.nullable_nonterminals(), HashSet::from(["A"]));
+
+    let grammar_source = include_str!("../tests/test_files/grammar1.cfg");
+    let grammar: Grammar = parser::grammar(grammar_source).unwrap().try_into().unwrap();
+    assert_eq!(grammar.nullable_nonterminals(), HashSet::from(["O"]));
+}
+
+#[test]
+fn test_grammar_epsilon_and_end_of_input() {
+    let input = "
+    Nonterminals: S A
+    Terminals: a
+    Start: S
+    S → A
+    A → a |
+    ";
+    let grammar: Grammar = parser::grammar(input).unwrap().try_into().unwrap();
+    assert_eq!(
+        grammar.first_sets()["A"],
+        HashSet::from(["a", grammar::EPSILON])
+    );
+    assert_eq!(
+        grammar.follow_sets()["A"],
+        HashSet::from([grammar::END_OF_INPUT])
+    );
+    let table = grammar.ll1_table().unwrap();
+    assert_eq!(table.get("A", grammar::END_OF_INPUT), Some(&[][..]));
+    assert_eq!(table.get("A", grammar::EPSILON), None);
+}
+
+#[test]
+fn test_grammar_remove_epsilon_productions() {
+    let grammar_source = include_str!("../tests/test_files/grammar1.cfg");
+    let grammar: Grammar = parser::grammar(grammar_source).unwrap().try_into().unwrap();
+    let no_eps = grammar.remove_epsilon_productions();
+
+    let expected = Grammar {
+        nonterminals: vec!["E", "N", "O"],
+        terminals: vec!["1", "+", "-"],
+        start: "E",
+        productions: vec![
+            Production {
+                name: "N",
+                alternatives: vec![vec!["1", "N"], vec!["1"]],
+            },
+            Production {
+                name: "O",
+                alternatives: vec![vec!["+"], vec!["-"]],
+            },
+            Production {
+                name: "E",
+                alternatives: vec![vec!["N"], vec!["E", "O", "E"], vec!["E", "E"]],
+            },
+        ],
+    };
+    assert_eq!(no_eps, expected);
+
+    for word in [&["1", "1"][..], &["1", "-", "1", "1"], &["1"], &[], &["+"]] {
+        assert_eq!(grammar.accepts(word), no_eps.accepts(word));
+    }
+}
+
+#[test]
+fn test_grammar_remove_unit_productions() {
+    let grammar_source = include_str!("../tests/test_files/grammar1.cfg");
+    let grammar: Grammar = parser::grammar(grammar_source).unwrap().try_into().unwrap();
+    let no_units = grammar.remove_unit_productions();
+
+    let expected = Grammar {
+        nonterminals: vec!["E", "N", "O"],
+        terminals: vec!["1", "+", "-"],
+        start: "E",
+        productions: vec![
+            Production {
+                name: "N",
+                alternatives: vec![vec!["1", "N"], vec!["1"]],
+            },
+            Production {
+                name: "O",
+                alternatives: vec![vec!["+"], vec![], vec!["-"]],
+            },
+            Production {
+                name: "E",
+                alternatives: vec![vec!["E", "O", "E"], vec!["1", "N"], vec!["1"]],
+            },
+        ],
+    };
+    assert_eq!(no_units, expected);
+}
+
+#[test]
+fn test_grammar_to_nfa() {
+    let grammar_source = include_str!("../tests/test_files/grammar1.cfg");
+    let grammar: Grammar = parser::grammar(grammar_source).unwrap().try_into().unwrap();
+    let err = grammar.to_nfa().unwrap_err();
+    assert_eq!(err.name, "E");
+    assert_eq!(err.alternative, vec!["N"]);
+
+    let right_linear = "
+        Nonterminals: S A final
+        Terminals: a b
+        Start: S
+        S → a A | b S |
+        A → a S | b A | b
+    ";
+    let grammar: Grammar = parser::grammar(right_linear).unwrap().try_into().unwrap();
+    let nfa = grammar.to_nfa().unwrap();
+    for word in ["", "b", "aa", "ab", "abbab", "abba", "aaa"] {
+        let symbols = word.graphemes(true).collect::<Vec<_>>();
+        assert_eq!(nfa.accepts(&symbols), grammar.accepts(&symbols), "{word}");
+    }
+}
+
+#[test]
+fn test_initial_state_marker() {
+    let dfa: Dfa = parser::dfa(
+        "
+              a
+           s0 s1
+        -> s1 s0
+        ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let nfa = dfa.clone().to_nfa();
+
+    for dot in [dfa.to_dot(), nfa.to_dot()] {
+        assert!(dot.contains("    start [shape=none, label=\"\"];\n"));
+        assert!(dot.contains("    start -> q1;\n"));
+        assert_eq!(dot.matches("start ->").count(), 1);
+    }
+    for mermaid in [dfa.to_mermaid(), nfa.to_mermaid()] {
+        assert!(mermaid.contains("    [*] --> s1\n"));
+        assert_eq!(mermaid.matches("[*]").count(), 1);
+    }
+}
+
+#[test]
+fn test_tikz_export() {
+    let dfa: Dfa = parser::dfa(
+        "
+                a     b     &
+        -> q_0  50%   q_0   q_0
+           50%  x^$   q_0   x^$
+         * x^$  x^$   x^$   q_0
+        ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let tikz = dfa.to_tikz();
+    assert!(tikz.contains("    \\node[state, initial] (q0) at (0, 0) {q\\_0};\n"));
+    assert!(tikz.contains("    \\node[state] (q1) at (2, 0) {50\\%};\n"));
+    assert!(
+        tikz.contains("    \\node[state, accepting] (q2) at (4, 0) {x\\textasciicircum{}\\$};\n")
+    );
+    // Parallel edges are merged, and edges skipping over a state are bent
+    assert!(tikz.contains("    \\path (q0) edge[loop above] node {\\&, b} (q0);\n"));
+    assert!(tikz.contains("    \\path (q2) edge[bend left] node {\\&} (q0);\n"));
+    assert!(tikz.contains("    \\path (q1) edge[bend left] node {b} (q0);\n"));
+    assert_eq!(tikz.matches("\\path").count(), 6);
+}
+
+#[test]
+fn test_state_constructors() {
+    let dfa: Dfa = parser::dfa("a b \n -> s₀ s₁ s₀ \n * s₁ s₁ s₀")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let states = vec![
+        DfaState::new("s₀", true, false, vec![1, 0]),
+        DfaState::new(String::from("s₁"), false, true, vec![1, 0]),
+    ];
+    assert_eq!(states, dfa.states());
+    let alphabet = dfa.alphabet().to_vec();
+    assert_eq!(
+        Dfa::from_parts(alphabet.clone(), states, 0),
+        Ok(dfa.clone())
+    );
+
+    let nfa = dfa.to_nfa();
+    let states = vec![
+        NfaState::new("s₀", true, false, vec![], vec![vec![1], vec![0]]),
+        NfaState::new("s₁", false, true, vec![], vec![vec![1], vec![0]]),
+    ];
+    assert_eq!(states, nfa.states());
+    assert_eq!(Nfa::from_parts(alphabet, states, 0), Ok(nfa));
+}
+
+#[test]
+fn test_mermaid_escaping() {
+    let dfa: Dfa = parser::dfa(
+        "
+                 a        b
+        -> state0  \"q;1\"    state0
+         * \"q;1\"   \"q;1\"    state0
+        ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let mermaid = dfa.to_mermaid();
+    assert!(mermaid.contains("    state \"#34;q#59;1#34;\" as state1\n"));
+    assert!(mermaid.contains("    [*] --> state0\n"));
+    assert!(mermaid.contains("    state1 --> state0 : b\n"));
+    assert!(mermaid.contains("    class state1 accepting\n"));
+
+    // A generated identifier must not clash with another state
+    let dfa: Dfa = parser::dfa(
+        "
+               a
+        -> q:0 state0
+           state0 q:0
+        ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let mermaid = dfa.to_mermaid();
+    assert!(mermaid.contains("    state \"q#58;0\" as state0_\n"));
+    assert!(mermaid.contains("    state0_ --> state0 : a\n"));
+}
+
+#[test]
+fn test_dot_escaping() {
+    let dfa: Dfa = parser::dfa(
+        r#"
+                 "      \
+        -> "q"   "q"    a\b
+         * a\b   a\b    "q"
+        "#,
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let dot = dfa.to_dot();
+    assert!(dot.contains(r#"    q0 [label="\"q\""];"#));
+    assert!(dot.contains(r#"    q1 [label="a\\b", shape=doublecircle];"#));
+    assert!(dot.contains(r#"    q0 -> q0 [label="\""];"#));
+    assert!(dot.contains(r#"    q0 -> q1 [label="\\"];"#));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize() {
+    let dfa: Dfa = parser::dfa(
+        "
+              a  b
+        -> s₀ s₁ s₀
+         * s₁ s₁ s₁
+        ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let json = serde_json::to_value(&dfa).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "alphabet": ["a", "b"],
+            "states": [
+                {"name": "s₀", "initial": true, "accepting": false, "transitions": [1, 0]},
+                {"name": "s₁", "initial": false, "accepting": true, "transitions": [1, 1]},
+            ],
+            "initial_state": 0,
+        })
+    );
+
+    let json = serde_json::to_value(dfa.to_nfa()).unwrap();
+    assert_eq!(
+        json["states"][0]["epsilon_transitions"],
+        serde_json::json!([])
+    );
+    assert_eq!(
+        json["states"][0]["transitions"],
+        serde_json::json!([[1], [0]])
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize_grammar() {
+    let grammar_source = include_str!("../tests/test_files/grammar1.cfg");
+    let grammar: Grammar = parser::grammar(grammar_source).unwrap().try_into().unwrap();
+    let json = serde_json::to_string(&grammar).unwrap();
+    let owned: GrammarOwned = serde_json::from_str(&json).unwrap();
+    assert_eq!(owned, GrammarOwned::from(&grammar));
+    assert_eq!(owned.as_grammar(), Ok(grammar));
+
+    let json = serde_json::json!({
+        "nonterminals": ["S"],
+        "terminals": ["a"],
+        "start": "T",
+        "productions": [{"name": "S", "alternatives": [["a"]]}],
+    });
+    let owned: GrammarOwned = serde_json::from_value(json).unwrap();
+    assert!(owned.as_grammar().is_err());
+}
+
+#[test]
+fn test_regex_display() {
+    let cases = [
+        ("(a)(b)", "ab"),
+        ("((a)|(b))", "a|b"),
+        ("(a|b)*c", "(a|b)*c"),
+        ("a(b|c)", "a(b|c)"),
+        ("(ab)*", "(ab)*"),
+        ("((a*))*", "(a*)*"),
+        ("(a|(b|c))d*", "(a|b|c)d*"),
+        ("a+", "aa*"),
+        ("\\((\\*|ε)", "\\((\\*|ε)"),
+    ];
+    for (input, expected) in cases {
+        let regex = parser::regex(input).unwrap();
+        assert_eq!(regex.to_string(), expected, "Display of {input}");
+    }
+
+    let empty_seq = Regex::from(RegexTree::Sequence(vec![]));
+    assert_eq!(empty_seq.to_string(), "ε");
+    let empty_alt = Regex::from(RegexTree::Sequence(vec![
+        RegexTree::Char(RegexChar::Grapheme("a".into())),
+        RegexTree::Alt(vec![]),
+    ]));
+    assert_eq!(empty_alt.to_string(), "a∅");
+}
+
+#[test]
+fn test_star_height() {
+    let cases = [
+        ("∅", 0),
+        ("ab|c", 0),
+        ("a*b*", 1),
+        ("(a*b)*", 2),
+        ("((a*b)*|c)d", 2),
+        ("(a|(b(c*d)*)*)*", 4),
+        ("a+", 1),
+        ("[a-c]{2,}", 1),
+    ];
+    for (input, expected) in cases {
+        let regex = parser::regex(input).unwrap();
+        assert_eq!(regex.star_height(), expected, "Star height of {input}");
+    }
+}
+
+#[test]
+fn test_extended_regex() {
+    let a = || RegexTree::Char(RegexChar::Grapheme("a".into()));
+    let b = || RegexTree::Char(RegexChar::Grapheme("b".into()));
+    let regex = parser::extended_regex("~ab*|a&b").unwrap();
+    assert_eq!(
+        regex.as_tree(),
+        &RegexTree::Alt(vec![
+            RegexTree::Sequence(vec![
+                RegexTree::Complement(Box::new(a())),
+                RegexTree::Repeat(Box::new(b())),
+            ]),
+            RegexTree::Intersection(vec![a(), b()]),
+        ])
+    );
+    assert_eq!(regex.to_string(), "~ab*|a&b");
+    assert_eq!(
+        parser::extended_regex("~a*").unwrap().as_tree(),
+        &RegexTree::Complement(Box::new(RegexTree::Repeat(Box::new(a()))))
+    );
+    assert_eq!(
+        parser::extended_regex("(~a)*").unwrap().to_string(),
+        "(~a)*"
+    );
+    assert_eq!(
+        parser::extended_regex("(a|b)&~(ab)").unwrap().to_string(),
+        "(a|b)&~(ab)"
+    );
+
+    // The classic syntax treats & and ~ as ordinary characters, which are escaped when written
+    let classic = parser::regex("a&~b").unwrap();
+    assert!(classic.to_dfa().accepts_graphemes("a&~b"));
+    assert_eq!(classic.to_string(), "a\\&\\~b");
+    assert_eq!(
+        parser::extended_regex(&classic.to_string()).unwrap(),
+        classic
+    );
+    assert!(parser::extended_regex("a&").is_err());
+    assert!(parser::extended_regex("~").is_err());
+
+    // Strings of a's and b's with an even number of a's, but not containing bb
+    let regex = parser::extended_regex("(b*ab*a)*b*&~((a|b)*bb(a|b)*)").unwrap();
+    let dfa = regex.to_dfa();
+    let nfa = regex.to_nfa();
+    for (word, accepted) in [
+        ("", true),
+        ("aba", true),
+        ("abab", true),
+        ("ab", false),
+        ("aaa", false),
+        ("abba", false),
+        ("baab", true),
+    ] {
+        assert_eq!(dfa.accepts_graphemes(word), accepted, "{word}");
+        assert_eq!(nfa.accepts_graphemes(word), accepted, "{word}");
+    }
+}
+
+#[test]
+fn test_regex_to_dfa_empty_alphabet() {
+    for (input, accepting) in [("ε", true), ("∅", false), ("ε|∅*", true)] {
+        let dfa = parser::regex(input).unwrap().to_dfa();
+        assert!(dfa.alphabet().is_empty(), "{input}");
+        assert_eq!(dfa.states().len(), 1, "{input}");
+        assert_eq!(dfa.accepts(&[]), accepting, "{input}");
+        // The table format can't express an empty alphabet
+        assert!(parser::dfa(&dfa.to_table()).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn test_regex_nullable() {
+    let cases = [
+        ("ε", true),
+        ("∅", false),
+        ("a", false),
+        ("a*", true),
+        ("a+", false),
+        ("a*b*", true),
+        ("a*b", false),
+        ("a|b*", true),
+        ("(ab)*|c", true),
+        ("a{0,2}", true),
+        ("a{1,}", false),
+    ];
+    for (input, expected) in cases {
+        let regex = parser::regex(input).unwrap();
+        assert_eq!(regex.nullable(), expected, "{input}");
+        assert_eq!(regex.to_dfa().accepts_graphemes(""), expected, "{input}");
+    }
+
+    let cases = [
+        ("~a", true),
+        ("~a*", false),
+        ("a*&b*", true),
+        ("a*&b", false),
+    ];
+    for (input, expected) in cases {
+        let regex = parser::extended_regex(input).unwrap();
+        assert_eq!(regex.nullable(), expected, "{input}");
+    }
+}
+
+#[test]
+fn test_longest_accepted_prefix() {
+    let dfa = parser::regex("a+").unwrap().to_dfa();
+    assert_eq!(dfa.longest_accepted_prefix(&["a", "a", "b", "a"]), Some(2));
+    assert_eq!(dfa.longest_accepted_prefix(&["a", "a", "a"]), Some(3));
+    assert_eq!(dfa.longest_accepted_prefix(&["a", "c", "a"]), Some(1));
+    assert_eq!(dfa.longest_accepted_prefix(&["b"]), None);
+    assert_eq!(dfa.longest_accepted_prefix(&[]), None);
+
+    let dfa = parser::regex("(ab)*").unwrap().to_dfa();
+    assert_eq!(dfa.longest_accepted_prefix(&[]), Some(0));
+    assert_eq!(dfa.longest_accepted_prefix(&["a"]), Some(0));
+    assert_eq!(
+        dfa.longest_accepted_prefix(&["a", "b", "a", "b", "a"]),
+        Some(4)
+    );
+}
+
+#[test]
+fn test_dfa_trace() {
+    let dfa = parser::regex("(ab)*").unwrap().to_dfa();
+    let trace = dfa.trace(&["a", "b", "a"]).unwrap();
+    assert_eq!(trace.len(), 4);
+    assert_eq!(trace[0], dfa.initial_state_index());
+    assert_eq!(trace[0], trace[2]);
+    assert_eq!(trace[1], trace[3]);
+    assert_eq!(dfa.trace(&[]), Ok(vec![dfa.initial_state_index()]));
+    assert_eq!(
+        dfa.trace(&["a", "b", "c", "d"]),
+        Err(dfa::TraceError { position: 2 })
+    );
+
+    let names = dfa.state_names(&trace);
+    assert_eq!(names, vec!["0", "1", "0", "1"]);
+}
+
+#[test]
+fn test_from_parts() {
+    use crate::dfa::ConstructionError::*;
+    let dfa: Dfa = parser::dfa(
+        "
+           a  b
+    -> s₀  s₁ s₀
+     * s₁  s₁ s₀
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let (alphabet, states, initial_state) = dfa.clone().into_parts();
+    assert_eq!(
+        Dfa::from_parts(alphabet.clone(), states.clone(), initial_state),
+        Ok(dfa.clone())
+    );
+
+    let with_states = |f: &dyn Fn(&mut Vec<DfaState>)| {
+        let mut states = states.clone();
+        f(&mut states);
+        Dfa::from_parts(alphabet.clone(), states, initial_state)
+    };
+    assert_eq!(
+        with_states(&|states| states[1].transitions.truncate(1)),
+        Err(WrongNumberOfTransitions("s₁".into(), 1, 2))
+    );
+    assert_eq!(
+        with_states(&|states| states[0].transitions[1] = 2),
+        Err(TransitionDoesNotExist("s₀".into(), 2))
+    );
+    assert_eq!(
+        with_states(&|states| states[0].initial = false),
+        Err(MissingInitialState)
+    );
+    assert_eq!(
+        with_states(&|states| states[1].initial = true),
+        Err(MultipleInitialStates)
+    );
+    assert_eq!(
+        with_states(&|states| states[1].name = "s₀".into()),
+        Err(DuplicateStateDefinition("s₀".into()))
+    );
+    assert_eq!(
+        Dfa::from_parts(vec!["a".into(), "a".into()], states.clone(), 0),
+        Err(DuplicateAlphabetSymbol("a".into()))
+    );
+    assert_eq!(
+        Dfa::from_parts(alphabet.clone(), vec![], 0),
+        Err(InitialStateDoesNotExist(0))
+    );
+
+    let nfa = dfa.to_nfa();
+    let (alphabet, mut states, initial_state) = nfa.clone().into_parts();
+    assert_eq!(
+        Nfa::from_parts(alphabet.clone(), states.clone(), initial_state),
+        Ok(nfa)
+    );
+    states[1].epsilon_transitions.push(5);
+    assert_eq!(
+        Nfa::from_parts(alphabet, states, initial_state),
+        Err(TransitionDoesNotExist("s₁".into(), 5))
+    );
+}
+
+#[test]
+fn test_map_alphabet() {
+    let mut binary = parser::regex("(01)*").unwrap().to_dfa();
+    let letters = parser::regex("(ab)*").unwrap().to_dfa();
+    assert!(binary.union(&letters).is_none());
+    binary
+        .map_alphabet(|elem| match elem {
+            "0" => "a".to_string(),
+            _ => "b".to_string(),
+        })
+        .unwrap();
+    assert!(binary.equivalent_to(&letters));
+    assert!(binary.union(&letters).is_some());
+
+    let mut nfa = binary.to_nfa();
+    let before = nfa.clone();
+    assert_eq!(
+        nfa.map_alphabet(|elem| elem.to_uppercase().replace('B', "A")),
+        Err(dfa::AlphabetCollision {
+            first: "a".into(),
+            second: "b".into(),
+            mapped: "A".into(),
+        })
+    );
+    assert_eq!(nfa, before);
+}
+
+#[test]
+fn test_dfa_bytes_errors() {
+    use crate::dfa::{ConstructionError, DecodeError};
+
+    let dfa: Dfa = parser::dfa(
+        "
+           a    b
+    -> * s0 s1 s0
+         s1 s0 s1
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let bytes = dfa.to_bytes();
+
+    // Every proper prefix of the encoding is cut off somewhere
+    for len in 0..bytes.len() {
+        assert_eq!(
+            Dfa::from_bytes(&bytes[..len]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+    }
+
+    let mut trailing = bytes.clone();
+    trailing.extend([0, 0]);
+    assert_eq!(
+        Dfa::from_bytes(&trailing),
+        Err(DecodeError::TrailingBytes(2))
+    );
+
+    let mut version = bytes.clone();
+    version[0] = 2;
+    assert_eq!(
+        Dfa::from_bytes(&version),
+        Err(DecodeError::UnsupportedVersion(2))
+    );
+
+    // The alphabet is encoded as [2, 1, 'a', 1, 'b'], followed by the number of states and the initial state
+    let mut initial = bytes.clone();
+    initial[7] = 1;
+    assert_eq!(
+        Dfa::from_bytes(&initial),
+        Err(DecodeError::Invalid(
+            ConstructionError::InitialStateNotMarked(1)
+        ))
+    );
+
+    let mut utf8 = bytes.clone();
+    utf8[3] = 0xff;
+    assert_eq!(Dfa::from_bytes(&utf8), Err(DecodeError::InvalidUtf8(2)));
+
+    // The flags of the first state come after its name [2, 's', '0']
+    let mut flags = bytes.clone();
+    flags[11] = 4;
+    assert_eq!(
+        Dfa::from_bytes(&flags),
+        Err(DecodeError::InvalidFlags(11, 4))
+    );
+
+    let overflow = [
+        1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01,
+    ];
+    assert_eq!(
+        Dfa::from_bytes(&overflow),
+        Err(DecodeError::IntegerOverflow(1))
+    );
+}
+
+#[test]
+fn test_regex_equivalence() {
+    let equivalent = [
+        ("(a|b)*", "(a*b*)*"),
+        ("a(ba)*", "(ab)*a"),
+        ("(a|ε)(a|ε)", "ε|a|aa"),
+        ("a∅", "∅"),
+        ("ε*", "ε"),
+    ];
+    for (r1, r2) in equivalent {
+        let r1 = parser::regex(r1).unwrap();
+        let r2 = parser::regex(r2).unwrap();
+        assert!(r1.equivalent_to(&r2), "{r1} should be equivalent to {r2}");
+        assert!(r2.equivalent_to(&r1), "{r2} should be equivalent to {r1}");
+    }
+
+    let non_equivalent = [
+        ("(a|b)*", "(a|c)*"),
+        ("a*", "a+"),
+        ("ab", "ba"),
+        ("∅", "ε"),
+        ("a|∅", "a|b∅|c"),
+    ];
+    for (r1, r2) in non_equivalent {
+        let r1 = parser::regex(r1).unwrap();
+        let r2 = parser::regex(r2).unwrap();
+        assert!(
+            !r1.equivalent_to(&r2),
+            "{r1} should not be equivalent to {r2}"
+        );
+        assert!(
+            !r2.equivalent_to(&r1),
+            "{r2} should not be equivalent to {r1}"
+        );
+    }
+}
+
+#[test]
+fn test_regex_char_class() {
+    let class = parser::regex("[a-cx]").unwrap();
+    let alternation = parser::regex("a|b|c|x").unwrap();
+    assert_eq!(class, alternation);
+
+    let regex = parser::regex("[0-9]+(\\.[0-9\\-]+)*").unwrap().to_dfa();
+    assert!(regex.accepts_graphemes("19.2.10"));
+    assert!(regex.accepts_graphemes("19.-1"));
+    assert!(!regex.accepts_graphemes("19."));
+
+    assert!(parser::regex("[z-a]").is_err());
+    assert!(parser::regex("[]").is_err());
+    assert!(parser::regex("[ab").is_err());
+    assert!(parser::regex("\\[ab\\]").is_ok());
+}
+
+#[test]
+fn test_regex_bounded_repetition() {
+    let desugared = [
+        ("a{2,3}", "aa(a|ε)"),
+        ("a{2,}", "aaa*"),
+        ("(ab){2}", "(ab)(ab)"),
+        ("a{0,1}", "a|ε"),
+        ("a{0}", "ε"),
+        ("[ab]{1,}", "[ab][ab]*"),
+    ];
+    for (bounded, expected) in desugared {
+        assert_eq!(
+            parser::regex(bounded).unwrap(),
+            parser::regex(expected).unwrap()
+        );
+    }
+
+    assert_eq!(
+        parser::regex("a{3,1}"),
+        Err(parser::RegexParseError::InvalidRepetition(3, 1))
+    );
+    assert_eq!(
+        parser::extended_regex("(a|b){5,2}c")
+            .unwrap_err()
+            .to_string(),
+        "The repetition {5,2} has a greater minimum than maximum"
+    );
+    assert!(matches!(
+        parser::regex("a{,1}"),
+        Err(parser::RegexParseError::Syntax(_))
+    ));
+    assert!(matches!(
+        parser::regex("a{1"),
+        Err(parser::RegexParseError::Syntax(_))
+    ));
+    assert!(matches!(
+        parser::regex("{3,1}"),
+        Err(parser::RegexParseError::Syntax(_))
+    ));
+}
+
+#[test]
+fn test_regex_repetition_too_large() {
+    let max = parser::MAX_REPETITION;
+    assert!(parser::regex(&format!("a{{{max}}}")).is_ok());
+    assert!(parser::regex(&format!("a{{{max},}}")).is_ok());
+    assert_eq!(
+        parser::regex("a{1000000000}"),
+        Err(parser::RegexParseError::RepetitionTooLarge(1000000000))
+    );
+    assert_eq!(
+        parser::regex(&format!("a{{1,{}}}", max + 1)),
+        Err(parser::RegexParseError::RepetitionTooLarge(max + 1))
+    );
+    assert_eq!(
+        parser::extended_regex(&format!("(a|b){{{},2}}c", max + 1)),
+        Err(parser::RegexParseError::RepetitionTooLarge(max + 1))
+    );
+    assert_eq!(
+        parser::regex("a{2000}").unwrap_err().to_string(),
+        "The repetition bound 2000 is greater than the maximum of 1000"
+    );
+}
+
+#[test]
+fn test_dfa_isomorphism_unreachable() {
+    let dfa: Dfa = parser::dfa(
+        "
+           a  b
+    -> * s₀ s₁ s₀
+         s₁ s₁ s₀
+         u  s₀ u
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let other: Dfa = parser::dfa(
+        "
+           a  b
+         q₁ q₁ q₀
+    -> * q₀ q₁ q₀
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+
+    assert!(dfa.is_isomorphic_to(&other));
+    assert_eq!(dfa.isomorphism(&other), None);
+    assert_eq!(other.isomorphism(&dfa), None);
+
+    let mut trimmed = dfa.clone();
+    trimmed.remove_unreachable_states();
+    assert_eq!(trimmed.isomorphism(&other), Some(vec![1, 0]));
+    assert_eq!(other.isomorphism(&trimmed), Some(vec![1, 0]));
+}
@@ -0,0 +1,73 @@
+use crate::diagram::{group_edges, DiagramState};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// The distance between the states, in centimeters
+const STATE_DISTANCE: usize = 2;
+
+/// Generates a `tikzpicture` using the `automata` TikZ library from the given states and transitions (given as
+/// `(from, to, label)`). The state with index `i` gets the node name `q{i}` and is placed at `(2i, 0)`, so the states
+/// are laid out from left to right in order. Self-loops are drawn above their state, and transitions between states
+/// which aren't next to each other, or which go both ways, are bent so that they don't overlap. Parallel transitions are
+/// merged into one edge by [group_edges].
+pub fn picture(states: &[DiagramState], transitions: &[(usize, usize, &str)]) -> String {
+    let edges = group_edges(transitions.iter().copied());
+    let connected = edges
+        .iter()
+        .map(|edge| (edge.from, edge.to))
+        .collect::<HashSet<_>>();
+
+    let mut acc = String::from("\\begin{tikzpicture}[->, >=stealth, auto]\n");
+    for (idx, state) in states.iter().enumerate() {
+        let mut style = String::from("state");
+        if state.initial {
+            style.push_str(", initial");
+        }
+        if state.accepting {
+            style.push_str(", accepting");
+        }
+        let _ = writeln!(
+            acc,
+            "    \\node[{style}] (q{idx}) at ({}, 0) {{{}}};",
+            STATE_DISTANCE * idx,
+            escape(state.name)
+        );
+    }
+    for edge in &edges {
+        let (from, to) = (edge.from, edge.to);
+        let style = if from == to {
+            "[loop above]"
+        } else if from.abs_diff(to) > 1 || connected.contains(&(to, from)) {
+            "[bend left]"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            acc,
+            "    \\path (q{from}) edge{style} node {{{}}} (q{to});",
+            escape(&edge.label())
+        );
+    }
+    acc.push_str("\\end{tikzpicture}\n");
+    acc
+}
+
+/// Escapes the characters which are special in LaTeX, so that the string is typeset as it is. `ε` is written as
+/// `$\varepsilon$`, since it is often missing from text fonts.
+pub fn escape(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut acc, c| {
+            match c {
+                '\\' => acc.push_str("\\textbackslash{}"),
+                '~' => acc.push_str("\\textasciitilde{}"),
+                '^' => acc.push_str("\\textasciicircum{}"),
+                '{' | '}' | '$' | '&' | '#' | '_' | '%' => {
+                    acc.push('\\');
+                    acc.push(c);
+                }
+                'ε' => acc.push_str("$\\varepsilon$"),
+                c => acc.push(c),
+            }
+            acc
+        })
+}
@@ -1,6 +1,17 @@
-use std::collections::HashSet;
+#[cfg(feature = "std")]
+use crate::dfa::AlphabetCollision;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
 use std::rc::Rc;
 
+// The map and set used on the evaluation path, which are hash based with `std` and ordered without it
+#[cfg(not(feature = "std"))]
+pub(crate) use alloc::collections::{BTreeMap as Map, BTreeSet as Set};
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{HashMap as Map, HashSet as Set};
+
+#[cfg(feature = "std")]
 #[inline]
 pub fn alphabet_equal(a: &[Rc<str>], b: &[Rc<str>]) -> bool {
     if a.len() != b.len() {
@@ -11,3 +22,26 @@ pub fn alphabet_equal(a: &[Rc<str>], b: &[Rc<str>]) -> bool {
     let set2 = b.iter().collect::<HashSet<_>>();
     set1 == set2
 }
+
+/// Maps every element of the alphabet with `f`, failing if two distinct elements are mapped to the same string
+#[cfg(feature = "std")]
+pub fn map_alphabet(
+    alphabet: &[Rc<str>],
+    mut f: impl FnMut(&str) -> String,
+) -> Result<Rc<[Rc<str>]>, AlphabetCollision> {
+    let mut seen = HashMap::with_capacity(alphabet.len());
+    alphabet
+        .iter()
+        .map(|elem| {
+            let mapped: Rc<str> = Rc::from(f(elem));
+            match seen.insert(mapped.clone(), elem.clone()) {
+                Some(first) => Err(AlphabetCollision {
+                    first,
+                    second: elem.clone(),
+                    mapped,
+                }),
+                None => Ok(mapped),
+            }
+        })
+        .collect()
+}